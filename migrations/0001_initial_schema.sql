@@ -0,0 +1,159 @@
+-- Table type de formation
+CREATE TABLE course_type (
+    id SERIAL PRIMARY KEY,
+    name VARCHAR(100) NOT NULL
+);
+
+-- Table université
+CREATE TABLE university (
+    id VARCHAR(128) PRIMARY KEY,
+    name VARCHAR(255) NOT NULL,
+    login VARCHAR(100) NOT NULL,
+    password VARCHAR(255) NOT NULL,
+    mail VARCHAR(255) UNIQUE NOT NULL,
+    must_change_password BOOLEAN NOT NULL DEFAULT TRUE,
+    mail_verified BOOLEAN NOT NULL DEFAULT FALSE,
+    status VARCHAR(16) NOT NULL DEFAULT 'active', -- active, suspended ou disabled
+    status_reason TEXT
+);
+
+-- Fournisseur d'identité OpenID Connect de l'université
+CREATE TABLE university_oidc (
+    university_id VARCHAR(128) PRIMARY KEY REFERENCES university(id) ON DELETE CASCADE,
+    issuer VARCHAR(255) NOT NULL,
+    client_id VARCHAR(255) NOT NULL,
+    client_secret VARCHAR(255)
+);
+
+-- Comptes liés à un sujet OpenID Connect (étudiant ou université)
+CREATE TABLE oidc_identity (
+    university_id VARCHAR(128) REFERENCES university_oidc(university_id) ON DELETE CASCADE,
+    subject VARCHAR(255) NOT NULL,
+    user_id VARCHAR(128) NOT NULL,
+    user_type VARCHAR(16) NOT NULL,
+    PRIMARY KEY (university_id, subject)
+);
+
+-- Table promo
+CREATE TABLE class (
+    id VARCHAR(128) PRIMARY KEY,
+    name VARCHAR(100) NOT NULL,
+    course_type INT REFERENCES course_type(id) ON DELETE CASCADE,
+    start_date DATE,
+    end_date DATE,
+    min_length INT, -- Minimum stage length in weeks
+    max_length INT, -- Maximum stage length in weeks
+    university_id VARCHAR(128) REFERENCES university(id) ON DELETE CASCADE
+);
+
+
+-- Table étudiant
+CREATE TABLE student (
+    id VARCHAR(128) PRIMARY KEY,
+    first_name VARCHAR(100) NOT NULL,
+    last_name VARCHAR(100) NOT NULL,
+    login VARCHAR(100) UNIQUE NOT NULL,
+    password VARCHAR(255) NOT NULL,
+    mail VARCHAR(255) UNIQUE NOT NULL,
+    class_id VARCHAR(128) REFERENCES class(id) ON DELETE CASCADE,
+    must_change_password BOOLEAN NOT NULL DEFAULT TRUE,
+    mail_verified BOOLEAN NOT NULL DEFAULT FALSE,
+    status VARCHAR(16) NOT NULL DEFAULT 'active', -- active, suspended ou disabled
+    status_reason TEXT
+);
+
+-- Table entreprise
+CREATE TABLE company (
+    id VARCHAR(128) PRIMARY KEY,
+    name VARCHAR(255) NOT NULL,
+    login VARCHAR(100) UNIQUE NOT NULL,
+    password VARCHAR(255) NOT NULL,
+    mail VARCHAR(255) UNIQUE NOT NULL,
+    must_change_password BOOLEAN NOT NULL DEFAULT TRUE,
+    mail_verified BOOLEAN NOT NULL DEFAULT FALSE,
+    status VARCHAR(16) NOT NULL DEFAULT 'active', -- active, suspended ou disabled
+    status_reason TEXT
+);
+
+-- Clés d'API des entreprises (seul le hash de la clé est stocké)
+CREATE TABLE api_key (
+    id VARCHAR(128) PRIMARY KEY,
+    company_id VARCHAR(128) NOT NULL REFERENCES company(id) ON DELETE CASCADE,
+    name VARCHAR(100) NOT NULL,
+    prefix VARCHAR(16) NOT NULL,
+    key_hash VARCHAR(64) UNIQUE NOT NULL,
+    scopes TEXT[] NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    expires_at TIMESTAMPTZ,
+    last_used_at TIMESTAMPTZ
+);
+
+-- Table administrateur
+CREATE TABLE admin (
+    id VARCHAR(128) PRIMARY KEY,
+    login VARCHAR(100) UNIQUE NOT NULL,
+    password VARCHAR(255) NOT NULL,
+    mail VARCHAR(255) UNIQUE NOT NULL,
+    status VARCHAR(16) NOT NULL DEFAULT 'active',
+    status_reason TEXT
+);
+
+-- Table double authentification (absente = code par mail)
+CREATE TABLE twofa (
+    user_id VARCHAR(128) PRIMARY KEY,
+    method VARCHAR(16) NOT NULL DEFAULT 'mail',
+    totp_secret VARCHAR(64),
+    totp_pending_secret VARCHAR(64)
+);
+
+-- Codes de récupération à usage unique
+CREATE TABLE twofa_recovery_code (
+    user_id VARCHAR(128) REFERENCES twofa(user_id) ON DELETE CASCADE,
+    code_hash VARCHAR(64) NOT NULL,
+    PRIMARY KEY (user_id, code_hash)
+);
+
+-- Table stage
+CREATE TABLE internship (
+    id VARCHAR(128) PRIMARY KEY,
+    course_type INT REFERENCES course_type(id) ON DELETE RESTRICT,
+    company_id VARCHAR(128) REFERENCES company(id) ON DELETE CASCADE,
+    university_id VARCHAR(128) REFERENCES university(id) ON DELETE CASCADE,
+    start_date DATE, -- Total start time
+    end_date DATE, -- Total end time
+    min_internship_length INT, -- Minimum length of the internship
+    max_internship_length INT, -- Maximum length of the internship
+    title VARCHAR(255),
+    description TEXT,
+    place VARCHAR(255),
+
+    CONSTRAINT check_internship_creator CHECK (
+        (company_id IS NOT NULL AND university_id IS NULL)
+     OR (company_id IS NULL AND university_id IS NOT NULL)
+    )
+);
+
+-- Relation université <-> stage
+CREATE TABLE university_internship (
+    university_id VARCHAR(128) REFERENCES university(id) ON DELETE CASCADE,
+    internship_id VARCHAR(128) REFERENCES internship(id) ON DELETE CASCADE,
+    PRIMARY KEY (university_id, internship_id)
+);
+
+-- Journal d'audit (ajout uniquement)
+CREATE TABLE audit_log (
+    id BIGSERIAL PRIMARY KEY,
+    occurred_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    actor_id VARCHAR(128),
+    actor_role VARCHAR(16),
+    action VARCHAR(32) NOT NULL,
+    target_id VARCHAR(255),
+    ip VARCHAR(64),
+    outcome VARCHAR(16) NOT NULL
+);
+CREATE INDEX audit_log_occurred_at_idx ON audit_log (occurred_at);
+CREATE INDEX audit_log_actor_id_idx ON audit_log (actor_id);
+CREATE RULE audit_log_no_update AS ON UPDATE TO audit_log DO INSTEAD NOTHING;
+CREATE RULE audit_log_no_delete AS ON DELETE TO audit_log DO INSTEAD NOTHING;
+
+INSERT INTO course_type (name) VALUES ('info'); -- 1
@@ -0,0 +1,10 @@
+-- Postgres n'indexe pas les clés étrangères, les jointures et les suppressions en cascade
+-- parcouraient toute la table
+CREATE INDEX class_course_type_idx ON class (course_type);
+CREATE INDEX class_university_id_idx ON class (university_id);
+CREATE INDEX student_class_id_idx ON student (class_id);
+CREATE INDEX api_key_company_id_idx ON api_key (company_id);
+CREATE INDEX internship_course_type_idx ON internship (course_type);
+CREATE INDEX internship_company_id_idx ON internship (company_id);
+CREATE INDEX internship_university_id_idx ON internship (university_id);
+CREATE INDEX university_internship_internship_id_idx ON university_internship (internship_id);
@@ -0,0 +1,3 @@
+-- Admin ayant agi à la place de l'acteur via l'usurpation d'identité
+ALTER TABLE audit_log ADD COLUMN impersonator_id VARCHAR(128);
+CREATE INDEX audit_log_impersonator_id_idx ON audit_log (impersonator_id);
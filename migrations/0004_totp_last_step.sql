@@ -0,0 +1,2 @@
+-- Dernier pas de temps TOTP accepté, un code ne sert qu'une fois
+ALTER TABLE twofa ADD COLUMN totp_last_step BIGINT;
@@ -0,0 +1,2 @@
+-- Le login identifie l'université à la connexion, comme pour les autres comptes
+ALTER TABLE university ADD CONSTRAINT university_login_key UNIQUE (login);
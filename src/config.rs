@@ -0,0 +1,548 @@
+//! Typed configuration of the API, read once at launch.
+//!
+//! Values come from `Rocket.toml` (under the selected profile), overridden by the environment and
+//! by a local `.env`. Environment variables are named after the section and the field, e.g.
+//! `DATABASE_POOL_SIZE` sets `pool_size` of the `[default.database]` table.
+
+use std::{fmt, sync::OnceLock};
+
+use lettre::message::Mailbox;
+use redis::IntoConnectionInfo;
+use rocket::{
+	Config,
+	figment::{
+		providers::Env,
+		value::{Uncased, UncasedStr},
+	},
+	http::Status,
+};
+use serde::Deserialize;
+use tracing::warn;
+use tracing_subscriber::EnvFilter;
+use url::Url;
+
+use crate::error_handling::StatusOptionHandling;
+
+static CONFIG: OnceLock<AppConfig> = OnceLock::new();
+
+const TOP_LEVEL_KEYS: [&str; 4] = [
+	"rocket_secret",
+	"api_port",
+	"frontend_url",
+	"trusted_ip_header",
+];
+const SECTIONS: [&str; 11] = [
+	"database",
+	"redis",
+	"smtp",
+	"jwt",
+	"cors",
+	"ttl",
+	"password_policy",
+	"log",
+	"health",
+	"metrics",
+	"oidc",
+];
+
+/// Value which must never end up in the logs.
+#[derive(Clone, Deserialize)]
+#[serde(transparent)]
+pub struct Secret(String);
+
+impl Secret {
+	#[must_use]
+	pub fn expose(&self) -> &str {
+		&self.0
+	}
+}
+
+impl fmt::Debug for Secret {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "Secret(***)")
+	}
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct AppConfig {
+	pub rocket_secret: Secret,
+	pub api_port: u16,
+	/// Base of the links sent by mail and of the OpenID Connect redirect URI
+	pub frontend_url: String,
+	/// Header carrying the client IP, only to be set behind a proxy which overwrites it
+	pub trusted_ip_header: Option<String>,
+	pub database: DatabaseConfig,
+	#[serde(default)]
+	pub redis: RedisConfig,
+	pub smtp: SmtpConfig,
+	#[serde(default)]
+	pub jwt: JwtConfig,
+	#[serde(default)]
+	pub cors: CorsConfig,
+	#[serde(default)]
+	pub ttl: TtlConfig,
+	#[serde(default)]
+	pub password_policy: PasswordPolicy,
+	#[serde(default)]
+	pub log: LogConfig,
+	#[serde(default)]
+	pub health: HealthConfig,
+	#[serde(default)]
+	pub metrics: MetricsConfig,
+	#[serde(default)]
+	pub oidc: OidcConfig,
+}
+
+/// Settings of the postgres pool.
+///
+/// TLS is off unless `tls` is set, `ca_cert` adds a PEM root certificate for servers signed by a
+/// private authority. The `sslmode` of the URL still applies.
+#[derive(Debug, Clone, Deserialize)]
+pub struct DatabaseConfig {
+	pub url: Secret,
+	#[serde(default = "default_pool_size")]
+	pub pool_size: usize,
+	/// Time to wait for a free connection before answering `503`
+	#[serde(default = "default_timeout_seconds")]
+	pub pool_timeout_seconds: u64,
+	#[serde(default = "default_timeout_seconds")]
+	pub connect_timeout_seconds: u64,
+	#[serde(default)]
+	pub tls: bool,
+	pub ca_cert: Option<String>,
+	/// Apply the pending migrations at launch, otherwise refuse to start until they are
+	#[serde(default = "default_migrate_on_startup")]
+	pub migrate_on_startup: bool,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct RedisConfig {
+	pub url: Secret,
+	pub connect_timeout_seconds: u64,
+	pub response_timeout_seconds: u64,
+}
+
+/// Relay used to send the mails, over implicit TLS.
+#[derive(Debug, Clone, Deserialize)]
+pub struct SmtpConfig {
+	pub host: String,
+	/// Port of the relay, 465 if not set
+	pub port: Option<u16>,
+	pub username: Option<String>,
+	pub password: Option<Secret>,
+	/// Sender of the mails, e.g. `Mosifra <noreply@mosifra.fr>`
+	pub from: String,
+}
+
+/// Either a single HS256 `secret`, or the path of a `keyring` (see `models::auth::keyring`).
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct JwtConfig {
+	pub secret: Option<Secret>,
+	pub keyring: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct CorsConfig {
+	/// Origins allowed to call the API, only the frontend when empty
+	#[serde(default)]
+	pub allowed_origins: Vec<String>,
+}
+
+/// Lifetimes, in seconds, of the tokens and of the temporary data kept in redis.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct TtlConfig {
+	pub access_token_seconds: u64,
+	/// Lifetime of a session without "remember me", renewed by each refresh
+	pub session_seconds: u64,
+	pub remember_me_seconds: u64,
+	/// Time left to enter the 2FA code after the password
+	pub login_transaction_seconds: u64,
+	pub password_reset_seconds: u64,
+	pub mail_verification_seconds: u64,
+	pub oidc_state_seconds: u64,
+	pub impersonation_seconds: u64,
+}
+
+/// Rules for the passwords chosen by users, generated ones are made to follow them.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct PasswordPolicy {
+	pub min_length: usize,
+	pub require_lowercase: bool,
+	pub require_uppercase: bool,
+	pub require_digit: bool,
+	pub require_symbol: bool,
+}
+
+/// Output of the logs, see `logging`.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct LogConfig {
+	pub format: LogFormat,
+	/// `tracing` filter, e.g. `info` or `info,Mosifra_API::redis=debug`
+	pub level: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LogFormat {
+	/// One JSON object per line, for the log collectors
+	Json,
+	/// Readable lines, for a terminal
+	Pretty,
+}
+
+/// Probes of `/health/ready`.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct HealthConfig {
+	/// Time given to each dependency to answer before it is reported down
+	pub timeout_seconds: u64,
+}
+
+/// Access to `/metrics`.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct MetricsConfig {
+	/// Bearer token the scraper has to send, `/metrics` is closed when it is not set
+	pub token: Option<Secret>,
+}
+
+/// Identity providers of the universities, see `models::auth::OidcProvider`.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct OidcConfig {
+	/// Accepts `http` and local addresses for the provider URLs, only for a provider mock
+	pub allow_insecure_providers: bool,
+	/// Time the discovery documents and the provider keys are kept, 0 fetches them each time
+	pub cache_seconds: u64,
+}
+
+const fn default_pool_size() -> usize {
+	16
+}
+
+const fn default_timeout_seconds() -> u64 {
+	5
+}
+
+const fn default_migrate_on_startup() -> bool {
+	true
+}
+
+impl Default for RedisConfig {
+	fn default() -> Self {
+		Self {
+			url: Secret("redis://default:@redis/".to_string()),
+			connect_timeout_seconds: default_timeout_seconds(),
+			response_timeout_seconds: default_timeout_seconds(),
+		}
+	}
+}
+
+impl Default for LogConfig {
+	fn default() -> Self {
+		Self {
+			format: LogFormat::Pretty,
+			level: "info".to_string(),
+		}
+	}
+}
+
+impl OidcConfig {
+	const DEFAULT: Self = Self {
+		allow_insecure_providers: false,
+		cache_seconds: 3600,
+	};
+}
+
+impl Default for OidcConfig {
+	fn default() -> Self {
+		Self::DEFAULT
+	}
+}
+
+impl HealthConfig {
+	const DEFAULT: Self = Self { timeout_seconds: 2 };
+}
+
+impl Default for HealthConfig {
+	fn default() -> Self {
+		Self::DEFAULT
+	}
+}
+
+impl TtlConfig {
+	const DEFAULT: Self = Self {
+		access_token_seconds: 15 * 60,
+		session_seconds: 30 * 60,
+		remember_me_seconds: 30 * 24 * 3600,
+		login_transaction_seconds: 15 * 60,
+		password_reset_seconds: 30 * 60,
+		mail_verification_seconds: 48 * 60 * 60,
+		oidc_state_seconds: 10 * 60,
+		impersonation_seconds: 15 * 60,
+	};
+}
+
+impl Default for TtlConfig {
+	fn default() -> Self {
+		Self::DEFAULT
+	}
+}
+
+impl PasswordPolicy {
+	const DEFAULT: Self = Self {
+		min_length: 8,
+		require_lowercase: true,
+		require_uppercase: true,
+		require_digit: true,
+		require_symbol: false,
+	};
+}
+
+impl Default for PasswordPolicy {
+	fn default() -> Self {
+		Self::DEFAULT
+	}
+}
+
+impl AppConfig {
+	/// Reads and validates the configuration, the error lists every invalid setting.
+	pub fn load() -> Result<Self, String> {
+		// A missing .env is fine, the variables can come from the real environment which wins
+		dotenvy::dotenv().ok();
+
+		let config: Self = Config::figment()
+			.merge(Env::raw().filter_map(env_key).global())
+			.extract()
+			.map_err(|e| format!("Invalid configuration: {e}"))?;
+
+		let errors = config.validate();
+		if errors.is_empty() {
+			Ok(config)
+		} else {
+			Err(format!(
+				"Invalid configuration:\n  - {}",
+				errors.join("\n  - ")
+			))
+		}
+	}
+
+	fn validate(&self) -> Vec<String> {
+		let mut errors = Vec::new();
+
+		if self.rocket_secret.expose().is_empty() {
+			errors.push("ROCKET_SECRET can't be empty".to_string());
+		}
+		if let Err(e) = parse_http_url(&self.frontend_url) {
+			errors.push(format!("FRONTEND_URL {e}"));
+		}
+		self.database.validate(&mut errors);
+		self.redis.validate(&mut errors);
+		self.smtp.validate(&mut errors);
+		self.jwt.validate(&mut errors);
+		for origin in &self.cors.allowed_origins {
+			if let Err(e) = parse_http_url(origin) {
+				errors.push(format!("CORS_ALLOWED_ORIGINS {origin} {e}"));
+			}
+		}
+		self.ttl.validate(&mut errors);
+		if self.password_policy.min_length < PasswordPolicy::DEFAULT.min_length {
+			errors.push(format!(
+				"PASSWORD_POLICY_MIN_LENGTH must be at least {}",
+				PasswordPolicy::DEFAULT.min_length
+			));
+		}
+		if let Err(e) = EnvFilter::try_new(&self.log.level) {
+			errors.push(format!("LOG_LEVEL is not a valid filter: {e}"));
+		}
+		if self.health.timeout_seconds == 0 {
+			errors.push("HEALTH_TIMEOUT_SECONDS must be at least 1".to_string());
+		}
+		if self
+			.metrics
+			.token
+			.as_ref()
+			.is_some_and(|token| token.expose().is_empty())
+		{
+			errors.push("METRICS_TOKEN must not be empty when set".to_string());
+		}
+
+		errors
+	}
+
+	/// Exact origins allowed by CORS, the frontend's one when none are configured.
+	#[must_use]
+	pub fn cors_origins(&self) -> Vec<String> {
+		let origins = if self.cors.allowed_origins.is_empty() {
+			std::slice::from_ref(&self.frontend_url)
+		} else {
+			&self.cors.allowed_origins
+		};
+
+		origins
+			.iter()
+			.filter_map(|origin| parse_http_url(origin).ok())
+			.map(|url| url.origin().ascii_serialization())
+			.collect()
+	}
+}
+
+impl DatabaseConfig {
+	fn validate(&self, errors: &mut Vec<String>) {
+		if let Err(e) = self.url.expose().parse::<tokio_postgres::Config>() {
+			errors.push(format!("DATABASE_URL is invalid: {e}"));
+		}
+		if self.pool_size == 0 {
+			errors.push("DATABASE_POOL_SIZE must be at least 1".to_string());
+		}
+		if self.pool_timeout_seconds == 0 || self.connect_timeout_seconds == 0 {
+			errors.push("DATABASE_*_TIMEOUT_SECONDS must be at least 1".to_string());
+		}
+		if self.ca_cert.is_some() && !self.tls {
+			errors.push("DATABASE_CA_CERT is set but DATABASE_TLS is off".to_string());
+		}
+	}
+}
+
+impl RedisConfig {
+	fn validate(&self, errors: &mut Vec<String>) {
+		if let Err(e) = self.url.expose().into_connection_info() {
+			errors.push(format!("REDIS_URL is invalid: {e}"));
+		}
+		if self.connect_timeout_seconds == 0 || self.response_timeout_seconds == 0 {
+			errors.push("REDIS_*_TIMEOUT_SECONDS must be at least 1".to_string());
+		}
+	}
+}
+
+impl SmtpConfig {
+	fn validate(&self, errors: &mut Vec<String>) {
+		if self.host.is_empty() {
+			errors.push("SMTP_HOST can't be empty".to_string());
+		}
+		if let Err(e) = self.from.parse::<Mailbox>() {
+			errors.push(format!("SMTP_FROM is not a valid address: {e}"));
+		}
+		if self.username.is_some() != self.password.is_some() {
+			errors.push("SMTP_USERNAME and SMTP_PASSWORD must be set together".to_string());
+		}
+	}
+}
+
+impl JwtConfig {
+	fn validate(&self, errors: &mut Vec<String>) {
+		match (&self.secret, &self.keyring) {
+			(None, None) => errors.push("JWT_KEYRING or JWT_SECRET must be set".to_string()),
+			(Some(secret), None) if secret.expose().is_empty() => {
+				errors.push("JWT_SECRET can't be empty".to_string());
+			}
+			_ => {}
+		}
+	}
+}
+
+impl TtlConfig {
+	fn validate(&self, errors: &mut Vec<String>) {
+		let ttls = [
+			("TTL_ACCESS_TOKEN_SECONDS", self.access_token_seconds),
+			("TTL_SESSION_SECONDS", self.session_seconds),
+			("TTL_REMEMBER_ME_SECONDS", self.remember_me_seconds),
+			(
+				"TTL_LOGIN_TRANSACTION_SECONDS",
+				self.login_transaction_seconds,
+			),
+			("TTL_PASSWORD_RESET_SECONDS", self.password_reset_seconds),
+			(
+				"TTL_MAIL_VERIFICATION_SECONDS",
+				self.mail_verification_seconds,
+			),
+			("TTL_OIDC_STATE_SECONDS", self.oidc_state_seconds),
+			("TTL_IMPERSONATION_SECONDS", self.impersonation_seconds),
+		];
+		for (name, ttl) in ttls {
+			if ttl == 0 {
+				errors.push(format!("{name} must be at least 1"));
+			}
+		}
+
+		// The access token would stay valid after the end of its session
+		if self.access_token_seconds > self.session_seconds {
+			errors.push(
+				"TTL_ACCESS_TOKEN_SECONDS can't be longer than TTL_SESSION_SECONDS".to_string(),
+			);
+		}
+	}
+}
+
+/// Names the variables after their table, `DATABASE_URL` becomes `database.url`.
+fn env_key(key: &UncasedStr) -> Option<Uncased<'_>> {
+	let key = key.as_str().to_ascii_lowercase();
+	if TOP_LEVEL_KEYS.contains(&key.as_str()) {
+		return Some(key.into());
+	}
+
+	SECTIONS.iter().find_map(|section| {
+		let field = key.strip_prefix(section)?.strip_prefix('_')?;
+		Some(format!("{section}.{field}").into())
+	})
+}
+
+fn parse_http_url(value: &str) -> Result<Url, String> {
+	let url = Url::parse(value).map_err(|e| format!("is not a valid URL: {e}"))?;
+
+	if matches!(url.scheme(), "http" | "https") {
+		Ok(url)
+	} else {
+		Err("must be an http or https URL".to_string())
+	}
+}
+
+/// Installs the configuration read by the whole API, once at launch.
+pub fn install_config(config: AppConfig) {
+	if CONFIG.set(config).is_err() {
+		warn!("Configuration already installed");
+	}
+}
+
+pub fn app_config() -> Result<&'static AppConfig, Status> {
+	CONFIG
+		.get()
+		.internal_server_error("Configuration is not installed")
+}
+
+/// Configured lifetimes, or the defaults before the configuration is installed.
+#[must_use]
+pub fn ttl() -> &'static TtlConfig {
+	static DEFAULT: TtlConfig = TtlConfig::DEFAULT;
+
+	CONFIG.get().map_or(&DEFAULT, |config| &config.ttl)
+}
+
+/// Configured password policy, or the default one before the configuration is installed.
+#[must_use]
+pub fn password_policy() -> &'static PasswordPolicy {
+	static DEFAULT: PasswordPolicy = PasswordPolicy::DEFAULT;
+
+	CONFIG
+		.get()
+		.map_or(&DEFAULT, |config| &config.password_policy)
+}
+
+/// Configured OIDC settings, or the default ones before the configuration is installed.
+#[must_use]
+pub fn oidc_config() -> &'static OidcConfig {
+	static DEFAULT: OidcConfig = OidcConfig::DEFAULT;
+
+	CONFIG.get().map_or(&DEFAULT, |config| &config.oidc)
+}
+
+/// Configured readiness probes, or the default ones before the configuration is installed.
+#[must_use]
+pub fn health_config() -> &'static HealthConfig {
+	static DEFAULT: HealthConfig = HealthConfig::DEFAULT;
+
+	CONFIG.get().map_or(&DEFAULT, |config| &config.health)
+}
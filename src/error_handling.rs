@@ -1,8 +1,17 @@
-use rocket::http::Status;
+use rocket::{
+	Request,
+	http::Status,
+	request::Outcome,
+	response::{self, Responder},
+	serde::json::Json,
+};
+use serde::Serialize;
+use tracing::error;
+
+use crate::models::auth::AccountStatus;
 
 pub trait StatusResultHandling<T, E: std::fmt::Debug> {
 	fn internal_server_error<M: ToString>(self, message: M) -> Result<T, Status>;
-	fn internal_server_error_no_message(self) -> Result<T, Status>;
 }
 
 impl<T, E: std::fmt::Debug> StatusResultHandling<T, E> for Result<T, E> {
@@ -10,17 +19,7 @@ impl<T, E: std::fmt::Debug> StatusResultHandling<T, E> for Result<T, E> {
 		match self {
 			Ok(value) => Ok(value),
 			Err(e) => {
-				eprintln!("{} : {e:?}", message.to_string());
-				Err(Status::InternalServerError)
-			}
-		}
-	}
-
-	fn internal_server_error_no_message(self) -> Result<T, Status> {
-		match self {
-			Ok(value) => Ok(value),
-			Err(e) => {
-				eprintln!("{e:?}");
+				error!("{} : {e:?}", message.to_string());
 				Err(Status::InternalServerError)
 			}
 		}
@@ -36,9 +35,263 @@ impl<T> StatusOptionHandling<T> for Option<T> {
 		match self {
 			Some(value) => Ok(value),
 			None => {
-				eprintln!("{}", message.to_string());
+				error!("{}", message.to_string());
 				Err(Status::InternalServerError)
 			}
 		}
 	}
 }
+
+/// Error returned by the routes, rendered as `{code, message, details}` with its status.
+///
+/// The `code` of each variant is stable, clients should match on it rather than on the message.
+/// A `Status` returned by the models converts into the matching variant with `?`.
+#[derive(Debug, Clone)]
+pub enum ApiError {
+	BadRequest(String),
+	Unauthorized(String),
+	Forbidden(String),
+	NotFound(String),
+	Conflict(String),
+	/// One entry per refused field of the payload
+	Validation(Vec<FieldError>),
+	/// Unknown login or wrong password
+	InvalidCredentials,
+	/// The credentials are right but the mail address was never verified
+	MailNotVerified,
+	/// The request authenticated by the cookies doesn't repeat the CSRF token in its header
+	InvalidCsrfToken,
+	/// The session was opened with a generated password, only the password change and the logout are allowed
+	PasswordChangeRequired,
+	/// The account was suspended or disabled by an admin
+	AccountInactive {
+		status: AccountStatus,
+		reason: Option<String>,
+	},
+	/// Unknown, expired or already used refresh token
+	InvalidRefreshToken,
+	/// The 2FA transaction expired, was used, or belongs to another user type
+	InvalidTwofaTransaction,
+	/// Wrong 2FA code, `retry_after` is set when this failure locked the client IP
+	InvalidTwofaCode {
+		remaining_attempts: u64,
+		retry_after: Option<u64>,
+	},
+	/// Too many failures, `retry_after` is unset when the login must start over
+	Locked {
+		retry_after: Option<u64>,
+	},
+	/// Wrong TOTP code while enrolling or disabling TOTP
+	InvalidTotpCode,
+	/// The OIDC state is unknown or the provider's answer was refused
+	OidcLoginFailed,
+	/// Admins and accounts which are not active can't be impersonated
+	ImpersonationRefused,
+	/// Postgres, Redis or the mail server can't be reached, the client can come back later
+	Unavailable(String),
+	/// The cause was already logged, it is not given to the client
+	Internal,
+	/// Any other status, e.g. `502 Bad Gateway` from an OIDC provider
+	Http(Status),
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct FieldError {
+	pub field: String,
+	pub message: String,
+}
+
+impl FieldError {
+	pub fn new(field: impl ToString, message: impl ToString) -> Self {
+		Self {
+			field: field.to_string(),
+			message: message.to_string(),
+		}
+	}
+}
+
+#[derive(Debug, Serialize)]
+pub struct ErrorBody {
+	pub code: &'static str,
+	pub message: String,
+	pub details: Option<Vec<FieldError>>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub account_status: Option<AccountStatus>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub status_reason: Option<String>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub remaining_attempts: Option<u64>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub retry_after: Option<u64>,
+}
+
+impl ApiError {
+	#[must_use]
+	pub const fn status(&self) -> Status {
+		match self {
+			Self::BadRequest(_) => Status::BadRequest,
+			Self::Unauthorized(_) => Status::Unauthorized,
+			Self::Forbidden(_) => Status::Forbidden,
+			Self::NotFound(_) => Status::NotFound,
+			Self::Conflict(_) => Status::Conflict,
+			Self::Validation(_) => Status::UnprocessableEntity,
+			Self::InvalidCsrfToken
+			| Self::MailNotVerified
+			| Self::PasswordChangeRequired
+			| Self::AccountInactive { .. }
+			| Self::ImpersonationRefused => Status::Forbidden,
+			Self::InvalidCredentials
+			| Self::InvalidRefreshToken
+			| Self::InvalidTwofaTransaction
+			| Self::InvalidTwofaCode { .. }
+			| Self::OidcLoginFailed => Status::Unauthorized,
+			Self::Locked { .. } => Status::TooManyRequests,
+			Self::InvalidTotpCode => Status::BadRequest,
+			Self::Unavailable(_) => Status::ServiceUnavailable,
+			Self::Internal => Status::InternalServerError,
+			Self::Http(status) => *status,
+		}
+	}
+
+	#[must_use]
+	pub const fn code(&self) -> &'static str {
+		match self {
+			Self::BadRequest(_) => "bad_request",
+			Self::Unauthorized(_) => "unauthorized",
+			Self::Forbidden(_) => "forbidden",
+			Self::NotFound(_) => "not_found",
+			Self::Conflict(_) => "conflict",
+			Self::Validation(_) => "validation_failed",
+			Self::InvalidCredentials => "invalid_credentials",
+			Self::MailNotVerified => "mail_not_verified",
+			Self::InvalidCsrfToken => "invalid_csrf_token",
+			Self::PasswordChangeRequired => "password_change_required",
+			Self::AccountInactive {
+				status: AccountStatus::Disabled,
+				..
+			} => "account_disabled",
+			Self::AccountInactive { .. } => "account_suspended",
+			Self::InvalidRefreshToken => "invalid_refresh_token",
+			Self::InvalidTwofaTransaction => "invalid_twofa_transaction",
+			Self::InvalidTwofaCode { .. } => "invalid_twofa_code",
+			Self::Locked { .. } => "locked",
+			Self::InvalidTotpCode => "invalid_totp_code",
+			Self::OidcLoginFailed => "oidc_login_failed",
+			Self::ImpersonationRefused => "impersonation_refused",
+			Self::Unavailable(_) => "service_unavailable",
+			Self::Internal => "internal_error",
+			Self::Http(status) => match status.code {
+				405 => "method_not_allowed",
+				406 => "not_acceptable",
+				408 => "request_timeout",
+				413 => "payload_too_large",
+				415 => "unsupported_media_type",
+				429 => "too_many_requests",
+				502 => "bad_gateway",
+				504 => "gateway_timeout",
+				_ => "http_error",
+			},
+		}
+	}
+
+	#[must_use]
+	pub fn into_body(self) -> ErrorBody {
+		let code = self.code();
+		let mut body = ErrorBody {
+			code,
+			message: String::new(),
+			details: None,
+			account_status: None,
+			status_reason: None,
+			remaining_attempts: None,
+			retry_after: None,
+		};
+
+		body.message = match self {
+			Self::BadRequest(message)
+			| Self::Unauthorized(message)
+			| Self::Forbidden(message)
+			| Self::NotFound(message)
+			| Self::Conflict(message)
+			| Self::Unavailable(message) => message,
+			Self::Validation(fields) => {
+				body.details = Some(fields);
+				"Some fields are invalid".to_string()
+			}
+			Self::InvalidCredentials => "Invalid login or password".to_string(),
+			Self::MailNotVerified => "Mail address not verified".to_string(),
+			Self::InvalidCsrfToken => "Invalid CSRF token".to_string(),
+			Self::PasswordChangeRequired => "Password change required".to_string(),
+			Self::AccountInactive { status, reason } => {
+				body.account_status = Some(status);
+				body.status_reason = reason;
+				format!("Account {status}")
+			}
+			Self::InvalidRefreshToken => "Invalid refresh token".to_string(),
+			Self::InvalidTwofaTransaction => "Invalid or expired 2FA transaction".to_string(),
+			Self::InvalidTwofaCode {
+				remaining_attempts,
+				retry_after,
+			} => {
+				body.remaining_attempts = Some(remaining_attempts);
+				body.retry_after = retry_after;
+				"Invalid 2FA code".to_string()
+			}
+			Self::Locked { retry_after } => {
+				body.retry_after = retry_after;
+				"Too many failed attempts".to_string()
+			}
+			Self::InvalidTotpCode => "Invalid TOTP code".to_string(),
+			Self::OidcLoginFailed => "OIDC login failed".to_string(),
+			Self::ImpersonationRefused => "This account can't be impersonated".to_string(),
+			Self::Internal => "Internal server error".to_string(),
+			Self::Http(status) => status.reason_lossy().to_string(),
+		};
+
+		body
+	}
+}
+
+impl From<Status> for ApiError {
+	fn from(status: Status) -> Self {
+		let message = status.reason_lossy().to_string();
+
+		match status.code {
+			400 => Self::BadRequest(message),
+			401 => Self::Unauthorized(message),
+			403 => Self::Forbidden(message),
+			404 => Self::NotFound(message),
+			409 => Self::Conflict(message),
+			422 => Self::Validation(vec![]),
+			500 => Self::Internal,
+			503 => Self::Unavailable(message),
+			_ => Self::Http(status),
+		}
+	}
+}
+
+/// Error of a failed request guard. Rocket only hands the status to the catchers, so the first
+/// error is kept in the request cache for `default_catcher`.
+#[derive(Debug)]
+struct GuardError(Option<ApiError>);
+
+/// Fails a guard with an error whose code and details reach the client.
+pub fn guard_error<T>(request: &Request<'_>, error: ApiError) -> Outcome<T, String> {
+	let status = error.status();
+	let message = format!("{error:?}");
+	request.local_cache(|| GuardError(Some(error)));
+
+	Outcome::Error((status, message))
+}
+
+/// Error given to `guard_error` during this request, if any.
+pub fn cached_guard_error(request: &Request<'_>) -> Option<ApiError> {
+	request.local_cache(|| GuardError(None)).0.clone()
+}
+
+impl<'r> Responder<'r, 'static> for ApiError {
+	fn respond_to(self, request: &'r Request<'_>) -> response::Result<'static> {
+		let status = self.status();
+		(status, Json(self.into_body())).respond_to(request)
+	}
+}
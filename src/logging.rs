@@ -0,0 +1,370 @@
+//! Logs of the API, written through `tracing` as JSON lines or as readable text (`LOG_FORMAT`).
+//!
+//! Each request gets an id, echoed in the `X-Request-Id` header. The handlers of the `traced`
+//! routes run in a `request` span holding it, so everything logged while serving the request,
+//! including the `postgres`, `redis` and `smtp` spans (at the `debug` level), is attached to it.
+//!
+//! The value of a field named like a secret (password, code, token, key...) is never written.
+
+use std::{
+	fmt,
+	future::Future,
+	io::IsTerminal,
+	time::{Duration, Instant},
+};
+
+use chrono::Utc;
+use rocket::{
+	Data, Request, Response, Route,
+	fairing::{Fairing, Info, Kind},
+	http::Header,
+	route::{Handler, Outcome},
+};
+use serde_json::{Map, Value};
+use tracing::{
+	Event, Instrument, Span, Subscriber, debug,
+	field::{Field, Visit},
+	info, info_span,
+};
+use tracing_subscriber::{
+	EnvFilter,
+	field::{MakeExt, RecordFields},
+	filter::filter_fn,
+	fmt::{
+		FmtContext, FormatEvent, FormatFields, FormattedFields,
+		format::{Writer, debug_fn},
+	},
+	layer::SubscriberExt,
+	registry::LookupSpan,
+	util::SubscriberInitExt,
+};
+use uuid::Uuid;
+
+use crate::config::{LogConfig, LogFormat};
+
+const REQUEST_ID_HEADER: &str = "X-Request-Id";
+const MAX_REQUEST_ID_LENGTH: usize = 64;
+/// Parts of the field names whose values are replaced by `REDACTED`
+const SECRET_FIELDS: [&str; 8] = [
+	"password",
+	"code",
+	"token",
+	"secret",
+	"jwt",
+	"key",
+	"cookie",
+	"authorization",
+];
+const REDACTED: &str = "[redacted]";
+/// Whatever `LOG_LEVEL` says: Rocket logs the whole URI of the requests, whose query string can
+/// hold a token, and `tokio_postgres` the parameters of the queries at the `debug` level
+const FORCED_DIRECTIVES: [&str; 2] = ["rocket::server=off", "tokio_postgres=info"];
+
+/// Installs the global subscriber, the `log` records of Rocket and of the libraries included.
+pub fn init_logging(config: &LogConfig) -> Result<(), String> {
+	let mut filter =
+		EnvFilter::try_new(&config.level).map_err(|e| format!("Invalid LOG_LEVEL: {e}"))?;
+	for directive in FORCED_DIRECTIVES {
+		filter = filter.add_directive(
+			directive
+				.parse()
+				.map_err(|e| format!("Invalid filter {directive}: {e}"))?,
+		);
+	}
+	let subscriber = tracing_subscriber::registry()
+		.with(filter)
+		.with(filter_fn(|metadata| !is_route_report(metadata.target())));
+
+	let installed = match config.format {
+		LogFormat::Json => subscriber
+			.with(
+				tracing_subscriber::fmt::layer()
+					.fmt_fields(JsonFields)
+					.event_format(JsonFormat),
+			)
+			.try_init(),
+		LogFormat::Pretty => subscriber
+			.with(
+				tracing_subscriber::fmt::layer()
+					.fmt_fields(debug_fn(write_field).delimited(" "))
+					.with_ansi(std::io::stdout().is_terminal()),
+			)
+			.try_init(),
+	};
+
+	installed.map_err(|e| format!("Can't install the logger: {e}"))
+}
+
+/// Rocket reports the failed guards of a route under `<module of the route>::_`, with the raw body
+/// when a JSON payload doesn't parse.
+fn is_route_report(target: &str) -> bool {
+	target.starts_with(env!("CARGO_CRATE_NAME")) && target.ends_with("::_")
+}
+
+fn is_secret(field: &str) -> bool {
+	let field = field.to_ascii_lowercase();
+	SECRET_FIELDS.iter().any(|secret| field.contains(secret))
+}
+
+/// Fields of the readable output, the `log.*` ones only describe where a `log` record comes from.
+fn write_field(writer: &mut Writer<'_>, field: &Field, value: &dyn fmt::Debug) -> fmt::Result {
+	match field.name() {
+		"message" => write!(writer, "{value:?}"),
+		name if name.starts_with("log.") => Ok(()),
+		name if is_secret(name) => write!(writer, "{name}={REDACTED}"),
+		name => write!(writer, "{name}={value:?}"),
+	}
+}
+
+/// Runs `call` in `span` and logs how long it took, for the spans around Postgres and Redis.
+pub async fn timed<F: Future>(span: Span, call: F) -> F::Output {
+	async {
+		let start = Instant::now();
+		let output = call.await;
+		debug!(duration_ms = millis(start.elapsed()), "Done");
+		output
+	}
+	.instrument(span)
+	.await
+}
+
+#[must_use]
+pub fn millis(duration: Duration) -> u64 {
+	u64::try_from(duration.as_millis()).unwrap_or(u64::MAX)
+}
+
+/// Id of the request, the one set by a proxy in `X-Request-Id` is kept if it is usable.
+#[derive(Debug, Clone)]
+pub struct RequestId(pub String);
+
+impl RequestId {
+	#[must_use]
+	pub fn of<'r>(request: &'r Request<'_>) -> &'r str {
+		&request
+			.local_cache(|| {
+				let id = request
+					.headers()
+					.get_one(REQUEST_ID_HEADER)
+					.filter(|id| is_valid_request_id(id))
+					.map_or_else(|| Uuid::new_v4().to_string(), ToString::to_string);
+
+				Self(id)
+			})
+			.0
+	}
+}
+
+/// The id is written in the logs, it can't be used to forge lines.
+fn is_valid_request_id(id: &str) -> bool {
+	!id.is_empty()
+		&& id.len() <= MAX_REQUEST_ID_LENGTH
+		&& id
+			.chars()
+			.all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+/// When the request was received, cached by `RequestLogger`.
+pub struct RequestStart(Instant);
+
+impl RequestStart {
+	#[must_use]
+	pub fn elapsed(request: &Request<'_>) -> Duration {
+		request.local_cache(|| Self(Instant::now())).0.elapsed()
+	}
+}
+
+/// Gives its id to each request, returns it in `X-Request-Id` and logs the outcome.
+pub struct RequestLogger;
+
+#[async_trait]
+impl Fairing for RequestLogger {
+	fn info(&self) -> Info {
+		Info {
+			name: "Request logger",
+			kind: Kind::Request | Kind::Response,
+		}
+	}
+
+	async fn on_request(&self, request: &mut Request<'_>, _data: &mut Data<'_>) {
+		request.local_cache(|| RequestStart(Instant::now()));
+	}
+
+	async fn on_response<'r>(&self, request: &'r Request<'_>, response: &mut Response<'r>) {
+		let request_id = RequestId::of(request);
+
+		response.set_header(Header::new(REQUEST_ID_HEADER, request_id.to_string()));
+
+		// Only the path, the query string can hold a token
+		info!(
+			request_id,
+			method = %request.method(),
+			path = request.uri().path().as_str(),
+			status = response.status().code,
+			duration_ms = millis(RequestStart::elapsed(request)),
+			"Request completed"
+		);
+	}
+}
+
+/// Handler running the one of the route in the `request` span.
+#[derive(Clone)]
+struct Traced(Box<dyn Handler>);
+
+#[async_trait]
+impl Handler for Traced {
+	async fn handle<'r>(&self, request: &'r Request<'_>, data: Data<'r>) -> Outcome<'r> {
+		let span = info_span!(
+			"request",
+			request_id = RequestId::of(request),
+			method = %request.method(),
+			path = request.uri().path().as_str(),
+		);
+
+		self.0.handle(request, data).instrument(span).await
+	}
+}
+
+/// Routes whose handler, guards included, run in the `request` span.
+#[must_use]
+pub fn traced(routes: Vec<Route>) -> Vec<Route> {
+	routes
+		.into_iter()
+		.map(|mut route| {
+			route.handler = Box::new(Traced(route.handler));
+			route
+		})
+		.collect()
+}
+
+/// Fields of an event or a span as a JSON object, secrets redacted.
+#[derive(Default)]
+struct JsonVisitor {
+	fields: Map<String, Value>,
+	/// Target of a `log` record, its event is emitted by the `log` target
+	log_target: Option<String>,
+}
+
+impl JsonVisitor {
+	fn insert(&mut self, field: &Field, value: Value) {
+		match field.name() {
+			"log.target" => self.log_target = value.as_str().map(ToString::to_string),
+			name if name.starts_with("log.") => {}
+			name if is_secret(name) => {
+				self.fields.insert(name.to_string(), REDACTED.into());
+			}
+			name => {
+				self.fields.insert(name.to_string(), value);
+			}
+		}
+	}
+}
+
+impl Visit for JsonVisitor {
+	fn record_f64(&mut self, field: &Field, value: f64) {
+		self.insert(field, value.into());
+	}
+
+	fn record_i64(&mut self, field: &Field, value: i64) {
+		self.insert(field, value.into());
+	}
+
+	fn record_u64(&mut self, field: &Field, value: u64) {
+		self.insert(field, value.into());
+	}
+
+	fn record_bool(&mut self, field: &Field, value: bool) {
+		self.insert(field, value.into());
+	}
+
+	fn record_str(&mut self, field: &Field, value: &str) {
+		self.insert(field, value.into());
+	}
+
+	fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+		self.insert(field, format!("{value:?}").into());
+	}
+}
+
+/// Stores the fields of the spans as JSON, read back by `JsonFormat`.
+struct JsonFields;
+
+impl<'writer> FormatFields<'writer> for JsonFields {
+	fn format_fields<R: RecordFields>(
+		&self,
+		mut writer: Writer<'writer>,
+		fields: R,
+	) -> fmt::Result {
+		let mut visitor = JsonVisitor::default();
+		fields.record(&mut visitor);
+
+		write!(writer, "{}", Value::Object(visitor.fields))
+	}
+
+	fn add_fields(
+		&self,
+		current: &'writer mut FormattedFields<Self>,
+		fields: &tracing::span::Record<'_>,
+	) -> fmt::Result {
+		let mut visitor = JsonVisitor {
+			fields: serde_json::from_str(&current.fields).unwrap_or_default(),
+			log_target: None,
+		};
+		fields.record(&mut visitor);
+		current.fields = Value::Object(visitor.fields).to_string();
+
+		Ok(())
+	}
+}
+
+/// One object per event, with the fields of its spans (e.g. `request_id`) and their names.
+struct JsonFormat;
+
+impl<S, N> FormatEvent<S, N> for JsonFormat
+where
+	S: Subscriber + for<'lookup> LookupSpan<'lookup>,
+	N: for<'writer> FormatFields<'writer> + 'static,
+{
+	fn format_event(
+		&self,
+		ctx: &FmtContext<'_, S, N>,
+		mut writer: Writer<'_>,
+		event: &Event<'_>,
+	) -> fmt::Result {
+		let mut visitor = JsonVisitor::default();
+		event.record(&mut visitor);
+
+		let metadata = event.metadata();
+		let mut line = Map::new();
+		line.insert("timestamp".to_string(), Utc::now().to_rfc3339().into());
+		line.insert("level".to_string(), metadata.level().as_str().into());
+		line.insert(
+			"target".to_string(),
+			visitor
+				.log_target
+				.unwrap_or_else(|| metadata.target().to_string())
+				.into(),
+		);
+
+		let mut spans = vec![];
+		for span in ctx
+			.event_scope()
+			.into_iter()
+			.flat_map(|scope| scope.from_root())
+		{
+			spans.push(Value::from(span.name()));
+
+			if let Some(fields) = span.extensions().get::<FormattedFields<N>>()
+				&& let Ok(Value::Object(fields)) = serde_json::from_str(&fields.fields)
+			{
+				line.extend(fields);
+			}
+		}
+		if !spans.is_empty() {
+			line.insert("spans".to_string(), spans.into());
+		}
+
+		line.extend(visitor.fields);
+
+		writeln!(writer, "{}", Value::Object(line))
+	}
+}
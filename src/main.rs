@@ -1,109 +1,239 @@
-use std::process::exit;
-
-use rocket::{
-	Config,
-	figment::{Figment, providers::Env},
-	http::Method,
-};
-use rocket_cors::{AllowedOrigins, CorsOptions};
-use routes::{
-	auth::{check_session, login_route, logout_route, twofa_route},
-	courses::{
-		delete::class::delete_class,
-		get::{
-			class::students::get_class_students, classes::get_classes, internships::get_internships,
-		},
-	},
-	create::{
-		class::create_class, company::create_company, internship::create_internship,
-		students::create_students, university::create_university,
-	},
-	user::{
-		delete::{company::delete_company, university::delete_university},
-		get::{
-			companies::get_companies,
-			student::{course_type::get_student_course_type, info::get_student_info},
-			universities::get_universities,
-			university::course_types::get_university_course_types,
-			user_type::get_user_type,
-		},
-	},
-};
-use serde::Deserialize;
-
-mod error_handling;
-pub mod models;
-pub mod postgres;
-pub mod redis;
-pub mod routes;
-pub mod utils;
-
-#[macro_use]
-extern crate rocket;
-
-#[derive(Debug, PartialEq, Deserialize)]
-struct Environment {
-	rocket_secret: String,
-	api_port: usize,
-}
-
-#[launch]
-fn rocket() -> _ {
-	let env: Environment = Figment::from(Env::raw().only(&["rocket_secret", "api_port"]))
-		.extract()
-		.unwrap_or_else(|e| {
-			eprintln!("Error while trying to get the env: {e}");
-			exit(1);
-		});
-
-	let rocket = rocket::custom(Config::from(
-		Config::figment()
-			.merge(("secret_key", env.rocket_secret))
-			.merge(("port", env.api_port)),
-	));
-
-	let cors = CorsOptions::default()
-		.allowed_origins(AllowedOrigins::all())
-		.allowed_methods(
-			vec![
-				Method::Get,
-				Method::Post,
-				Method::Patch,
-				Method::Options,
-				Method::Delete,
-			]
-			.into_iter()
-			.map(From::from)
-			.collect(),
-		)
-		.allow_credentials(true);
-
-	rocket
-		.mount(
-			"/",
-			routes![
-				login_route,
-				twofa_route,
-				check_session,
-				create_company,
-				create_students,
-				create_university,
-				get_user_type,
-				create_class,
-				get_student_info,
-				get_classes,
-				get_class_students,
-				delete_class,
-				logout_route,
-				create_internship,
-				get_internships,
-				get_university_course_types,
-				get_student_course_type,
-				get_companies,
-				get_universities,
-				delete_company,
-				delete_university,
-			],
-		)
-		.attach(cors.to_cors().unwrap())
-}
+use std::process::exit;
+
+use config::{AppConfig, LogFormat, install_config};
+use deadpool_postgres::Pool;
+use logging::{RequestLogger, init_logging, traced};
+use metrics::RequestMetrics;
+use migrations::{check_migrations, run_migrations};
+use models::auth::JwtKeyring;
+use postgres::create_pool;
+use redis::{create_connection_manager, install_connection_manager};
+use repositories::Repositories;
+use rocket::{Build, Config, Rocket, http::Method};
+use rocket_cors::{AllowedOrigins, CorsOptions};
+use routes::{
+	audit::events::get_audit_events,
+	auth::{
+		check_session, confirm_totp, disable_totp, enroll_totp, forgot_password, get_oidc_providers,
+		get_sessions, impersonate_route, login_route, logout_route, oidc_authorize, oidc_callback,
+		refresh_route, resend_mail_verification, reset_password, revoke_all_sessions,
+		revoke_session, twofa_route, verify_mail,
+	},
+	catchers::default_catcher,
+	courses::{
+		delete::{class::delete_class, internship::delete_internship},
+		get::{
+			class::students::get_class_students,
+			classes::get_classes,
+			internships::{get_internships, get_internships_with_api_key},
+		},
+	},
+	create::{
+		api_key::create_api_key, class::create_class, company::create_company,
+		internship::create_internship, oidc_provider::create_oidc_provider,
+		students::create_students, university::create_university,
+	},
+	health::{database::get_database_health, live::get_liveness, ready::get_readiness},
+	metrics::get_metrics,
+	user::{
+		delete::{
+			api_key::delete_api_key, company::delete_company, oidc_provider::delete_oidc_provider,
+			university::delete_university,
+		},
+		get::{
+			api_keys::get_api_keys,
+			companies::get_companies,
+			student::{course_type::get_student_course_type, info::get_student_info},
+			universities::get_universities,
+			university::course_types::get_university_course_types,
+			user_type::get_user_type,
+		},
+		patch::{password::change_password, status::change_account_status},
+	},
+};
+use tracing::error;
+use utils::mail::Mailer;
+
+pub mod config;
+mod error_handling;
+pub mod logging;
+pub mod metrics;
+mod migrations;
+pub mod models;
+pub mod postgres;
+pub mod redis;
+pub mod repositories;
+pub mod routes;
+#[cfg(test)]
+mod tests;
+pub mod utils;
+
+#[macro_use]
+extern crate rocket;
+
+#[launch]
+async fn rocket() -> _ {
+	// The logger is configured by the configuration, its errors can only go to stderr
+	let config = AppConfig::load().unwrap_or_else(|e| {
+		eprintln!("{e}");
+		exit(1);
+	});
+	init_logging(&config.log).unwrap_or_else(|e| {
+		eprintln!("{e}");
+		exit(1);
+	});
+
+	let keyring = JwtKeyring::load(&config.jwt).unwrap_or_else(|e| {
+		error!("Error while loading the JWT keyring: {e}");
+		exit(1);
+	});
+
+	let pool = create_pool(&config.database).unwrap_or_else(|e| {
+		error!("Error while setting up the database: {e}");
+		exit(1);
+	});
+
+	// `Mosifra-API migrate` only brings the schema up to date
+	let migrate_only = match std::env::args().nth(1).as_deref() {
+		None => false,
+		Some("migrate") => true,
+		Some(command) => {
+			error!("Unknown subcommand {command}, the only one is migrate");
+			exit(2);
+		}
+	};
+	let migrated = if migrate_only || config.database.migrate_on_startup {
+		run_migrations(&pool).await
+	} else {
+		check_migrations(&pool).await
+	};
+	if let Err(e) = migrated {
+		error!("Error while migrating the database: {e}");
+		exit(1);
+	}
+	if migrate_only {
+		exit(0);
+	}
+
+	let mailer = Mailer::smtp(&config.smtp).unwrap_or_else(|e| {
+		error!("Error while setting up the mailer: {e}");
+		exit(1);
+	});
+
+	let redis = create_connection_manager(&config.redis)
+		.await
+		.unwrap_or_else(|e| {
+			error!("Error while setting up redis: {e}");
+			exit(1);
+		});
+	install_connection_manager(redis);
+
+	let repositories = Repositories::postgres(pool.clone());
+	let rocket = build_rocket(&config, keyring, mailer, pool, repositories);
+	install_config(config);
+	rocket
+}
+
+/// The API with its routes, fairings and managed state, also mounted by the tests on in-memory
+/// repositories.
+pub fn build_rocket(
+	config: &AppConfig,
+	keyring: JwtKeyring,
+	mailer: Mailer,
+	pool: Pool,
+	repositories: Repositories,
+) -> Rocket<Build> {
+	let figment = Config::figment()
+		.merge(("secret_key", config.rocket_secret.expose()))
+		.merge(("port", config.api_port))
+		// Rocket's own logs go through the logger, without colors in JSON
+		.merge(("cli_colors", config.log.format == LogFormat::Pretty));
+	// Rocket trusts `X-Real-IP` by default, any client could pick the IP of the throttles and of
+	// the audit log
+	let figment = match &config.trusted_ip_header {
+		Some(header) => figment.merge(("ip_header", header)),
+		None => figment.merge(("ip_header", false)),
+	};
+	let rocket = rocket::custom(Config::from(figment));
+
+	let cors = CorsOptions::default()
+		.allowed_origins(AllowedOrigins::some_exact(&config.cors_origins()))
+		.allowed_methods(
+			vec![
+				Method::Get,
+				Method::Post,
+				Method::Patch,
+				Method::Options,
+				Method::Delete,
+			]
+			.into_iter()
+			.map(From::from)
+			.collect(),
+		)
+		.allow_credentials(true);
+
+	rocket
+		.mount(
+			"/",
+			traced(routes![
+				login_route,
+				twofa_route,
+				get_oidc_providers,
+				oidc_authorize,
+				oidc_callback,
+				impersonate_route,
+				create_oidc_provider,
+				delete_oidc_provider,
+				refresh_route,
+				enroll_totp,
+				confirm_totp,
+				disable_totp,
+				forgot_password,
+				reset_password,
+				verify_mail,
+				resend_mail_verification,
+				check_session,
+				get_sessions,
+				revoke_session,
+				revoke_all_sessions,
+				create_company,
+				create_students,
+				create_university,
+				get_user_type,
+				create_class,
+				get_student_info,
+				get_classes,
+				get_class_students,
+				delete_class,
+				logout_route,
+				create_internship,
+				get_internships,
+				get_internships_with_api_key,
+				delete_internship,
+				create_api_key,
+				get_api_keys,
+				delete_api_key,
+				get_university_course_types,
+				get_student_course_type,
+				get_companies,
+				get_universities,
+				delete_company,
+				delete_university,
+				change_password,
+				change_account_status,
+				get_audit_events,
+				get_database_health,
+				get_liveness,
+				get_readiness,
+				get_metrics,
+			]),
+		)
+		.register("/", catchers![default_catcher])
+		.manage(keyring)
+		.manage(mailer)
+		.manage(pool)
+		.manage(repositories)
+		.attach(RequestLogger)
+		.attach(RequestMetrics)
+		.attach(cors.to_cors().unwrap())
+}
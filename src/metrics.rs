@@ -0,0 +1,172 @@
+//! Metrics of the API in the Prometheus text format, served by `GET /metrics`.
+//!
+//! `RequestMetrics` counts the requests and measures their duration by route, under the path of
+//! the route rather than the one requested so ids don't multiply the series. The domain counters
+//! are incremented by the routes where the events happen.
+
+use std::{sync::OnceLock, time::Duration};
+
+use prometheus::{
+	Encoder, Histogram, HistogramOpts, HistogramVec, IntCounter, IntCounterVec, Opts, Registry,
+	TextEncoder, exponential_buckets,
+};
+use rocket::{
+	Request, Response,
+	fairing::{Fairing, Info, Kind},
+};
+
+use crate::{logging::RequestStart, models::auth::UserType};
+
+static METRICS: OnceLock<Metrics> = OnceLock::new();
+
+const NAMESPACE: &str = "mosifra";
+/// Route of the requests no route matched
+const UNMATCHED_ROUTE: &str = "unmatched";
+
+struct Metrics {
+	registry: Registry,
+	requests: IntCounterVec,
+	request_duration: HistogramVec,
+	query_duration: Histogram,
+	logins: IntCounterVec,
+	twofa: IntCounterVec,
+	students_imported: IntCounter,
+	internships_created: IntCounter,
+	mails: IntCounterVec,
+}
+
+impl Metrics {
+	fn new() -> Result<Self, prometheus::Error> {
+		let registry = Registry::new_custom(Some(NAMESPACE.to_string()), None)?;
+
+		let requests = IntCounterVec::new(
+			Opts::new("http_requests_total", "Requests answered"),
+			&["method", "route", "status"],
+		)?;
+		let request_duration = HistogramVec::new(
+			HistogramOpts::new(
+				"http_request_duration_seconds",
+				"Time spent serving requests",
+			),
+			&["method", "route"],
+		)?;
+		let query_duration = Histogram::with_opts(
+			HistogramOpts::new(
+				"postgres_query_duration_seconds",
+				"Time spent running Postgres statements",
+			)
+			.buckets(exponential_buckets(0.001, 2.0, 12)?),
+		)?;
+		let logins = IntCounterVec::new(
+			Opts::new("logins_total", "Password checks of the login step"),
+			&["user_type", "outcome"],
+		)?;
+		let twofa =
+			IntCounterVec::new(Opts::new("twofa_total", "2FA codes checked"), &["outcome"])?;
+		let students_imported = IntCounter::new(
+			"students_imported_total",
+			"Students created from the CSV imports",
+		)?;
+		let internships_created =
+			IntCounter::new("internships_created_total", "Internships created")?;
+		let mails = IntCounterVec::new(
+			Opts::new("mails_total", "Mails handed to the relay"),
+			&["outcome"],
+		)?;
+
+		registry.register(Box::new(requests.clone()))?;
+		registry.register(Box::new(request_duration.clone()))?;
+		registry.register(Box::new(query_duration.clone()))?;
+		registry.register(Box::new(logins.clone()))?;
+		registry.register(Box::new(twofa.clone()))?;
+		registry.register(Box::new(students_imported.clone()))?;
+		registry.register(Box::new(internships_created.clone()))?;
+		registry.register(Box::new(mails.clone()))?;
+
+		Ok(Self {
+			registry,
+			requests,
+			request_duration,
+			query_duration,
+			logins,
+			twofa,
+			students_imported,
+			internships_created,
+			mails,
+		})
+	}
+}
+
+fn metrics() -> &'static Metrics {
+	METRICS.get_or_init(|| Metrics::new().expect("Metrics are defined once with valid names"))
+}
+
+const fn outcome(success: bool) -> &'static str {
+	if success { "success" } else { "failure" }
+}
+
+pub fn record_login(user_type: UserType, success: bool) {
+	metrics()
+		.logins
+		.with_label_values(&[user_type.table_name(), outcome(success)])
+		.inc();
+}
+
+pub fn record_twofa(success: bool) {
+	metrics().twofa.with_label_values(&[outcome(success)]).inc();
+}
+
+pub fn record_student_imported() {
+	metrics().students_imported.inc();
+}
+
+pub fn record_internship_created() {
+	metrics().internships_created.inc();
+}
+
+pub fn record_mail(sent: bool) {
+	let outcome = if sent { "sent" } else { "failed" };
+	metrics().mails.with_label_values(&[outcome]).inc();
+}
+
+pub fn record_query(duration: Duration) {
+	metrics().query_duration.observe(duration.as_secs_f64());
+}
+
+/// Every metric in the text format.
+pub fn encode_metrics() -> Result<String, prometheus::Error> {
+	let mut buffer = vec![];
+	TextEncoder::new().encode(&metrics().registry.gather(), &mut buffer)?;
+
+	String::from_utf8(buffer).map_err(|e| prometheus::Error::Msg(e.to_string()))
+}
+
+/// Counts the requests and measures their duration, attached after `RequestLogger`.
+pub struct RequestMetrics;
+
+#[async_trait]
+impl Fairing for RequestMetrics {
+	fn info(&self) -> Info {
+		Info {
+			name: "Request metrics",
+			kind: Kind::Response,
+		}
+	}
+
+	async fn on_response<'r>(&self, request: &'r Request<'_>, response: &mut Response<'r>) {
+		let method = request.method().as_str();
+		let route = request
+			.route()
+			.map_or(UNMATCHED_ROUTE, |route| route.uri.path());
+		let metrics = metrics();
+
+		metrics
+			.requests
+			.with_label_values(&[method, route, &response.status().code.to_string()])
+			.inc();
+		metrics
+			.request_duration
+			.with_label_values(&[method, route])
+			.observe(RequestStart::elapsed(request).as_secs_f64());
+	}
+}
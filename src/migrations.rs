@@ -0,0 +1,217 @@
+//! SQL migrations embedded in the binary, applied in order and recorded in `schema_migrations`.
+//!
+//! A migration must never be edited once released: its checksum is compared at each launch, add
+//! a new file to `migrations/` and to `MIGRATIONS` instead.
+
+use deadpool_postgres::{Object, Pool, PoolError};
+use tracing::info;
+
+use crate::utils::crypto::hash_token;
+
+struct Migration {
+	version: i64,
+	name: &'static str,
+	sql: &'static str,
+}
+
+const MIGRATIONS: &[Migration] = &[
+	Migration {
+		version: 1,
+		name: "initial_schema",
+		sql: include_str!("../migrations/0001_initial_schema.sql"),
+	},
+	Migration {
+		version: 2,
+		name: "foreign_key_indexes",
+		sql: include_str!("../migrations/0002_foreign_key_indexes.sql"),
+	},
+	Migration {
+		version: 3,
+		name: "audit_impersonator",
+		sql: include_str!("../migrations/0003_audit_impersonator.sql"),
+	},
+	Migration {
+		version: 4,
+		name: "totp_last_step",
+		sql: include_str!("../migrations/0004_totp_last_step.sql"),
+	},
+	Migration {
+		version: 5,
+		name: "university_login_unique",
+		sql: include_str!("../migrations/0005_university_login_unique.sql"),
+	},
+];
+
+// Held while migrating, so that two instances launched together don't race
+const MIGRATION_LOCK_ID: i64 = 0x006d_6f73_6966_7261;
+
+struct AppliedMigration {
+	version: i64,
+	checksum: String,
+}
+
+impl Migration {
+	fn checksum(&self) -> String {
+		hash_token(self.sql)
+	}
+}
+
+/// Applies the pending migrations, each in its own transaction.
+///
+/// Fails if the database was migrated by a newer binary or if a released migration was edited.
+pub async fn run_migrations(pool: &Pool) -> Result<(), String> {
+	let mut client = connect(pool).await?;
+
+	client
+		.execute("SELECT pg_advisory_lock($1);", &[&MIGRATION_LOCK_ID])
+		.await
+		.map_err(|e| format!("Can't lock the migrations: {}", describe(&e)))?;
+	let result = match create_migrations_table(&client).await {
+		Ok(()) => apply_pending(&mut client).await,
+		Err(e) => Err(e),
+	};
+	client
+		.execute("SELECT pg_advisory_unlock($1);", &[&MIGRATION_LOCK_ID])
+		.await
+		.map_err(|e| format!("Can't unlock the migrations: {}", describe(&e)))?;
+
+	result
+}
+
+/// Checks that the schema is exactly the one expected by this binary, without changing it.
+pub async fn check_migrations(pool: &Pool) -> Result<(), String> {
+	let client = connect(pool).await?;
+	let pending = pending_migrations(&client).await?;
+
+	match pending.first() {
+		None => Ok(()),
+		Some(migration) => Err(format!(
+			"Migration {:04}_{} is not applied, run the migrate subcommand",
+			migration.version, migration.name
+		)),
+	}
+}
+
+async fn connect(pool: &Pool) -> Result<Object, String> {
+	pool.get().await.map_err(|e| match e {
+		PoolError::Backend(e) => format!("Can't connect to the database: {}", describe(&e)),
+		e => format!("Can't connect to the database: {e}"),
+	})
+}
+
+/// Created under the lock, two instances creating it together would both try to insert its type.
+async fn create_migrations_table(client: &Object) -> Result<(), String> {
+	client
+		.batch_execute(
+			"CREATE TABLE IF NOT EXISTS schema_migrations (
+				version BIGINT PRIMARY KEY,
+				name VARCHAR(255) NOT NULL,
+				checksum VARCHAR(64) NOT NULL,
+				applied_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+			);",
+		)
+		.await
+		.map_err(|e| format!("Can't create schema_migrations: {}", describe(&e)))
+}
+
+async fn apply_pending(client: &mut Object) -> Result<(), String> {
+	for migration in pending_migrations(client).await? {
+		let transaction = client
+			.transaction()
+			.await
+			.map_err(|e| format!("Can't start a transaction: {}", describe(&e)))?;
+
+		transaction
+			.batch_execute(migration.sql)
+			.await
+			.map_err(|e| {
+				format!(
+					"Migration {:04}_{} failed: {}",
+					migration.version,
+					migration.name,
+					describe(&e)
+				)
+			})?;
+		transaction
+			.execute(
+				"INSERT INTO schema_migrations (version, name, checksum) VALUES ($1, $2, $3);",
+				&[&migration.version, &migration.name, &migration.checksum()],
+			)
+			.await
+			.map_err(|e| {
+				format!(
+					"Can't record migration {}: {}",
+					migration.version,
+					describe(&e)
+				)
+			})?;
+		transaction.commit().await.map_err(|e| {
+			format!(
+				"Can't commit migration {}: {}",
+				migration.version,
+				describe(&e)
+			)
+		})?;
+
+		info!(
+			"Applied migration {:04}_{}",
+			migration.version, migration.name
+		);
+	}
+
+	Ok(())
+}
+
+/// Migrations of this binary which are not applied yet, after checking the applied ones.
+async fn pending_migrations(client: &Object) -> Result<Vec<&'static Migration>, String> {
+	let migrated: bool = client
+		.query_one("SELECT to_regclass('schema_migrations') IS NOT NULL;", &[])
+		.await
+		.map_err(|e| format!("Can't look for schema_migrations: {}", describe(&e)))?
+		.get(0);
+	if !migrated {
+		return Ok(MIGRATIONS.iter().collect());
+	}
+
+	let applied: Vec<AppliedMigration> = client
+		.query(
+			"SELECT version, checksum FROM schema_migrations ORDER BY version;",
+			&[],
+		)
+		.await
+		.map_err(|e| format!("Can't read schema_migrations: {}", describe(&e)))?
+		.iter()
+		.map(|row| AppliedMigration {
+			version: row.get(0),
+			checksum: row.get(1),
+		})
+		.collect();
+
+	for applied in &applied {
+		let Some(migration) = MIGRATIONS.iter().find(|m| m.version == applied.version) else {
+			return Err(format!(
+				"The database schema is at version {}, ahead of this binary which stops at {}",
+				applied.version,
+				MIGRATIONS.last().map_or(0, |m| m.version)
+			));
+		};
+
+		if migration.checksum() != applied.checksum {
+			return Err(format!(
+				"Migration {:04}_{} was modified after being applied",
+				migration.version, migration.name
+			));
+		}
+	}
+
+	Ok(MIGRATIONS
+		.iter()
+		.filter(|m| !applied.iter().any(|applied| applied.version == m.version))
+		.collect())
+}
+
+/// The server's message, `tokio_postgres` only displays "db error" for them.
+fn describe(e: &tokio_postgres::Error) -> String {
+	e.as_db_error()
+		.map_or_else(|| e.to_string(), ToString::to_string)
+}
@@ -0,0 +1,201 @@
+use std::{fmt::Display, net::IpAddr, str::FromStr};
+
+use chrono::{DateTime, Utc};
+use rocket::http::Status;
+use serde::{Deserialize, Serialize};
+use crate::{models::auth::UserType, repositories::AuditRepository};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AuditAction {
+	Login,
+	Twofa,
+	OidcLogin,
+	Logout,
+	SessionRevoke,
+	PasswordChange,
+	PasswordResetRequest,
+	PasswordReset,
+	MailVerificationRequest,
+	MailVerification,
+	Impersonate,
+	StatusChange,
+	Create,
+	Delete,
+}
+
+impl Display for AuditAction {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self {
+			Self::Login => write!(f, "login"),
+			Self::Twofa => write!(f, "twofa"),
+			Self::OidcLogin => write!(f, "oidc_login"),
+			Self::Logout => write!(f, "logout"),
+			Self::SessionRevoke => write!(f, "session_revoke"),
+			Self::PasswordChange => write!(f, "password_change"),
+			Self::PasswordResetRequest => write!(f, "password_reset_request"),
+			Self::PasswordReset => write!(f, "password_reset"),
+			Self::MailVerificationRequest => write!(f, "mail_verification_request"),
+			Self::MailVerification => write!(f, "mail_verification"),
+			Self::Impersonate => write!(f, "impersonate"),
+			Self::StatusChange => write!(f, "status_change"),
+			Self::Create => write!(f, "create"),
+			Self::Delete => write!(f, "delete"),
+		}
+	}
+}
+
+impl FromStr for AuditAction {
+	type Err = Status;
+
+	fn from_str(value: &str) -> Result<Self, Self::Err> {
+		match value {
+			"login" => Ok(Self::Login),
+			"twofa" => Ok(Self::Twofa),
+			"oidc_login" => Ok(Self::OidcLogin),
+			"logout" => Ok(Self::Logout),
+			"session_revoke" => Ok(Self::SessionRevoke),
+			"password_change" => Ok(Self::PasswordChange),
+			"password_reset_request" => Ok(Self::PasswordResetRequest),
+			"password_reset" => Ok(Self::PasswordReset),
+			"mail_verification_request" => Ok(Self::MailVerificationRequest),
+			"mail_verification" => Ok(Self::MailVerification),
+			"impersonate" => Ok(Self::Impersonate),
+			"status_change" => Ok(Self::StatusChange),
+			"create" => Ok(Self::Create),
+			"delete" => Ok(Self::Delete),
+			_ => Err(Status::BadRequest),
+		}
+	}
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AuditOutcome {
+	Success,
+	Failure,
+}
+
+impl AuditOutcome {
+	#[must_use]
+	pub const fn from_success(success: bool) -> Self {
+		if success { Self::Success } else { Self::Failure }
+	}
+}
+
+impl Display for AuditOutcome {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self {
+			Self::Success => write!(f, "success"),
+			Self::Failure => write!(f, "failure"),
+		}
+	}
+}
+
+impl FromStr for AuditOutcome {
+	type Err = Status;
+
+	fn from_str(value: &str) -> Result<Self, Self::Err> {
+		match value {
+			"success" => Ok(Self::Success),
+			"failure" => Ok(Self::Failure),
+			_ => Err(Status::BadRequest),
+		}
+	}
+}
+
+/// Entry of the append-only audit log, see `AuditRepository`.
+///
+/// `actor_id` is unknown for anonymous actions such as a failed login, `target_id` is the
+/// resource the action was applied on (created user, deleted class, attempted login...).
+/// `impersonator_id` is the admin who acted through an impersonated session of the actor.
+#[derive(Debug, Clone, Serialize)]
+pub struct AuditEvent {
+	pub id: i64,
+	pub occurred_at: DateTime<Utc>,
+	pub actor_id: Option<String>,
+	pub actor_role: Option<UserType>,
+	pub impersonator_id: Option<String>,
+	pub action: AuditAction,
+	pub target_id: Option<String>,
+	pub ip: Option<String>,
+	pub outcome: AuditOutcome,
+}
+
+#[derive(Debug, Default)]
+pub struct AuditFilter {
+	pub actor_id: Option<String>,
+	pub actor_role: Option<UserType>,
+	pub impersonator_id: Option<String>,
+	pub action: Option<AuditAction>,
+	pub target_id: Option<String>,
+	pub outcome: Option<AuditOutcome>,
+	pub from: Option<DateTime<Utc>>,
+	pub to: Option<DateTime<Utc>>,
+}
+
+/// Event being built, written with `record`.
+#[derive(Debug)]
+#[must_use]
+pub struct NewAuditEvent {
+	pub(crate) actor_id: Option<String>,
+	pub(crate) actor_role: Option<UserType>,
+	pub(crate) impersonator_id: Option<String>,
+	pub(crate) action: AuditAction,
+	pub(crate) target_id: Option<String>,
+	pub(crate) ip: Option<IpAddr>,
+	pub(crate) outcome: AuditOutcome,
+}
+
+impl AuditEvent {
+	pub const fn builder(action: AuditAction, outcome: AuditOutcome) -> NewAuditEvent {
+		NewAuditEvent {
+			actor_id: None,
+			actor_role: None,
+			impersonator_id: None,
+			action,
+			target_id: None,
+			ip: None,
+			outcome,
+		}
+	}
+}
+
+impl NewAuditEvent {
+	pub fn actor(mut self, actor_id: impl Into<String>, actor_role: UserType) -> Self {
+		self.actor_id = Some(actor_id.into());
+		self.actor_role = Some(actor_role);
+		self
+	}
+
+	/// Admin using the session of the actor, see `AuthGuard::impersonator`.
+	pub fn impersonator(mut self, impersonator_id: Option<&str>) -> Self {
+		self.impersonator_id = impersonator_id.map(str::to_string);
+		self
+	}
+
+	/// Role known without an authenticated actor, e.g. the user type of a login attempt.
+	pub const fn role(mut self, actor_role: UserType) -> Self {
+		self.actor_role = Some(actor_role);
+		self
+	}
+
+	pub fn target(mut self, target_id: impl Into<String>) -> Self {
+		self.target_id = Some(target_id.into());
+		self
+	}
+
+	pub const fn outcome(mut self, outcome: AuditOutcome) -> Self {
+		self.outcome = outcome;
+		self
+	}
+
+	pub const fn ip(mut self, ip: Option<IpAddr>) -> Self {
+		self.ip = ip;
+		self
+	}
+
+	pub async fn record(self, audit: &dyn AuditRepository) -> Result<(), Status> {
+		audit.record(&self).await
+	}
+}
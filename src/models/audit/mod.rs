@@ -0,0 +1,3 @@
+mod audit_event;
+
+pub use audit_event::{AuditAction, AuditEvent, AuditFilter, AuditOutcome, NewAuditEvent};
@@ -0,0 +1,37 @@
+use std::{fmt::Display, str::FromStr};
+
+use rocket::http::Status;
+use serde::{Deserialize, Serialize};
+
+/// Suspended accounts are expected to come back, disabled ones are closed but kept for history.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AccountStatus {
+	#[default]
+	Active,
+	Suspended,
+	Disabled,
+}
+
+impl Display for AccountStatus {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self {
+			Self::Active => write!(f, "active"),
+			Self::Suspended => write!(f, "suspended"),
+			Self::Disabled => write!(f, "disabled"),
+		}
+	}
+}
+
+impl FromStr for AccountStatus {
+	type Err = Status;
+
+	fn from_str(value: &str) -> Result<Self, Self::Err> {
+		match value {
+			"active" => Ok(Self::Active),
+			"suspended" => Ok(Self::Suspended),
+			"disabled" => Ok(Self::Disabled),
+			_ => Err(Status::InternalServerError),
+		}
+	}
+}
@@ -0,0 +1,99 @@
+use std::{fmt::Display, str::FromStr};
+
+use chrono::{DateTime, Utc};
+use rocket::http::Status;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::{
+	repositories::ApiKeyRepository,
+	utils::crypto::{generate_token, hash_token},
+};
+
+pub(super) const API_KEY_HEADER: &str = "X-Api-Key";
+const API_KEY_PREFIX: &str = "mosifra_";
+// Length of the beginning of the key kept in clear to help users recognize their keys
+const API_KEY_DISPLAY_LENGTH: usize = 16;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ApiKeyScope {
+	#[serde(rename = "internships:read")]
+	InternshipsRead,
+	#[serde(rename = "internships:write")]
+	InternshipsWrite,
+}
+
+impl Display for ApiKeyScope {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self {
+			Self::InternshipsRead => write!(f, "internships:read"),
+			Self::InternshipsWrite => write!(f, "internships:write"),
+		}
+	}
+}
+
+impl FromStr for ApiKeyScope {
+	type Err = Status;
+
+	fn from_str(value: &str) -> Result<Self, Self::Err> {
+		match value {
+			"internships:read" => Ok(Self::InternshipsRead),
+			"internships:write" => Ok(Self::InternshipsWrite),
+			_ => Err(Status::InternalServerError),
+		}
+	}
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ApiKey {
+	pub id: String,
+	#[serde(skip)]
+	pub company_id: String,
+	pub name: String,
+	pub prefix: String,
+	pub scopes: Vec<ApiKeyScope>,
+	pub created_at: DateTime<Utc>,
+	pub expires_at: Option<DateTime<Utc>>,
+	pub last_used_at: Option<DateTime<Utc>>,
+}
+
+impl ApiKey {
+	/// Creates a key for the company. The raw key is returned only here, only its hash is stored.
+	pub async fn create(
+		api_keys: &dyn ApiKeyRepository,
+		company_id: String,
+		name: String,
+		scopes: Vec<ApiKeyScope>,
+		expires_at: Option<DateTime<Utc>>,
+	) -> Result<(Self, String), Status> {
+		let raw_key = format!("{API_KEY_PREFIX}{}", generate_token());
+
+		let api_key = Self {
+			id: Uuid::new_v4().to_string(),
+			company_id,
+			name,
+			prefix: raw_key[..API_KEY_DISPLAY_LENGTH].to_string(),
+			scopes,
+			created_at: Utc::now(),
+			expires_at,
+			last_used_at: None,
+		};
+
+		api_keys.insert(&api_key, &hash_token(&raw_key)).await?;
+
+		Ok((api_key, raw_key))
+	}
+
+	/// Finds the key matching the raw value sent by a client, if it exists and is not expired.
+	pub async fn authenticate(
+		api_keys: &dyn ApiKeyRepository,
+		raw_key: &str,
+	) -> Result<Option<Self>, Status> {
+		api_keys.authenticate(&hash_token(raw_key)).await
+	}
+
+	#[must_use]
+	pub fn has_scope(&self, scope: ApiKeyScope) -> bool {
+		self.scopes.contains(&scope)
+	}
+}
@@ -1,189 +1,244 @@
-use std::{collections::HashSet, env, process::exit};
-
-use jsonwebtoken::{DecodingKey, EncodingKey, Header, Validation, decode, encode};
+use jsonwebtoken::get_current_timestamp;
 use rocket::{
 	Request,
 	http::Status,
 	request::{FromRequest, Outcome},
 };
 use serde::{Deserialize, Serialize};
+use uuid::Uuid;
 
 use crate::{
-	error_handling::StatusResultHandling,
-	models::users::{Company, GenericUser, Student, University, admin::Admin},
-	redis::{self, session_exist},
+	config::ttl,
+	error_handling::{ApiError, guard_error},
+	models::users::GenericUser,
+	redis::{self, get_session, touch_session},
+	repositories::{AccountLookup, Repositories, UserRepository},
 };
 
-use super::UserType;
+use super::{
+	AccountStatus, JwtKeyring, UserType,
+	api_key::API_KEY_HEADER,
+	session_cookies::{SESSION_COOKIE, is_csrf_valid},
+};
 
 #[derive(Debug, Serialize, Deserialize)]
 struct Claims {
 	session_id: String,
 	user_type: UserType,
+	exp: u64,
+	iat: u64,
+	jti: String,
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	impersonator: Option<String>,
 }
 
 #[derive(Debug)]
 pub struct AuthGuard {
 	pub session_id: String,
 	pub user_type: UserType,
+	/// Id of the admin acting on behalf of the user, if any
+	pub impersonator: Option<String>,
 }
 
 impl AuthGuard {
-	fn from_raw_jwt(raw_jwt: &str) -> Result<Self, String> {
-		let jwt_secret = env::var("JWT_SECRET").ok().map_or_else(
-			|| {
-				eprintln!("JWT Secret must be in .env");
-				exit(1)
-			},
-			|secret| secret,
-		);
-
-		let mut validation = Validation::new(jsonwebtoken::Algorithm::HS256);
-		validation.required_spec_claims = HashSet::new();
-		validation.validate_exp = false;
-
-		let token = decode::<Claims>(
-			&raw_jwt,
-			&DecodingKey::from_secret(jwt_secret.as_bytes()),
-			&validation,
-		)
-		.map_err(|e| format!("JWT is not valid: {e}"))?;
+	fn from_raw_jwt(keyring: &JwtKeyring, raw_jwt: &str) -> Result<Self, String> {
+		let claims: Claims = keyring.decode(raw_jwt, &["exp", "iat", "jti"])?;
 
 		Ok(Self {
-			session_id: token.claims.session_id,
-			user_type: token.claims.user_type,
+			session_id: claims.session_id,
+			user_type: claims.user_type,
+			impersonator: claims.impersonator,
 		})
 	}
 
-	pub fn new_raw_jwt_from_data(
+	pub async fn new_raw_jwt_from_data(
+		keyring: &JwtKeyring,
 		session_id: String,
 		user_type: UserType,
 	) -> Result<Option<String>, Status> {
-		let jwt_secret = env::var("JWT_SECRET").ok().map_or_else(
-			|| {
-				eprintln!("JWT Secret must be in .env");
-				exit(1)
-			},
-			|secret| secret,
-		);
-
-		if !session_exist(&session_id)? {
+		// The impersonator comes from the session so that refreshed tokens keep it
+		let Some(session) = get_session(&session_id).await? else {
 			return Ok(None);
-		}
+		};
 
+		// Impersonated sessions get no refresh token, their only token lasts as long as them
+		let lifetime = if session.impersonator.is_some() {
+			ttl().impersonation_seconds
+		} else {
+			ttl().access_token_seconds
+		};
+		let iat = get_current_timestamp();
 		let claims = Claims {
 			session_id,
 			user_type,
+			exp: iat + lifetime,
+			iat,
+			jti: Uuid::new_v4().to_string(),
+			impersonator: session.impersonator,
 		};
 
-		let jwt = encode(
-			&Header::default(),
-			&claims,
-			&EncodingKey::from_secret(jwt_secret.as_bytes()),
-		)
-		.internal_server_error("Failed to create JWT token")?;
-
-		Ok(Some(jwt))
+		Ok(Some(keyring.encode(&claims)?))
 	}
 
-	pub async fn get_generic_user(&self) -> Result<GenericUser, Status> {
-		match self.user_type {
-			UserType::Admin => Ok(GenericUser::new(Admin::default(), String::new())),
-			UserType::University => Ok(GenericUser::new(
-				University::from_id(self.get_user_id()?).await?,
+	/// `401 Unauthorized` if the account was deleted while the session was still open.
+	pub async fn get_generic_user(
+		&self,
+		users: &dyn UserRepository,
+	) -> Result<GenericUser, Status> {
+		let user_id = self.get_user_id().await?;
+
+		let generic_user = match self.user_type {
+			UserType::Admin => GenericUser::new(
+				users
+					.get_admin(&user_id)
+					.await?
+					.ok_or(Status::Unauthorized)?,
+				self.session_id.clone(),
+			),
+			UserType::University => GenericUser::new(
+				users
+					.get_university(&user_id)
+					.await?
+					.ok_or(Status::Unauthorized)?,
 				self.session_id.clone(),
-			)),
-			UserType::Student => Ok(GenericUser::new(
-				Student::from_id(self.get_user_id()?).await?,
+			),
+			UserType::Student => GenericUser::new(
+				users
+					.get_student(&user_id)
+					.await?
+					.ok_or(Status::Unauthorized)?,
 				self.session_id.clone(),
-			)),
-			UserType::Company => Ok(GenericUser::new(
-				Company::from_id(self.get_user_id()?).await?,
+			),
+			UserType::Company => GenericUser::new(
+				users
+					.get_company(&user_id)
+					.await?
+					.ok_or(Status::Unauthorized)?,
 				self.session_id.clone(),
-			)),
+			),
+		};
+
+		Ok(generic_user.with_impersonator(self.impersonator.clone()))
+	}
+
+	/// Impersonated sessions can't touch the credentials of the user (password, 2FA...).
+	pub const fn forbid_impersonation(&self) -> Result<(), Status> {
+		if self.impersonator.is_some() {
+			Err(Status::Forbidden)
+		} else {
+			Ok(())
 		}
 	}
 
-	pub fn get_user_id(&self) -> Result<String, Status> {
-		redis::get_user_id_from_session_id(self.session_id.clone())
+	pub async fn get_user_id(&self) -> Result<String, Status> {
+		redis::get_user_id_from_session_id(self.session_id.clone()).await
 	}
 }
 
-#[async_trait]
-impl<'r> FromRequest<'r> for AuthGuard {
-	type Error = String;
-
-	async fn from_request(request: &'r Request<'_>) -> Outcome<Self, Self::Error> {
+impl AuthGuard {
+	async fn authenticate(request: &Request<'_>, allow_restricted: bool) -> Outcome<Self, String> {
 		let auth_header = request.headers().get_one("Authorization");
-		match auth_header {
+		let jwt = match auth_header {
 			Some(header) if header.starts_with("Bearer ") => {
-				let jwt = header.trim_start_matches("Bearer ");
-				if validate_jwt(jwt) {
-					let auth_guard = match Self::from_raw_jwt(jwt) {
-						Ok(auth_guard) => auth_guard,
-						Err(e) => {
-							return Outcome::Error((
-								Status::InternalServerError,
-								format!(
-									"Error while getting the jwt information (Should be impossible ?) : {e}"
-								),
-							));
-						}
-					};
-					if auth_guard.user_type == UserType::Admin {
-						Outcome::Success(auth_guard) // NOT GOOD BUT WILL DO FOR TESTING
-					} else {
-						let session_exist = match session_exist(&auth_guard.session_id) {
-							Ok(session_exist) => session_exist,
-							Err(e) => {
-								return Outcome::Error((
-									e,
-									"Error while checking session".to_string(),
-								));
-							}
-						};
-						if session_exist {
-							Outcome::Success(auth_guard)
-						} else {
-							Outcome::Error((Status::Unauthorized, "Session expired".to_string()))
-						}
-					}
-				} else {
-					Outcome::Error((Status::Unauthorized, "Invalid Token".to_string()))
+				header.trim_start_matches("Bearer ").to_string()
+			}
+			None => match request.cookies().get_private(SESSION_COOKIE) {
+				// The browser sends the cookie by itself, the request must prove its origin
+				Some(_) if !is_csrf_valid(request) => {
+					return guard_error(request, ApiError::InvalidCsrfToken);
+				}
+				Some(cookie) => cookie.value().to_string(),
+				// Machine clients go to the guards which accept API keys (see `CompanyUser`)
+				None if request.headers().contains(API_KEY_HEADER) => {
+					return Outcome::Forward(Status::Unauthorized);
 				}
+				None => {
+					return Outcome::Error((
+						Status::Unauthorized,
+						"Authorization header missing".to_string(),
+					));
+				}
+			},
+			Some(_) => {
+				return Outcome::Error((
+					Status::Unauthorized,
+					"Authorization header missing".to_string(),
+				));
+			}
+		};
+
+		let Some(keyring) = request.rocket().state::<JwtKeyring>() else {
+			return Outcome::Error((
+				Status::InternalServerError,
+				"JWT keyring is not managed".to_string(),
+			));
+		};
+		let auth_guard = match Self::from_raw_jwt(keyring, &jwt) {
+			Ok(auth_guard) => auth_guard,
+			Err(e) => {
+				return Outcome::Error((Status::Unauthorized, format!("Invalid Token: {e}")));
+			}
+		};
+		let session = match get_session(&auth_guard.session_id).await {
+			Ok(Some(session)) => session,
+			Ok(None) => {
+				return Outcome::Error((Status::Unauthorized, "Session expired".to_string()));
+			}
+			Err(e) => return Outcome::Error((e, "Error while checking session".to_string())),
+		};
+
+		let repositories = match Repositories::from_request(request) {
+			Ok(repositories) => repositories,
+			Err(e) => return Outcome::Error((e, "Repositories are not managed".to_string())),
+		};
+
+		// Sessions opened before a suspension are refused as well
+		match repositories
+			.accounts
+			.get_status(auth_guard.user_type, AccountLookup::Id, &session.user_id)
+			.await
+		{
+			Ok(Some((AccountStatus::Active, _))) => {}
+			Ok(Some((status, reason))) => {
+				return guard_error(request, ApiError::AccountInactive { status, reason });
 			}
-			_ => Outcome::Error((
-				Status::Unauthorized,
-				"Authorization header missing".to_string(),
-			)),
+			Ok(None) => {
+				return Outcome::Error((Status::Unauthorized, "Account deleted".to_string()));
+			}
+			Err(e) => {
+				return Outcome::Error((e, "Error while checking account status".to_string()));
+			}
+		}
+
+		if session.restricted && !allow_restricted {
+			return guard_error(request, ApiError::PasswordChangeRequired);
+		}
+
+		match touch_session(&auth_guard.session_id, session).await {
+			Ok(()) => Outcome::Success(auth_guard),
+			Err(e) => Outcome::Error((e, "Error while updating session".to_string())),
 		}
 	}
 }
 
-fn validate_jwt(jwt: &str) -> bool {
-	let jwt_secret = env::var("JWT_SECRET").ok().map_or_else(
-		|| {
-			eprintln!("JWT Secret must be in .env");
-			exit(1)
-		},
-		|secret| secret,
-	);
-
-	let mut validation = Validation::new(jsonwebtoken::Algorithm::HS256);
-	validation.required_spec_claims = HashSet::new();
-	validation.validate_exp = false;
-
-	let token = decode::<Claims>(
-		&jwt,
-		&DecodingKey::from_secret(jwt_secret.as_bytes()),
-		&validation,
-	);
-
-	match token {
-		Ok(_) => true,
-		Err(e) => {
-			println!("{e}");
-			false
-		}
+#[async_trait]
+impl<'r> FromRequest<'r> for AuthGuard {
+	type Error = String;
+
+	async fn from_request(request: &'r Request<'_>) -> Outcome<Self, Self::Error> {
+		Self::authenticate(request, false).await
+	}
+}
+
+/// Also accepts restricted sessions, which must only be able to set a new password or log out.
+#[derive(Debug)]
+pub struct PasswordChangeGuard(pub AuthGuard);
+
+#[async_trait]
+impl<'r> FromRequest<'r> for PasswordChangeGuard {
+	type Error = String;
+
+	async fn from_request(request: &'r Request<'_>) -> Outcome<Self, Self::Error> {
+		AuthGuard::authenticate(request, true).await.map(Self)
 	}
 }
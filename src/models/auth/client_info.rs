@@ -0,0 +1,28 @@
+use std::{convert::Infallible, net::IpAddr};
+
+use rocket::{
+	Request,
+	request::{FromRequest, Outcome},
+};
+
+/// Informations about the device used, kept with the session.
+#[derive(Debug)]
+pub struct ClientInfo {
+	pub ip: Option<IpAddr>,
+	pub user_agent: Option<String>,
+}
+
+#[async_trait]
+impl<'r> FromRequest<'r> for ClientInfo {
+	type Error = Infallible;
+
+	async fn from_request(request: &'r Request<'_>) -> Outcome<Self, Self::Error> {
+		Outcome::Success(Self {
+			ip: request.client_ip(),
+			user_agent: request
+				.headers()
+				.get_one("User-Agent")
+				.map(ToString::to_string),
+		})
+	}
+}
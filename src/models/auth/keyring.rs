@@ -0,0 +1,240 @@
+//! Keys used to sign and verify the JWTs issued by the API.
+//!
+//! The keyring is described by the TOML file pointed to by `JWT_KEYRING` (`jwt.keyring`):
+//!
+//! ```toml
+//! signing_kid = "2026-10"
+//!
+//! [[keys]]
+//! kid = "2026-10"
+//! algorithm = "HS256"
+//! secret = "..."
+//!
+//! [[keys]]
+//! kid = "2026-04"
+//! algorithm = "RS256"
+//! public_key = "keys/2026-04.pub.pem"
+//! verify_until = "2026-11-01T00:00:00Z"
+//! ```
+//!
+//! New tokens are signed with `signing_kid`, the other keys only verify the tokens they signed
+//! until `verify_until`. Without a keyring, `JWT_SECRET` is used as a single HS256 key.
+
+use std::{collections::HashMap, fs, path::Path};
+
+use chrono::{DateTime, Utc};
+use jsonwebtoken::{
+	Algorithm, DecodingKey, EncodingKey, Header, Validation, decode, decode_header, encode,
+};
+use rocket::{
+	figment::{
+		Figment,
+		providers::{Format, Toml},
+	},
+	http::Status,
+};
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+
+use crate::{config::JwtConfig, error_handling::StatusResultHandling};
+
+/// Key id of the `JWT_SECRET` key, also used for the tokens issued before key ids existed.
+const LEGACY_KID: &str = "default";
+
+#[derive(Debug, Deserialize)]
+struct KeyringConfig {
+	signing_kid: String,
+	keys: Vec<KeyConfig>,
+}
+
+#[derive(Debug, Deserialize)]
+struct KeyConfig {
+	kid: String,
+	algorithm: Algorithm,
+	/// Shared secret of the HMAC algorithms
+	secret: Option<String>,
+	/// PEM files of the asymmetric algorithms, the private key is only needed to sign
+	private_key: Option<String>,
+	public_key: Option<String>,
+	verify_until: Option<DateTime<Utc>>,
+}
+
+struct JwtKey {
+	algorithm: Algorithm,
+	encoding: Option<EncodingKey>,
+	decoding: DecodingKey,
+	verify_until: Option<DateTime<Utc>>,
+}
+
+pub struct JwtKeyring {
+	signing_kid: String,
+	keys: HashMap<String, JwtKey>,
+}
+
+impl std::fmt::Debug for JwtKeyring {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		let mut kids: Vec<&String> = self.keys.keys().collect();
+		kids.sort();
+
+		f.debug_struct("JwtKeyring")
+			.field("signing_kid", &self.signing_kid)
+			.field("kids", &kids)
+			.finish()
+	}
+}
+
+impl JwtKeyring {
+	/// Loads the configured keyring, or the single `JWT_SECRET` key.
+	pub fn load(jwt: &JwtConfig) -> Result<Self, String> {
+		if let Some(path) = &jwt.keyring {
+			let config: KeyringConfig = Figment::from(Toml::file_exact(path))
+				.extract()
+				.map_err(|e| format!("Invalid JWT keyring {path}: {e}"))?;
+
+			return Self::from_config(config);
+		}
+
+		let secret = jwt
+			.secret
+			.as_ref()
+			.ok_or_else(|| "JWT_KEYRING or JWT_SECRET must be set".to_string())?;
+
+		Self::from_config(KeyringConfig {
+			signing_kid: LEGACY_KID.to_string(),
+			keys: vec![KeyConfig {
+				kid: LEGACY_KID.to_string(),
+				algorithm: Algorithm::HS256,
+				secret: Some(secret.expose().to_string()),
+				private_key: None,
+				public_key: None,
+				verify_until: None,
+			}],
+		})
+	}
+
+	fn from_config(config: KeyringConfig) -> Result<Self, String> {
+		let mut keys = HashMap::new();
+
+		for key in config.keys {
+			let kid = key.kid.clone();
+			let signing = kid == config.signing_kid;
+			if keys.insert(kid.clone(), JwtKey::from_config(key, signing)?).is_some() {
+				return Err(format!("JWT key {kid} is defined twice"));
+			}
+		}
+
+		match keys.get(&config.signing_kid) {
+			Some(key) if key.verify_until.is_some() => Err(format!(
+				"Signing JWT key {} can't have a verify_until",
+				config.signing_kid
+			)),
+			Some(_) => Ok(Self {
+				signing_kid: config.signing_kid,
+				keys,
+			}),
+			None => Err(format!("Signing JWT key {} is missing", config.signing_kid)),
+		}
+	}
+
+	/// Signs the claims with the current signing key, its `kid` is written in the header.
+	pub fn encode<T: Serialize>(&self, claims: &T) -> Result<String, Status> {
+		let key = &self.keys[&self.signing_kid];
+		let encoding = key
+			.encoding
+			.as_ref()
+			.ok_or(Status::InternalServerError)?;
+
+		let mut header = Header::new(key.algorithm);
+		header.kid = Some(self.signing_kid.clone());
+
+		encode(&header, claims, encoding).internal_server_error("Failed to create JWT token")
+	}
+
+	/// Checks the token with the key named by its `kid` and returns its claims.
+	pub fn decode<T: DeserializeOwned>(
+		&self,
+		token: &str,
+		required_claims: &[&str],
+	) -> Result<T, String> {
+		let header = decode_header(token).map_err(|e| format!("JWT is not valid: {e}"))?;
+		let kid = header.kid.as_deref().unwrap_or(LEGACY_KID);
+
+		let key = self
+			.keys
+			.get(kid)
+			.ok_or_else(|| format!("Unknown JWT key {kid}"))?;
+		if key.verify_until.is_some_and(|verify_until| verify_until <= Utc::now()) {
+			return Err(format!("JWT key {kid} is retired"));
+		}
+
+		let mut validation = Validation::new(key.algorithm);
+		validation.set_required_spec_claims(required_claims);
+
+		decode::<T>(token, &key.decoding, &validation)
+			.map(|token| token.claims)
+			.map_err(|e| format!("JWT is not valid: {e}"))
+	}
+}
+
+impl JwtKey {
+	fn from_config(config: KeyConfig, signing: bool) -> Result<Self, String> {
+		let kid = &config.kid;
+
+		let (encoding, decoding) = match config.algorithm {
+			Algorithm::HS256 | Algorithm::HS384 | Algorithm::HS512 => {
+				let secret = config
+					.secret
+					.ok_or_else(|| format!("JWT key {kid} needs a secret"))?;
+
+				(
+					Some(EncodingKey::from_secret(secret.as_bytes())),
+					DecodingKey::from_secret(secret.as_bytes()),
+				)
+			}
+			algorithm => {
+				let public_key = read_pem(kid, config.public_key.as_deref(), "public_key")?;
+				let private_key = if signing {
+					Some(read_pem(kid, config.private_key.as_deref(), "private_key")?)
+				} else {
+					None
+				};
+
+				asymmetric_keys(algorithm, private_key.as_deref(), &public_key)
+					.map_err(|e| format!("Invalid PEM for JWT key {kid}: {e}"))?
+			}
+		};
+
+		Ok(Self {
+			algorithm: config.algorithm,
+			encoding,
+			decoding,
+			verify_until: config.verify_until,
+		})
+	}
+}
+
+fn read_pem(kid: &str, path: Option<&str>, field: &str) -> Result<Vec<u8>, String> {
+	let path = path.ok_or_else(|| format!("JWT key {kid} needs a {field}"))?;
+
+	fs::read(Path::new(path)).map_err(|e| format!("Can't read {field} of JWT key {kid}: {e}"))
+}
+
+fn asymmetric_keys(
+	algorithm: Algorithm,
+	private_key: Option<&[u8]>,
+	public_key: &[u8],
+) -> Result<(Option<EncodingKey>, DecodingKey), jsonwebtoken::errors::Error> {
+	match algorithm {
+		Algorithm::ES256 | Algorithm::ES384 => Ok((
+			private_key.map(EncodingKey::from_ec_pem).transpose()?,
+			DecodingKey::from_ec_pem(public_key)?,
+		)),
+		Algorithm::EdDSA => Ok((
+			private_key.map(EncodingKey::from_ed_pem).transpose()?,
+			DecodingKey::from_ed_pem(public_key)?,
+		)),
+		_ => Ok((
+			private_key.map(EncodingKey::from_rsa_pem).transpose()?,
+			DecodingKey::from_rsa_pem(public_key)?,
+		)),
+	}
+}
@@ -0,0 +1,84 @@
+use jsonwebtoken::get_current_timestamp;
+use rocket::http::Status;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::{
+	config::ttl,
+	redis::{consume_mail_verification, set_mail_verification},
+	repositories::AccountRepository,
+	utils::mail::{Mailer, send_mail_verification_mail},
+};
+
+use super::{JwtKeyring, UserType};
+
+const MAIL_VERIFICATION_PURPOSE: &str = "mail_verification";
+
+#[derive(Debug, Serialize, Deserialize)]
+struct MailVerificationClaims {
+	sub: String,
+	user_type: UserType,
+	purpose: String,
+	exp: u64,
+	jti: String,
+}
+
+/// Mails a verification link to the address of the account, the previous link stops working.
+///
+/// Returns `false` without sending anything if the account doesn't exist or is already verified.
+pub async fn send_mail_verification(
+	keyring: &JwtKeyring,
+	accounts: &dyn AccountRepository,
+	mailer: &Mailer,
+	user_type: UserType,
+	user_id: &str,
+) -> Result<bool, Status> {
+	let Some((mail, false)) = accounts.get_mail_verification(user_type, user_id).await? else {
+		return Ok(false);
+	};
+
+	let ttl_seconds = ttl().mail_verification_seconds;
+	let jti = Uuid::new_v4().to_string();
+	let claims = MailVerificationClaims {
+		sub: user_id.to_string(),
+		user_type,
+		purpose: MAIL_VERIFICATION_PURPOSE.to_string(),
+		exp: get_current_timestamp() + ttl_seconds,
+		jti,
+	};
+
+	let token = keyring.encode(&claims)?;
+
+	set_mail_verification(&claims.sub, &claims.jti, ttl_seconds).await?;
+	send_mail_verification_mail(mailer, &mail, &token).await?;
+
+	Ok(true)
+}
+
+/// Marks the address as verified if the link is the last one sent and has not expired.
+/// Returns the verified account, or `None` if the token is rejected.
+pub async fn verify_mail_address(
+	keyring: &JwtKeyring,
+	accounts: &dyn AccountRepository,
+	token: &str,
+) -> Result<Option<(String, UserType)>, Status> {
+	let Ok(claims) = keyring.decode::<MailVerificationClaims>(token, &["exp", "sub", "jti"])
+	else {
+		return Ok(None);
+	};
+
+	if claims.purpose != MAIL_VERIFICATION_PURPOSE
+		|| !consume_mail_verification(&claims.sub, &claims.jti).await?
+	{
+		return Ok(None);
+	}
+
+	if !accounts
+		.set_mail_verified(claims.user_type, &claims.sub)
+		.await?
+	{
+		return Ok(None);
+	}
+
+	Ok(Some((claims.sub, claims.user_type)))
+}
@@ -0,0 +1,39 @@
+use rocket::{
+	Request,
+	http::Status,
+	request::{FromRequest, Outcome},
+};
+
+use crate::{config::app_config, utils::crypto::hash_token};
+
+/// Scraper of `/metrics`, which has to send `Authorization: Bearer <METRICS_TOKEN>`.
+///
+/// Without a configured token nobody gets in, the metrics are never public by mistake.
+#[derive(Debug)]
+pub struct MetricsScraper;
+
+#[async_trait]
+impl<'r> FromRequest<'r> for MetricsScraper {
+	type Error = String;
+
+	async fn from_request(request: &'r Request<'_>) -> Outcome<Self, Self::Error> {
+		let config = match app_config() {
+			Ok(config) => config,
+			Err(status) => return Outcome::Error((status, "Configuration missing".to_string())),
+		};
+		let Some(token) = &config.metrics.token else {
+			return Outcome::Error((Status::Forbidden, "Metrics are disabled".to_string()));
+		};
+
+		match request.headers().get_one("Authorization") {
+			// Hashed so the comparison doesn't tell how much of the token is right
+			Some(header)
+				if header.strip_prefix("Bearer ").map(hash_token)
+					== Some(hash_token(token.expose())) =>
+			{
+				Outcome::Success(Self)
+			}
+			_ => Outcome::Error((Status::Unauthorized, "Invalid metrics token".to_string())),
+		}
+	}
+}
@@ -1,5 +1,38 @@
+mod account_status;
+mod api_key;
 mod auth_guard;
+mod client_info;
+mod keyring;
+mod mail_verification;
+mod metrics_scraper;
+mod oidc;
+mod password_reset;
+pub mod policy;
+mod refresh_token;
+mod role_guards;
+mod session_cookies;
+mod throttle;
+mod twofa;
 mod user_type;
 
-pub use auth_guard::AuthGuard;
+pub use account_status::AccountStatus;
+pub use api_key::{ApiKey, ApiKeyScope};
+pub use auth_guard::{AuthGuard, PasswordChangeGuard};
+pub use client_info::ClientInfo;
+pub use keyring::JwtKeyring;
+pub use mail_verification::{send_mail_verification, verify_mail_address};
+pub use metrics_scraper::MetricsScraper;
+pub use oidc::{OidcIdentity, OidcProvider};
+pub use password_reset::{request_password_reset, reset_password};
+pub use refresh_token::{
+	RotatedRefreshToken, issue_refresh_token, refresh_token_ttl, rotate_refresh_token,
+};
+pub use role_guards::{AdminUser, CompanyUser, RoleUser, StudentUser, UniversityUser};
+pub use session_cookies::{
+	CsrfChecked, SessionTokens, clear_session_cookies, deliver_session_tokens, get_refresh_cookie,
+};
+pub use throttle::{
+	Throttle, register_reset_request, register_twofa_attempt, reset_twofa_failures,
+};
+pub use twofa::{TwofaMethod, TwofaSettings};
 pub use user_type::UserType;
@@ -0,0 +1,437 @@
+//! OpenID Connect relying party (authorization code flow with PKCE).
+//!
+//! Each university can plug its own identity provider. Its students and its own account can then
+//! log in through the provider instead of the password and 2FA steps, which stay available.
+//!
+//! The provider URLs are chosen by the universities but fetched by the API, so they must be
+//! `https` and resolve to public addresses, unless `OIDC_ALLOW_INSECURE_PROVIDERS` is set for a
+//! local mock. The discovery documents and key sets are kept `OIDC_CACHE_SECONDS`.
+
+use std::{
+	collections::HashMap,
+	net::{IpAddr, SocketAddr},
+	sync::{LazyLock, Mutex},
+	time::{Duration, Instant},
+};
+
+use base64::{Engine, engine::general_purpose::URL_SAFE_NO_PAD};
+use jsonwebtoken::{
+	Algorithm, DecodingKey, Validation, decode, decode_header,
+	jwk::{Jwk, JwkSet},
+};
+use reqwest::{Client, redirect::Policy};
+use rocket::http::Status;
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+use sha2::{Digest, Sha256};
+use tokio::net::lookup_host;
+use tracing::warn;
+use url::{Host, Url};
+
+use crate::{
+	config::{app_config, oidc_config, ttl},
+	error_handling::StatusResultHandling,
+	redis::{OidcLoginState, consume_oidc_state, set_oidc_state},
+	repositories::{AccountLookup, Repositories},
+	utils::crypto::generate_token,
+};
+
+use super::UserType;
+
+const OIDC_HTTP_TIMEOUT_SECONDS: u64 = 10;
+const OIDC_SCOPES: &str = "openid email";
+
+// Symmetric algorithms would be keyed with the client secret, only provider keys are trusted
+const ALLOWED_ALGORITHMS: [Algorithm; 8] = [
+	Algorithm::RS256,
+	Algorithm::RS384,
+	Algorithm::RS512,
+	Algorithm::PS256,
+	Algorithm::PS384,
+	Algorithm::PS512,
+	Algorithm::ES256,
+	Algorithm::ES384,
+];
+
+#[derive(Debug, Clone, Serialize)]
+pub struct OidcProvider {
+	pub university_id: String,
+	pub issuer: String,
+	pub client_id: String,
+	#[serde(skip)]
+	pub client_secret: Option<String>,
+}
+
+static DISCOVERY_CACHE: LazyLock<ProviderCache<DiscoveryDocument>> =
+	LazyLock::new(ProviderCache::default);
+static JWKS_CACHE: LazyLock<ProviderCache<JwkSet>> = LazyLock::new(ProviderCache::default);
+
+#[derive(Debug, Clone, Deserialize)]
+struct DiscoveryDocument {
+	issuer: String,
+	authorization_endpoint: String,
+	token_endpoint: String,
+	jwks_uri: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct TokenResponse {
+	id_token: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct IdTokenClaims {
+	sub: String,
+	email: Option<String>,
+	email_verified: Option<bool>,
+	nonce: Option<String>,
+}
+
+/// Account matched by a successful OIDC login.
+#[derive(Debug)]
+pub struct OidcIdentity {
+	pub user_id: String,
+	pub user_type: UserType,
+	pub remember_me: bool,
+}
+
+impl OidcProvider {
+	/// Prepares a login and returns the provider URL the user must be redirected to.
+	pub async fn start_login(&self, remember_me: bool) -> Result<String, Status> {
+		let discovery = self.discover().await?;
+		let state = generate_token();
+		let nonce = generate_token();
+		let code_verifier = generate_token();
+		let code_challenge = URL_SAFE_NO_PAD.encode(Sha256::digest(code_verifier.as_bytes()));
+
+		set_oidc_state(
+			&state,
+			&OidcLoginState {
+				university_id: self.university_id.clone(),
+				nonce: nonce.clone(),
+				code_verifier,
+				remember_me,
+			},
+			ttl().oidc_state_seconds,
+		)
+		.await?;
+
+		let url = Url::parse_with_params(
+			&discovery.authorization_endpoint,
+			&[
+				("response_type", "code"),
+				("client_id", &self.client_id),
+				("redirect_uri", &redirect_uri()?),
+				("scope", OIDC_SCOPES),
+				("state", &state),
+				("nonce", &nonce),
+				("code_challenge", &code_challenge),
+				("code_challenge_method", "S256"),
+			],
+		)
+		.map_err(provider_error("Invalid authorization_endpoint"))?;
+
+		Ok(url.into())
+	}
+
+	/// Exchanges the code sent back by the provider and finds the matching account.
+	///
+	/// Returns `None` if the state is unknown or no student or university matches the identity.
+	pub async fn finish_login(
+		repositories: &Repositories,
+		state: &str,
+		code: &str,
+	) -> Result<Option<OidcIdentity>, Status> {
+		let Some(login_state) = consume_oidc_state(state).await? else {
+			return Ok(None);
+		};
+		let Some(provider) = repositories
+			.oidc_providers
+			.get(&login_state.university_id)
+			.await?
+		else {
+			return Ok(None);
+		};
+
+		let discovery = provider.discover().await?;
+		let redirect_uri = redirect_uri()?;
+		let mut params = vec![
+			("grant_type", "authorization_code"),
+			("code", code),
+			("redirect_uri", &redirect_uri),
+			("client_id", &provider.client_id),
+			("code_verifier", &login_state.code_verifier),
+		];
+		if let Some(client_secret) = &provider.client_secret {
+			params.push(("client_secret", client_secret));
+		}
+
+		let token_response = provider_client(&discovery.token_endpoint)
+			.await?
+			.post(&discovery.token_endpoint)
+			.form(&params)
+			.send()
+			.await
+			.and_then(reqwest::Response::error_for_status)
+			.map_err(provider_error("Error during the OIDC code exchange"))?
+			.json::<TokenResponse>()
+			.await
+			.map_err(provider_error("Invalid OIDC token response"))?;
+
+		let claims = provider
+			.validate_id_token(&discovery, &token_response.id_token)
+			.await?;
+
+		if claims.nonce.as_deref() != Some(login_state.nonce.as_str()) {
+			return Ok(None);
+		}
+
+		let Some((user_id, user_type)) = provider.find_account(repositories, &claims).await? else {
+			return Ok(None);
+		};
+		if repositories
+			.accounts
+			.ensure_active(user_type, &user_id)
+			.await
+			.is_err()
+		{
+			return Ok(None);
+		}
+
+		Ok(Some(OidcIdentity {
+			user_id,
+			user_type,
+			remember_me: login_state.remember_me,
+		}))
+	}
+
+	async fn discover(&self) -> Result<DiscoveryDocument, Status> {
+		let issuer = self.issuer.trim_end_matches('/');
+		let url = format!("{issuer}/.well-known/openid-configuration");
+		if let Some(discovery) = DISCOVERY_CACHE.get(&url) {
+			return Ok(discovery);
+		}
+
+		let discovery: DiscoveryDocument = fetch_json(&url).await?;
+		if discovery.issuer.trim_end_matches('/') != issuer {
+			warn!("OIDC discovery issuer does not match {issuer}");
+			return Err(Status::BadGateway);
+		}
+
+		DISCOVERY_CACHE.insert(url, discovery.clone());
+
+		Ok(discovery)
+	}
+
+	async fn validate_id_token(
+		&self,
+		discovery: &DiscoveryDocument,
+		id_token: &str,
+	) -> Result<IdTokenClaims, Status> {
+		let header = decode_header(id_token).map_err(provider_error("Invalid ID token header"))?;
+		if !ALLOWED_ALGORITHMS.contains(&header.alg) {
+			warn!("ID token signed with unsupported algorithm {:?}", header.alg);
+			return Err(Status::BadGateway);
+		}
+
+		let jwk = match JWKS_CACHE
+			.get(&discovery.jwks_uri)
+			.and_then(|jwks| find_key(&jwks, header.kid.as_deref()))
+		{
+			Some(jwk) => jwk,
+			// Not fetched yet, expired, or the provider rotated its keys
+			None => {
+				let jwks: JwkSet = fetch_json(&discovery.jwks_uri).await?;
+				JWKS_CACHE.insert(discovery.jwks_uri.clone(), jwks.clone());
+				find_key(&jwks, header.kid.as_deref()).ok_or(Status::BadGateway)?
+			}
+		};
+		let key = DecodingKey::from_jwk(&jwk).map_err(provider_error("Invalid provider key"))?;
+
+		let mut validation = Validation::new(header.alg);
+		validation.set_issuer(&[&discovery.issuer]);
+		validation.set_audience(&[&self.client_id]);
+		validation.set_required_spec_claims(&["exp", "iss", "aud", "sub"]);
+
+		decode::<IdTokenClaims>(id_token, &key, &validation)
+			.map(|token| token.claims)
+			.map_err(provider_error("ID token is not valid"))
+	}
+
+	/// Looks for the account already linked to the subject, then for an account of the university
+	/// with the same verified email, which gets linked to the subject for the next logins.
+	async fn find_account(
+		&self,
+		repositories: &Repositories,
+		claims: &IdTokenClaims,
+	) -> Result<Option<(String, UserType)>, Status> {
+		let providers = repositories.oidc_providers.as_ref();
+
+		if let Some((user_id, user_type)) = providers
+			.find_identity(&self.university_id, &claims.sub)
+			.await?
+		{
+			// The account may have been deleted or moved to another university since
+			return Ok(providers
+				.find_member(&self.university_id, AccountLookup::Id, &user_id)
+				.await?
+				.filter(|(_, member_type)| *member_type == user_type));
+		}
+
+		// Providers which don't vouch for the address could be used to take over the account
+		let Some(email) = &claims.email else {
+			return Ok(None);
+		};
+		if claims.email_verified != Some(true) {
+			return Ok(None);
+		}
+
+		let Some((user_id, user_type)) = providers
+			.find_member(&self.university_id, AccountLookup::Mail, email)
+			.await?
+		else {
+			return Ok(None);
+		};
+
+		providers
+			.link_identity(&self.university_id, &claims.sub, &user_id, user_type)
+			.await?;
+
+		// The provider vouched for the address, no need for the verification link anymore
+		repositories
+			.accounts
+			.set_mail_verified(user_type, &user_id)
+			.await?;
+
+		Ok(Some((user_id, user_type)))
+	}
+}
+
+fn redirect_uri() -> Result<String, Status> {
+	let frontend_url = &app_config()?.frontend_url;
+
+	Ok(format!("{}/oidc/callback", frontend_url.trim_end_matches('/')))
+}
+
+fn find_key(jwks: &JwkSet, kid: Option<&str>) -> Option<Jwk> {
+	match kid {
+		Some(kid) => jwks.find(kid),
+		None => jwks.keys.first(),
+	}
+	.cloned()
+}
+
+/// Client for a provider URL, refused unless it is `https` and every address of its host is
+/// public. The host is pinned to the checked addresses and redirects are not followed, so the
+/// request can't be sent elsewhere afterwards.
+async fn provider_client(url: &str) -> Result<Client, Status> {
+	let allow_insecure = oidc_config().allow_insecure_providers;
+	let parsed = Url::parse(url).map_err(provider_error(format!("Invalid provider URL {url}")))?;
+
+	if parsed.scheme() != "https" && !(allow_insecure && parsed.scheme() == "http") {
+		warn!("Provider URL {url} is not https");
+		return Err(Status::BadGateway);
+	}
+
+	let port = parsed.port_or_known_default().unwrap_or(443);
+	let (domain, addresses) = match parsed.host() {
+		Some(Host::Domain(domain)) => {
+			let addresses: Vec<SocketAddr> = lookup_host((domain, port))
+				.await
+				.map_err(provider_error(format!("Can't resolve {domain}")))?
+				.collect();
+			(Some(domain), addresses)
+		}
+		Some(Host::Ipv4(ip)) => (None, vec![SocketAddr::new(IpAddr::V4(ip), port)]),
+		Some(Host::Ipv6(ip)) => (None, vec![SocketAddr::new(IpAddr::V6(ip), port)]),
+		None => (None, vec![]),
+	};
+
+	if addresses.is_empty()
+		|| !(allow_insecure || addresses.iter().all(|address| is_public(address.ip())))
+	{
+		warn!("Provider URL {url} does not resolve to public addresses");
+		return Err(Status::BadGateway);
+	}
+
+	let mut builder = Client::builder()
+		.timeout(Duration::from_secs(OIDC_HTTP_TIMEOUT_SECONDS))
+		.redirect(Policy::none());
+	if let Some(domain) = domain {
+		builder = builder.resolve_to_addrs(domain, &addresses);
+	}
+
+	builder
+		.build()
+		.internal_server_error("Error while building the HTTP client")
+}
+
+/// Whether the address is outside of the loopback, private and link-local ranges.
+fn is_public(ip: IpAddr) -> bool {
+	match ip {
+		IpAddr::V4(ip) => {
+			!(ip.is_loopback() || ip.is_private() || ip.is_link_local() || ip.is_unspecified())
+		}
+		IpAddr::V6(ip) => match ip.to_ipv4_mapped() {
+			Some(ip) => is_public(IpAddr::V4(ip)),
+			None => {
+				!(ip.is_loopback()
+					|| ip.is_unique_local()
+					|| ip.is_unicast_link_local()
+					|| ip.is_unspecified())
+			}
+		},
+	}
+}
+
+async fn fetch_json<T: DeserializeOwned>(url: &str) -> Result<T, Status> {
+	provider_client(url)
+		.await?
+		.get(url)
+		.send()
+		.await
+		.and_then(reqwest::Response::error_for_status)
+		.map_err(provider_error(format!("Error while fetching {url}")))?
+		.json()
+		.await
+		.map_err(provider_error(format!("Invalid JSON from {url}")))
+}
+
+/// Documents fetched from the providers, by URL.
+#[derive(Debug)]
+struct ProviderCache<T> {
+	entries: Mutex<HashMap<String, (Instant, T)>>,
+}
+
+impl<T> Default for ProviderCache<T> {
+	fn default() -> Self {
+		Self {
+			entries: Mutex::new(HashMap::new()),
+		}
+	}
+}
+
+impl<T: Clone> ProviderCache<T> {
+	fn get(&self, url: &str) -> Option<T> {
+		let max_age = Duration::from_secs(oidc_config().cache_seconds);
+		let entries = self.entries.lock().ok()?;
+
+		entries
+			.get(url)
+			.filter(|(fetched_at, _)| fetched_at.elapsed() < max_age)
+			.map(|(_, value)| value.clone())
+	}
+
+	fn insert(&self, url: String, value: T) {
+		if let Ok(mut entries) = self.entries.lock() {
+			entries.insert(url, (Instant::now(), value));
+		}
+	}
+}
+
+/// Errors coming from the identity provider are reported as `502 Bad Gateway`.
+fn provider_error<E: std::fmt::Debug, M: ToString>(message: M) -> impl FnOnce(E) -> Status {
+	move |e| {
+		warn!("{} : {e:?}", message.to_string());
+		Status::BadGateway
+	}
+}
@@ -0,0 +1,88 @@
+use jsonwebtoken::get_current_timestamp;
+use rocket::http::Status;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::{
+	config::ttl,
+	redis::{consume_password_reset, invalidate_user_sessions, set_password_reset},
+	repositories::AccountRepository,
+	utils::{
+		crypto::{hash_password, is_password_valid},
+		mail::{Mailer, send_password_reset_mail},
+	},
+};
+
+use super::{JwtKeyring, UserType};
+
+const PASSWORD_RESET_PURPOSE: &str = "password_reset";
+
+#[derive(Debug, Serialize, Deserialize)]
+struct PasswordResetClaims {
+	sub: String,
+	user_type: UserType,
+	purpose: String,
+	exp: u64,
+	jti: String,
+}
+
+/// Mails a single-use reset link if an account of this type uses this address.
+///
+/// The returned account id is only meant for the audit log, the client must not be told whether
+/// the account exists.
+pub async fn request_password_reset(
+	keyring: &JwtKeyring,
+	accounts: &dyn AccountRepository,
+	mailer: &Mailer,
+	user_type: UserType,
+	mail: &str,
+) -> Result<Option<String>, Status> {
+	let Some(user_id) = accounts.find_id_by_mail(user_type, mail).await? else {
+		return Ok(None);
+	};
+
+	let ttl_seconds = ttl().password_reset_seconds;
+	let jti = Uuid::new_v4().to_string();
+	let claims = PasswordResetClaims {
+		sub: user_id,
+		user_type,
+		purpose: PASSWORD_RESET_PURPOSE.to_string(),
+		exp: get_current_timestamp() + ttl_seconds,
+		jti: jti.clone(),
+	};
+
+	let token = keyring.encode(&claims)?;
+
+	set_password_reset(&jti, ttl_seconds).await?;
+	send_password_reset_mail(mailer, mail, &token).await?;
+
+	Ok(Some(claims.sub))
+}
+
+/// Sets the new password if the token is valid and unused, then revokes every session of the
+/// user. Returns the account updated, or `None` if the token or the password is rejected.
+pub async fn reset_password(
+	keyring: &JwtKeyring,
+	accounts: &dyn AccountRepository,
+	token: &str,
+	new_password: &str,
+) -> Result<Option<(String, UserType)>, Status> {
+	if !is_password_valid(new_password) {
+		return Ok(None);
+	}
+
+	let Ok(claims) = keyring.decode::<PasswordResetClaims>(token, &["exp", "sub", "jti"]) else {
+		return Ok(None);
+	};
+
+	if claims.purpose != PASSWORD_RESET_PURPOSE || !consume_password_reset(&claims.jti).await? {
+		return Ok(None);
+	}
+
+	accounts
+		.update_password(claims.user_type, &claims.sub, &hash_password(new_password)?)
+		.await?;
+	invalidate_user_sessions(&claims.sub).await?;
+
+	Ok(Some((claims.sub, claims.user_type)))
+}
@@ -0,0 +1,35 @@
+//! Ownership rules between users and the resources they act on.
+//!
+//! Role checks are done by the typed guards (`AdminUser`, `UniversityUser`...), these functions
+//! answer the second question: does this user own the resource targeted by the request.
+
+use rocket::http::Status;
+
+use crate::{
+	models::users::{Company, University},
+	repositories::InternshipRepository,
+};
+
+/// Turns a failed ownership check into a `403 Forbidden`.
+pub const fn ensure(allowed: bool) -> Result<(), Status> {
+	if allowed {
+		Ok(())
+	} else {
+		Err(Status::Forbidden)
+	}
+}
+
+#[must_use]
+pub fn university_owns_class(university: &University, class_id: &str) -> bool {
+	university.has_class(class_id)
+}
+
+pub async fn company_owns_internship(
+	internships: &dyn InternshipRepository,
+	company: &Company,
+	internship_id: &str,
+) -> Result<bool, Status> {
+	internships
+		.is_owned_by_company(internship_id, &company.id)
+		.await
+}
@@ -0,0 +1,94 @@
+use rocket::http::Status;
+use tracing::warn;
+
+use crate::{
+	config::ttl,
+	redis::{
+		ConsumedRefreshToken, RefreshData, consume_refresh_token, extend_session,
+		invalidate_session, set_refresh_token,
+	},
+	utils::crypto::{generate_token, hash_token},
+};
+
+use super::UserType;
+
+#[must_use]
+pub fn refresh_token_ttl(remember_me: bool) -> u64 {
+	if remember_me {
+		ttl().remember_me_seconds
+	} else {
+		ttl().session_seconds
+	}
+}
+
+#[derive(Debug)]
+pub struct RotatedRefreshToken {
+	pub session_id: String,
+	pub user_type: UserType,
+	pub remember_me: bool,
+	pub refresh_token: String,
+}
+
+/// Creates a new refresh token for a session. The raw token is `{session_id}.{secret}`,
+/// only the hash of the whole token is kept in redis.
+pub async fn issue_refresh_token(
+	session_id: &str,
+	user_type: UserType,
+	remember_me: bool,
+) -> Result<String, Status> {
+	let refresh_token = format!("{session_id}.{}", generate_token());
+
+	set_refresh_token(
+		session_id,
+		&hash_token(&refresh_token),
+		&RefreshData {
+			user_type,
+			remember_me,
+		},
+		refresh_token_ttl(remember_me),
+	)
+	.await?;
+
+	Ok(refresh_token)
+}
+
+/// Exchanges a refresh token for a new one and extends the session.
+///
+/// Presenting a refresh token that was already rotated means it leaked: the whole session
+/// is revoked. The token is consumed atomically, of two concurrent refreshes with it the second
+/// one counts as such a reuse.
+pub async fn rotate_refresh_token(
+	refresh_token: &str,
+) -> Result<Option<RotatedRefreshToken>, Status> {
+	let Some((session_id, _)) = refresh_token.split_once('.') else {
+		return Ok(None);
+	};
+	let token_hash = hash_token(refresh_token);
+	// The used marker lives as long as the token replacing it, so the reuse is caught until then
+	let used_ttl_seconds = ttl().remember_me_seconds.max(ttl().session_seconds);
+
+	let refresh_data =
+		match consume_refresh_token(session_id, &token_hash, used_ttl_seconds).await? {
+			ConsumedRefreshToken::Fresh(refresh_data) => refresh_data,
+			ConsumedRefreshToken::Reused => {
+				warn!("Refresh token reuse detected, revoking session");
+				invalidate_session(session_id).await?;
+				return Ok(None);
+			}
+			ConsumedRefreshToken::Unknown => return Ok(None),
+		};
+
+	if !extend_session(session_id, refresh_token_ttl(refresh_data.remember_me)).await? {
+		return Ok(None);
+	}
+
+	let refresh_token =
+		issue_refresh_token(session_id, refresh_data.user_type, refresh_data.remember_me).await?;
+
+	Ok(Some(RotatedRefreshToken {
+		session_id: session_id.to_string(),
+		user_type: refresh_data.user_type,
+		remember_me: refresh_data.remember_me,
+		refresh_token,
+	}))
+}
@@ -0,0 +1,302 @@
+use rocket::{
+	Request,
+	http::Status,
+	request::{FromRequest, Outcome},
+};
+
+use crate::{
+	models::users::{Company, Student, University, admin::Admin},
+	repositories::Repositories,
+};
+
+use super::{
+	ApiKey, ApiKeyScope, AuthGuard, UserType, api_key::API_KEY_HEADER, policy::ensure,
+};
+
+/// Checks the role carried by the session of an authenticated request.
+async fn authorize_as<'r>(
+	request: &'r Request<'_>,
+	auth: AuthGuard,
+	user_type: UserType,
+) -> Outcome<(AuthGuard, String, &'r Repositories), String> {
+	if auth.user_type != user_type {
+		return Outcome::Error((Status::Forbidden, format!("Route reserved to {user_type}")));
+	}
+
+	let repositories = match Repositories::from_request(request) {
+		Ok(repositories) => repositories,
+		Err(e) => return Outcome::Error((e, "Repositories are not managed".to_string())),
+	};
+
+	match auth.get_user_id().await {
+		Ok(user_id) => Outcome::Success((auth, user_id, repositories)),
+		Err(e) => Outcome::Error((e, "Error while getting user id".to_string())),
+	}
+}
+
+/// Turns the lookup of the authenticated user into the outcome of a guard.
+fn loaded<T>(user: Result<Option<T>, Status>, user_type: UserType) -> Result<T, (Status, String)> {
+	match user {
+		Ok(Some(user)) => Ok(user),
+		// Deleted while the session was still open
+		Ok(None) => Err((Status::Unauthorized, format!("Unknown {user_type}"))),
+		Err(e) => Err((e, format!("Error while loading {user_type}"))),
+	}
+}
+
+#[derive(Debug)]
+pub struct AdminUser {
+	pub auth: AuthGuard,
+	pub admin: Admin,
+}
+
+#[async_trait]
+impl<'r> FromRequest<'r> for AdminUser {
+	type Error = String;
+
+	async fn from_request(request: &'r Request<'_>) -> Outcome<Self, Self::Error> {
+		match request.guard::<AuthGuard>().await {
+			Outcome::Success(auth) => Self::from_auth(request, auth).await,
+			Outcome::Error(e) => Outcome::Error(e),
+			Outcome::Forward(status) => Outcome::Forward(status),
+		}
+	}
+}
+
+impl AdminUser {
+	async fn from_auth(request: &Request<'_>, auth: AuthGuard) -> Outcome<Self, String> {
+		let (auth, user_id, repositories) = match authorize_as(request, auth, UserType::Admin).await
+		{
+			Outcome::Success(value) => value,
+			Outcome::Error(e) => return Outcome::Error(e),
+			Outcome::Forward(status) => return Outcome::Forward(status),
+		};
+
+		match loaded(
+			repositories.users.get_admin(&user_id).await,
+			UserType::Admin,
+		) {
+			Ok(admin) => Outcome::Success(Self { auth, admin }),
+			Err(e) => Outcome::Error(e),
+		}
+	}
+}
+
+#[derive(Debug)]
+pub struct UniversityUser {
+	pub auth: AuthGuard,
+	pub university: University,
+}
+
+#[async_trait]
+impl<'r> FromRequest<'r> for UniversityUser {
+	type Error = String;
+
+	async fn from_request(request: &'r Request<'_>) -> Outcome<Self, Self::Error> {
+		match request.guard::<AuthGuard>().await {
+			Outcome::Success(auth) => Self::from_auth(request, auth).await,
+			Outcome::Error(e) => Outcome::Error(e),
+			Outcome::Forward(status) => Outcome::Forward(status),
+		}
+	}
+}
+
+impl UniversityUser {
+	async fn from_auth(request: &Request<'_>, auth: AuthGuard) -> Outcome<Self, String> {
+		let (auth, user_id, repositories) =
+			match authorize_as(request, auth, UserType::University).await {
+				Outcome::Success(value) => value,
+				Outcome::Error(e) => return Outcome::Error(e),
+				Outcome::Forward(status) => return Outcome::Forward(status),
+			};
+
+		match loaded(
+			repositories.users.get_university(&user_id).await,
+			UserType::University,
+		) {
+			Ok(university) => Outcome::Success(Self { auth, university }),
+			Err(e) => Outcome::Error(e),
+		}
+	}
+}
+
+#[derive(Debug)]
+pub struct StudentUser {
+	pub auth: AuthGuard,
+	pub student: Student,
+}
+
+#[async_trait]
+impl<'r> FromRequest<'r> for StudentUser {
+	type Error = String;
+
+	async fn from_request(request: &'r Request<'_>) -> Outcome<Self, Self::Error> {
+		match request.guard::<AuthGuard>().await {
+			Outcome::Success(auth) => Self::from_auth(request, auth).await,
+			Outcome::Error(e) => Outcome::Error(e),
+			Outcome::Forward(status) => Outcome::Forward(status),
+		}
+	}
+}
+
+impl StudentUser {
+	async fn from_auth(request: &Request<'_>, auth: AuthGuard) -> Outcome<Self, String> {
+		let (auth, user_id, repositories) =
+			match authorize_as(request, auth, UserType::Student).await {
+				Outcome::Success(value) => value,
+				Outcome::Error(e) => return Outcome::Error(e),
+				Outcome::Forward(status) => return Outcome::Forward(status),
+			};
+
+		match loaded(
+			repositories.users.get_student(&user_id).await,
+			UserType::Student,
+		) {
+			Ok(student) => Outcome::Success(Self { auth, student }),
+			Err(e) => Outcome::Error(e),
+		}
+	}
+}
+
+/// Company authenticated either by a session or by one of its API keys.
+#[derive(Debug)]
+pub struct CompanyUser {
+	/// `None` when the request is authenticated with an API key
+	pub auth: Option<AuthGuard>,
+	pub api_key: Option<ApiKey>,
+	pub company: Company,
+}
+
+impl CompanyUser {
+	/// Sessions have every right of the company, API keys only the scopes they were given.
+	pub fn require_scope(&self, scope: ApiKeyScope) -> Result<(), Status> {
+		ensure(self.api_key.as_ref().is_none_or(|api_key| api_key.has_scope(scope)))
+	}
+
+	/// Admin using the session, see `AuthGuard::impersonator`.
+	pub fn impersonator(&self) -> Option<&str> {
+		self.auth
+			.as_ref()
+			.and_then(|auth| auth.impersonator.as_deref())
+	}
+
+	/// Rejects API keys and impersonated sessions, e.g. so a key can't be used to manage keys.
+	pub fn require_session(&self) -> Result<(), Status> {
+		ensure(
+			self.auth
+				.as_ref()
+				.is_some_and(|auth| auth.impersonator.is_none()),
+		)
+	}
+
+	async fn from_api_key(repositories: &Repositories, raw_key: &str) -> Outcome<Self, String> {
+		let api_key = match ApiKey::authenticate(repositories.api_keys.as_ref(), raw_key).await {
+			Ok(Some(api_key)) => api_key,
+			Ok(None) => {
+				return Outcome::Error((Status::Unauthorized, "Invalid API key".to_string()));
+			}
+			Err(e) => return Outcome::Error((e, "Error while checking API key".to_string())),
+		};
+
+		match repositories
+			.accounts
+			.ensure_active(UserType::Company, &api_key.company_id)
+			.await
+		{
+			Ok(()) => {}
+			Err(e) => return Outcome::Error((e, "Company account is not active".to_string())),
+		}
+
+		let company = repositories.users.get_company(&api_key.company_id).await;
+		match loaded(company, UserType::Company) {
+			Ok(company) => Outcome::Success(Self {
+				auth: None,
+				api_key: Some(api_key),
+				company,
+			}),
+			Err(e) => Outcome::Error(e),
+		}
+	}
+}
+
+#[async_trait]
+impl<'r> FromRequest<'r> for CompanyUser {
+	type Error = String;
+
+	async fn from_request(request: &'r Request<'_>) -> Outcome<Self, Self::Error> {
+		let headers = request.headers();
+		if !headers.contains("Authorization")
+			&& let Some(raw_key) = headers.get_one(API_KEY_HEADER)
+		{
+			return match Repositories::from_request(request) {
+				Ok(repositories) => Self::from_api_key(repositories, raw_key).await,
+				Err(e) => Outcome::Error((e, "Repositories are not managed".to_string())),
+			};
+		}
+
+		match request.guard::<AuthGuard>().await {
+			Outcome::Success(auth) => Self::from_auth(request, auth).await,
+			Outcome::Error(e) => Outcome::Error(e),
+			Outcome::Forward(status) => Outcome::Forward(status),
+		}
+	}
+}
+
+impl CompanyUser {
+	async fn from_auth(request: &Request<'_>, auth: AuthGuard) -> Outcome<Self, String> {
+		let (auth, user_id, repositories) =
+			match authorize_as(request, auth, UserType::Company).await {
+				Outcome::Success(value) => value,
+				Outcome::Error(e) => return Outcome::Error(e),
+				Outcome::Forward(status) => return Outcome::Forward(status),
+			};
+
+		match loaded(
+			repositories.users.get_company(&user_id).await,
+			UserType::Company,
+		) {
+			Ok(company) => Outcome::Success(Self {
+				auth: Some(auth),
+				api_key: None,
+				company,
+			}),
+			Err(e) => Outcome::Error(e),
+		}
+	}
+}
+
+/// Authenticated user resolved to the typed guard of its role, for the routes which serve several
+/// roles differently.
+#[derive(Debug)]
+pub enum RoleUser {
+	Admin(AdminUser),
+	University(UniversityUser),
+	Student(StudentUser),
+	Company(CompanyUser),
+}
+
+#[async_trait]
+impl<'r> FromRequest<'r> for RoleUser {
+	type Error = String;
+
+	async fn from_request(request: &'r Request<'_>) -> Outcome<Self, Self::Error> {
+		let auth = match request.guard::<AuthGuard>().await {
+			Outcome::Success(auth) => auth,
+			Outcome::Error(e) => return Outcome::Error(e),
+			Outcome::Forward(status) => return Outcome::Forward(status),
+		};
+
+		match auth.user_type {
+			UserType::Admin => AdminUser::from_auth(request, auth).await.map(Self::Admin),
+			UserType::University => UniversityUser::from_auth(request, auth)
+				.await
+				.map(Self::University),
+			UserType::Student => StudentUser::from_auth(request, auth)
+				.await
+				.map(Self::Student),
+			UserType::Company => CompanyUser::from_auth(request, auth)
+				.await
+				.map(Self::Company),
+		}
+	}
+}
@@ -0,0 +1,147 @@
+//! Cookie transport of the session, for the web frontend which should not keep the tokens in
+//! JavaScript.
+//!
+//! The access and refresh tokens travel in private (encrypted) `HttpOnly` cookies. As the browser
+//! sends them on its own, the requests which change something must also repeat the value of the
+//! readable CSRF cookie in the `X-CSRF-Token` header (double-submit).
+
+use std::convert::Infallible;
+
+use rocket::{
+	Request,
+	http::{Cookie, CookieJar, Method, SameSite, Status},
+	request::{FromRequest, Outcome},
+	time::Duration,
+};
+
+use crate::{config::ttl, utils::crypto::generate_token};
+
+pub(super) const SESSION_COOKIE: &str = "mosifra_session";
+const REFRESH_COOKIE: &str = "mosifra_refresh";
+const CSRF_COOKIE: &str = "mosifra_csrf";
+const CSRF_HEADER: &str = "X-CSRF-Token";
+// The refresh token is only needed by a single route, it doesn't have to go everywhere
+const REFRESH_COOKIE_PATH: &str = "/auth/refresh";
+
+/// What the client receives in the body of the response.
+#[derive(Debug)]
+pub struct SessionTokens {
+	pub jwt: Option<String>,
+	pub refresh_token: Option<String>,
+	pub csrf_token: Option<String>,
+}
+
+/// Returns the tokens of the session to the client, or moves them into cookies if asked to.
+pub fn deliver_session_tokens(
+	cookies: &CookieJar<'_>,
+	use_cookie: bool,
+	jwt: String,
+	refresh_token: String,
+	refresh_ttl_seconds: u64,
+) -> SessionTokens {
+	if use_cookie {
+		SessionTokens {
+			jwt: None,
+			refresh_token: None,
+			csrf_token: Some(set_session_cookies(
+				cookies,
+				jwt,
+				refresh_token,
+				refresh_ttl_seconds,
+			)),
+		}
+	} else {
+		SessionTokens {
+			jwt: Some(jwt),
+			refresh_token: Some(refresh_token),
+			csrf_token: None,
+		}
+	}
+}
+
+/// Stores the tokens of a new or refreshed session and returns the CSRF token the client must
+/// send back in the `X-CSRF-Token` header.
+fn set_session_cookies(
+	cookies: &CookieJar<'_>,
+	jwt: String,
+	refresh_token: String,
+	refresh_ttl_seconds: u64,
+) -> String {
+	let csrf_token = generate_token();
+
+	cookies.add_private(
+		Cookie::build((SESSION_COOKIE, jwt))
+			.http_only(true)
+			.secure(true)
+			.same_site(SameSite::Strict)
+			.max_age(max_age(ttl().access_token_seconds)),
+	);
+	cookies.add_private(
+		Cookie::build((REFRESH_COOKIE, refresh_token))
+			.path(REFRESH_COOKIE_PATH)
+			.http_only(true)
+			.secure(true)
+			.same_site(SameSite::Strict)
+			.max_age(max_age(refresh_ttl_seconds)),
+	);
+	cookies.add(
+		Cookie::build((CSRF_COOKIE, csrf_token.clone()))
+			.http_only(false)
+			.secure(true)
+			.same_site(SameSite::Strict)
+			.max_age(max_age(refresh_ttl_seconds)),
+	);
+
+	csrf_token
+}
+
+fn max_age(ttl_seconds: u64) -> Duration {
+	Duration::seconds(i64::try_from(ttl_seconds).unwrap_or(i64::MAX))
+}
+
+pub fn clear_session_cookies(cookies: &CookieJar<'_>) {
+	cookies.remove_private(SESSION_COOKIE);
+	cookies.remove_private(Cookie::build(REFRESH_COOKIE).path(REFRESH_COOKIE_PATH));
+	cookies.remove(CSRF_COOKIE);
+}
+
+#[must_use]
+pub fn get_refresh_cookie(cookies: &CookieJar<'_>) -> Option<String> {
+	cookies
+		.get_private(REFRESH_COOKIE)
+		.map(|cookie| cookie.value().to_string())
+}
+
+/// Whether a request carried by the cookies really comes from the frontend.
+pub(super) fn is_csrf_valid(request: &Request<'_>) -> bool {
+	if matches!(request.method(), Method::Get | Method::Head | Method::Options) {
+		return true;
+	}
+
+	let Some(cookie) = request.cookies().get(CSRF_COOKIE) else {
+		return false;
+	};
+
+	request
+		.headers()
+		.get_one(CSRF_HEADER)
+		.is_some_and(|header| !header.is_empty() && header == cookie.value())
+}
+
+/// Successful when the request passes the double-submit check, for the routes which read the
+/// cookies without going through `AuthGuard`.
+#[derive(Debug)]
+pub struct CsrfChecked;
+
+#[async_trait]
+impl<'r> FromRequest<'r> for CsrfChecked {
+	type Error = Infallible;
+
+	async fn from_request(request: &'r Request<'_>) -> Outcome<Self, Self::Error> {
+		if is_csrf_valid(request) {
+			Outcome::Success(Self)
+		} else {
+			Outcome::Forward(Status::Forbidden)
+		}
+	}
+}
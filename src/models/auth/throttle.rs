@@ -0,0 +1,151 @@
+use std::net::IpAddr;
+
+use rocket::http::Status;
+
+use crate::redis::{get_lock_ttl, increment_attempts, reset_attempts, set_lock};
+
+use super::UserType;
+
+const MAX_TWOFA_ATTEMPTS: u64 = 5;
+const TWOFA_WINDOW_SECONDS: u64 = 900;
+
+const MAX_LOGIN_ATTEMPTS: u64 = 5;
+const LOGIN_WINDOW_SECONDS: u64 = 24 * 3600;
+const LOGIN_LOCK_BASE_SECONDS: u64 = 60;
+const LOGIN_LOCK_MAX_SECONDS: u64 = 3600;
+
+const MAX_IP_ATTEMPTS: u64 = 50;
+const IP_WINDOW_SECONDS: u64 = 900;
+const IP_LOCK_SECONDS: u64 = 900;
+
+const MAX_RESET_REQUESTS_PER_MAIL: u64 = 3;
+const MAX_RESET_REQUESTS_PER_IP: u64 = 20;
+const RESET_WINDOW_SECONDS: u64 = 3600;
+
+/// Redis backed failure counters for the login and 2FA steps.
+///
+/// Failures are counted per login, per account for the 2FA codes, per client IP and per 2FA
+/// transaction.
+#[derive(Debug)]
+pub struct Throttle {
+	account_key: Option<String>,
+	ip_key: Option<String>,
+}
+
+impl Throttle {
+	#[must_use]
+	pub fn for_login(user_type: UserType, login: &str, ip: Option<IpAddr>) -> Self {
+		Self {
+			account_key: Some(format!("login:{user_type}:{login}")),
+			ip_key: ip.map(|ip| format!("ip:{ip}")),
+		}
+	}
+
+	/// Wrong 2FA codes of the account, across its transactions.
+	///
+	/// Kept apart from the password failures, which a right password clears: each new transaction
+	/// would otherwise bring a fresh set of guesses.
+	#[must_use]
+	pub fn for_twofa(user_type: UserType, user_id: &str, ip: Option<IpAddr>) -> Self {
+		Self {
+			account_key: Some(format!("twofa_account:{user_type}:{user_id}")),
+			ip_key: ip.map(|ip| format!("ip:{ip}")),
+		}
+	}
+
+	#[must_use]
+	pub fn for_ip(ip: Option<IpAddr>) -> Self {
+		Self {
+			account_key: None,
+			ip_key: ip.map(|ip| format!("ip:{ip}")),
+		}
+	}
+
+	/// Returns the remaining lock time in seconds if the account or the IP is locked.
+	pub async fn locked_for(&self) -> Result<Option<u64>, Status> {
+		let mut locked_for = None;
+
+		for key in self.keys() {
+			locked_for = locked_for.max(get_lock_ttl(key).await?);
+		}
+
+		Ok(locked_for)
+	}
+
+	/// Counts a failed attempt and returns the lock time if this failure triggered a lock.
+	///
+	/// Account locks grow exponentially with each failure past the threshold.
+	pub async fn register_failure(&self) -> Result<Option<u64>, Status> {
+		let mut locked_for = None;
+
+		if let Some(key) = &self.account_key {
+			let attempts = increment_attempts(key, LOGIN_WINDOW_SECONDS).await?;
+			if attempts >= MAX_LOGIN_ATTEMPTS {
+				let exponent = u32::try_from(attempts - MAX_LOGIN_ATTEMPTS).unwrap_or(u32::MAX);
+				let lock = LOGIN_LOCK_BASE_SECONDS
+					.saturating_mul(2_u64.saturating_pow(exponent))
+					.min(LOGIN_LOCK_MAX_SECONDS);
+				set_lock(key, lock).await?;
+				locked_for = Some(lock);
+			}
+		}
+
+		if let Some(key) = &self.ip_key
+			&& increment_attempts(key, IP_WINDOW_SECONDS).await? >= MAX_IP_ATTEMPTS
+		{
+			set_lock(key, IP_LOCK_SECONDS).await?;
+			locked_for = locked_for.max(Some(IP_LOCK_SECONDS));
+		}
+
+		Ok(locked_for)
+	}
+
+	/// Clears the failures of the account after a success.
+	///
+	/// The IP counter is kept, a client guessing at many accounts could clear it with its own.
+	pub async fn reset(&self) -> Result<(), Status> {
+		if let Some(key) = &self.account_key {
+			reset_attempts(key).await?;
+		}
+
+		Ok(())
+	}
+
+	fn keys(&self) -> impl Iterator<Item = &String> {
+		self.account_key.iter().chain(self.ip_key.iter())
+	}
+}
+
+/// Counts a 2FA attempt before its code is checked and returns the attempts left after it, `None`
+/// once the limit is passed.
+///
+/// Counting first keeps parallel requests from getting more guesses than the limit.
+pub async fn register_twofa_attempt(transaction_id: &str) -> Result<Option<u64>, Status> {
+	let attempts =
+		increment_attempts(&format!("twofa:{transaction_id}"), TWOFA_WINDOW_SECONDS).await?;
+
+	Ok(MAX_TWOFA_ATTEMPTS.checked_sub(attempts))
+}
+
+/// Counts a password reset request for the address and the client IP, `false` once either is past
+/// its limit for the window.
+///
+/// Every request counts, whether the address is known or not, so the limit tells nothing about
+/// the accounts.
+pub async fn register_reset_request(mail: &str, ip: Option<IpAddr>) -> Result<bool, Status> {
+	let mail = mail.trim().to_lowercase();
+	let mut allowed = increment_attempts(&format!("reset:mail:{mail}"), RESET_WINDOW_SECONDS)
+		.await?
+		<= MAX_RESET_REQUESTS_PER_MAIL;
+
+	if let Some(ip) = ip {
+		allowed &= increment_attempts(&format!("reset:ip:{ip}"), RESET_WINDOW_SECONDS).await?
+			<= MAX_RESET_REQUESTS_PER_IP;
+	}
+
+	Ok(allowed)
+}
+
+pub async fn reset_twofa_failures(transaction_id: &str) -> Result<(), Status> {
+	reset_attempts(&format!("twofa:{transaction_id}")).await
+}
@@ -0,0 +1,148 @@
+use std::{fmt::Display, str::FromStr};
+
+use rocket::http::Status;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+	error_handling::StatusOptionHandling,
+	repositories::TwofaRepository,
+	utils::crypto::{generate_token, generate_totp_secret, hash_token, verify_totp_code},
+};
+
+const RECOVERY_CODES_COUNT: usize = 10;
+const RECOVERY_CODE_LENGTH: usize = 10;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TwofaMethod {
+	#[default]
+	Mail,
+	Totp,
+}
+
+impl Display for TwofaMethod {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self {
+			Self::Mail => write!(f, "mail"),
+			Self::Totp => write!(f, "totp"),
+		}
+	}
+}
+
+impl FromStr for TwofaMethod {
+	type Err = Status;
+
+	fn from_str(value: &str) -> Result<Self, Self::Err> {
+		match value {
+			"mail" => Ok(Self::Mail),
+			"totp" => Ok(Self::Totp),
+			_ => Err(Status::InternalServerError),
+		}
+	}
+}
+
+/// Stored by the `TwofaRepository`.
+#[derive(Debug, Clone, Default)]
+pub struct TwofaSettings {
+	pub user_id: String,
+	pub method: TwofaMethod,
+	pub totp_secret: Option<String>,
+	pub totp_pending_secret: Option<String>,
+	/// Time step of the last TOTP code accepted, older and equal ones are refused
+	pub totp_last_step: Option<i64>,
+}
+
+impl TwofaSettings {
+	/// Starts a TOTP enrollment. The current method stays active until the secret is confirmed.
+	pub async fn start_totp_enrollment(
+		&mut self,
+		twofa: &dyn TwofaRepository,
+	) -> Result<String, Status> {
+		let secret = generate_totp_secret();
+
+		twofa
+			.set_pending_secret(&self.user_id, self.method, &secret)
+			.await?;
+
+		self.totp_pending_secret = Some(secret.clone());
+
+		Ok(secret)
+	}
+
+	/// Activates the pending TOTP secret if the code matches and returns fresh recovery codes.
+	pub async fn confirm_totp_enrollment(
+		&mut self,
+		twofa: &dyn TwofaRepository,
+		code: &str,
+	) -> Result<Option<Vec<String>>, Status> {
+		let Some(secret) = self.totp_pending_secret.clone() else {
+			return Ok(None);
+		};
+
+		let Some(step) = verify_totp_code(&secret, code)? else {
+			return Ok(None);
+		};
+		let step = totp_step(step)?;
+
+		twofa.activate_totp(&self.user_id, &secret, step).await?;
+
+		self.method = TwofaMethod::Totp;
+		self.totp_secret = Some(secret);
+		self.totp_pending_secret = None;
+		self.totp_last_step = Some(step);
+
+		Ok(Some(self.regenerate_recovery_codes(twofa).await?))
+	}
+
+	pub async fn disable_totp(&mut self, twofa: &dyn TwofaRepository) -> Result<(), Status> {
+		twofa.delete(&self.user_id).await?;
+
+		self.method = TwofaMethod::Mail;
+		self.totp_secret = None;
+		self.totp_pending_secret = None;
+		self.totp_last_step = None;
+
+		Ok(())
+	}
+
+	/// Checks a TOTP code, falling back on single-use recovery codes.
+	///
+	/// A TOTP code is accepted once: its time step must be after the last one accepted.
+	pub async fn verify_code(
+		&self,
+		twofa: &dyn TwofaRepository,
+		code: &str,
+	) -> Result<bool, Status> {
+		if let Some(secret) = &self.totp_secret
+			&& let Some(step) = verify_totp_code(secret, code)?
+		{
+			return twofa.use_totp_step(&self.user_id, totp_step(step)?).await;
+		}
+
+		twofa
+			.use_recovery_code(&self.user_id, &hash_token(code.trim()))
+			.await
+	}
+
+	pub async fn regenerate_recovery_codes(
+		&self,
+		twofa: &dyn TwofaRepository,
+	) -> Result<Vec<String>, Status> {
+		let codes: Vec<String> = (0..RECOVERY_CODES_COUNT)
+			.map(|_| generate_token()[..RECOVERY_CODE_LENGTH].to_string())
+			.collect();
+		let code_hashes: Vec<String> = codes.iter().map(|code| hash_token(code)).collect();
+
+		twofa
+			.replace_recovery_codes(&self.user_id, &code_hashes)
+			.await?;
+
+		Ok(codes)
+	}
+}
+
+fn totp_step(step: u64) -> Result<i64, Status> {
+	i64::try_from(step)
+		.ok()
+		.internal_server_error("TOTP step out of range")
+}
@@ -3,7 +3,7 @@ use std::{fmt::Display, str::FromStr};
 use rocket::http::Status;
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
 pub enum UserType {
 	Admin,
@@ -12,6 +12,19 @@ pub enum UserType {
 	Company,
 }
 
+impl UserType {
+	/// Name of the postgres table holding this kind of user.
+	#[must_use]
+	pub const fn table_name(&self) -> &'static str {
+		match self {
+			Self::Admin => "admin",
+			Self::University => "university",
+			Self::Student => "student",
+			Self::Company => "company",
+		}
+	}
+}
+
 impl Display for UserType {
 	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
 		match self {
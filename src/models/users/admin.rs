@@ -1,2 +1,7 @@
-#[derive(Debug, Default)]
-pub struct Admin {}
+#[derive(Debug, Clone, Default)]
+pub struct Admin {
+	pub id: String,
+	pub login: String,
+	pub password: String,
+	pub mail: String,
+}
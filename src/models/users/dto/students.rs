@@ -1,4 +1,3 @@
-use rocket::http::Status;
 use serde::Serialize;
 
 use crate::models::users::Student;
@@ -10,14 +9,12 @@ pub struct StudentDto {
 	pub last_name: String,
 }
 
-impl StudentDto {
-	pub async fn from_id(id: String) -> Result<Self, Status> {
-		let student = Student::from_id(id).await?;
-
-		Ok(Self {
+impl From<Student> for StudentDto {
+	fn from(student: Student) -> Self {
+		Self {
 			mail: student.mail,
 			first_name: student.first_name,
 			last_name: student.last_name,
-		})
+		}
 	}
 }
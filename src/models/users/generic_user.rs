@@ -1,13 +1,14 @@
 use std::any::Any;
 
-use rocket::{http::Status, serde::json::Json};
+use rocket::http::Status;
 
-use crate::{error_handling::StatusOptionHandling, redis, routes::auth::DisconnectResponse};
+use crate::{error_handling::StatusOptionHandling, redis};
 
 use super::{Company, Student, University, admin::Admin};
 
 pub struct GenericUser {
 	session_id: String,
+	impersonator: Option<String>,
 	inner: Box<dyn Any + Send>,
 }
 
@@ -16,9 +17,22 @@ impl GenericUser {
 		Self {
 			inner: Box::new(value),
 			session_id,
+			impersonator: None,
 		}
 	}
 
+	#[must_use]
+	pub fn with_impersonator(mut self, impersonator: Option<String>) -> Self {
+		self.impersonator = impersonator;
+		self
+	}
+
+	/// Id of the admin using the session on behalf of this user.
+	#[must_use]
+	pub fn get_impersonator(&self) -> Option<&str> {
+		self.impersonator.as_deref()
+	}
+
 	pub fn is_university(&self) -> bool {
 		self.inner.is::<University>()
 	}
@@ -56,11 +70,34 @@ impl GenericUser {
 	pub fn to_admin(&self) -> Result<&Admin, Status> {
 		self.inner
 			.downcast_ref::<Admin>()
-			.internal_server_error("Cannot convert GenericUser to Company")
+			.internal_server_error("Cannot convert GenericUser to Admin")
+	}
+
+	pub fn get_id(&self) -> Result<&str, Status> {
+		if let Some(admin) = self.inner.downcast_ref::<Admin>() {
+			Ok(&admin.id)
+		} else if let Some(university) = self.inner.downcast_ref::<University>() {
+			Ok(&university.id)
+		} else if let Some(student) = self.inner.downcast_ref::<Student>() {
+			Ok(&student.id)
+		} else {
+			Ok(&self.to_company()?.id)
+		}
+	}
+
+	pub fn get_login(&self) -> Result<&str, Status> {
+		if let Some(admin) = self.inner.downcast_ref::<Admin>() {
+			Ok(&admin.login)
+		} else if let Some(university) = self.inner.downcast_ref::<University>() {
+			Ok(&university.login)
+		} else if let Some(student) = self.inner.downcast_ref::<Student>() {
+			Ok(&student.login)
+		} else {
+			Ok(&self.to_company()?.login)
+		}
 	}
 
-	pub fn logout(&self) -> Result<Json<DisconnectResponse>, Status> {
-		redis::invalidate_session(&self.session_id)?;
-		Ok(Json(DisconnectResponse { success: true }))
+	pub async fn logout(self) -> Result<(), Status> {
+		redis::invalidate_session(&self.session_id).await
 	}
 }
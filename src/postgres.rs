@@ -1,56 +1,125 @@
+use std::{
+	fs,
+	time::{Duration, Instant},
+};
+
+use deadpool_postgres::{Manager, ManagerConfig, Object, Pool, RecyclingMethod, Runtime};
+use native_tls::{Certificate, TlsConnector};
+use postgres_native_tls::MakeTlsConnector;
 use rocket::http::Status;
-use tokio_postgres::{Client, NoTls};
+use tokio_postgres::{Error, NoTls, Row, types::ToSql};
+use tracing::{debug_span, warn};
 
-use crate::error_handling::StatusResultHandling;
+use crate::{config::DatabaseConfig, logging::timed, metrics::record_query};
 
-pub async fn setup_database() -> Result<Client, Status> {
-	let database_url =
-		std::env::var("DATABASE_URL").internal_server_error("DATABASE_URL missing")?;
-	let (client, connection) = tokio_postgres::connect(&database_url, NoTls)
-		.await
-		.internal_server_error("Error connection to prostgres failed")?;
+/// Builds the pool, connections are only opened when first needed.
+pub fn create_pool(config: &DatabaseConfig) -> Result<Pool, String> {
+	let mut pg_config: tokio_postgres::Config = config
+		.url
+		.expose()
+		.parse()
+		.map_err(|e| format!("Invalid DATABASE_URL: {e}"))?;
+	pg_config.connect_timeout(Duration::from_secs(config.connect_timeout_seconds));
 
-	tokio::spawn(async move {
-		let _ = connection.await.internal_server_error("Connection error");
-	});
-	Ok(client)
+	let manager_config = ManagerConfig {
+		recycling_method: RecyclingMethod::Fast,
+	};
+	let manager = if config.tls {
+		Manager::from_config(pg_config, make_tls_connector(config)?, manager_config)
+	} else {
+		Manager::from_config(pg_config, NoTls, manager_config)
+	};
+
+	let timeout = Some(Duration::from_secs(config.pool_timeout_seconds));
+	Pool::builder(manager)
+		.max_size(config.pool_size)
+		.wait_timeout(timeout)
+		.create_timeout(Some(Duration::from_secs(config.connect_timeout_seconds)))
+		.recycle_timeout(timeout)
+		.runtime(Runtime::Tokio1)
+		.build()
+		.map_err(|e| format!("Can't build the postgres pool: {e}"))
 }
 
-#[async_trait]
-pub trait Db {
-	#[must_use]
-	async fn setup_database() -> Result<Client, Status> {
-		setup_database().await
+fn make_tls_connector(config: &DatabaseConfig) -> Result<MakeTlsConnector, String> {
+	let mut builder = TlsConnector::builder();
+
+	if let Some(path) = &config.ca_cert {
+		let pem = fs::read(path).map_err(|e| format!("Can't read DATABASE_CA_CERT: {e}"))?;
+		let certificate =
+			Certificate::from_pem(&pem).map_err(|e| format!("Invalid DATABASE_CA_CERT: {e}"))?;
+		builder.add_root_certificate(certificate);
 	}
 
-	async fn insert(&self) -> Result<(), Status> {
-		unimplemented!("Need to refacto this system")
+	let connector = builder
+		.build()
+		.map_err(|e| format!("Can't build the postgres TLS connector: {e}"))?;
+
+	Ok(MakeTlsConnector::new(connector))
+}
+
+/// Pooled connection whose queries run in a `postgres` span, with their statement but not their
+/// parameters, and are timed in the metrics.
+pub struct Database(Object);
+
+impl Database {
+	pub async fn query(
+		&self,
+		statement: &str,
+		params: &[&(dyn ToSql + Sync)],
+	) -> Result<Vec<Row>, Error> {
+		observed(statement, self.0.query(statement, params)).await
 	}
 
-	#[allow(unused_variables)]
-	async fn login(login: &str, password: &str) -> Result<Option<Self>, Status>
-	where
-		Self: Sized,
-	{
-		unimplemented!("Only for structs that represents users")
+	pub async fn query_one(
+		&self,
+		statement: &str,
+		params: &[&(dyn ToSql + Sync)],
+	) -> Result<Row, Error> {
+		observed(statement, self.0.query_one(statement, params)).await
 	}
 
-	#[allow(unused_variables)]
-	async fn get_name(&self, user_id: String) -> Result<String, Status> {
-		unimplemented!("For Company struct only")
+	pub async fn query_opt(
+		&self,
+		statement: &str,
+		params: &[&(dyn ToSql + Sync)],
+	) -> Result<Option<Row>, Error> {
+		observed(statement, self.0.query_opt(statement, params)).await
 	}
 
-	async fn delete(&self) -> Result<(), Status> {
-		unimplemented!("Need implementation")
+	pub async fn execute(
+		&self,
+		statement: &str,
+		params: &[&(dyn ToSql + Sync)],
+	) -> Result<u64, Error> {
+		observed(statement, self.0.execute(statement, params)).await
 	}
 }
 
-pub async fn is_login_taken(username: &str) -> Result<bool, Status> {
-	let client = setup_database().await?;
-	let row = client
-		.query_opt("SELECT 1 FROM student WHERE login=$1;", &[&username])
-		.await
-		.internal_server_error("Error during selection of login to check if login is taken")?;
+/// Runs `call` in a `postgres` span and records its duration in the metrics.
+async fn observed<F: Future>(statement: &str, call: F) -> F::Output {
+	let start = Instant::now();
+	let output = timed(debug_span!("postgres", statement), call).await;
+	record_query(start.elapsed());
+
+	output
+}
 
-	Ok(row.is_some())
+pub async fn setup_database(pool: &Pool) -> Result<Database, Status> {
+	// The pool is exhausted or the database is down, the client can come back later
+	pool.get().await.map(Database).map_err(|e| {
+		warn!("Can't get a postgres connection : {e:?}");
+		Status::ServiceUnavailable
+	})
+}
+
+/// Readiness probe, a pooled connection has to answer a query.
+pub async fn ping_database(pool: &Pool) -> Result<(), String> {
+	let client = pool.get().await.map(Database).map_err(|e| e.to_string())?;
+
+	client
+		.execute("SELECT 1;", &[])
+		.await
+		.map(|_| ())
+		.map_err(|e| e.to_string())
 }
@@ -1,120 +1,547 @@
-use redis::{Connection, TypedCommands};
+use std::{sync::OnceLock, time::Duration};
+
+use chrono::{DateTime, Utc};
+use redis::{
+	Arg, AsyncTypedCommands, Cmd, IntegerReplyOrNoOp, Pipeline, RedisFuture, RedisResult, Value,
+	aio::{ConnectionLike, ConnectionManager, ConnectionManagerConfig},
+};
 use rocket::http::Status;
 use serde::{Deserialize, Serialize};
+use tracing::{debug_span, error, warn};
 use uuid::Uuid;
 
 use crate::{
+	config::{RedisConfig, ttl},
 	error_handling::{StatusOptionHandling, StatusResultHandling},
+	logging::timed,
+	models::auth::{ClientInfo, TwofaMethod, UserType},
 	routes::auth::TwofaPayload,
 };
 
 #[derive(Debug, Serialize, Deserialize)]
 struct LoginTransaction {
 	user_id: String,
+	user_type: UserType,
+	method: TwofaMethod,
 	code: String,
 }
 
+// last_seen is only written again once it is older than this, not on every request
+const SESSION_TOUCH_INTERVAL_SECONDS: i64 = 60;
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct SessionData {
 	pub user_id: String,
+	/// Restricted sessions can only be used to set a new password.
+	#[serde(default)]
+	pub restricted: bool,
+	#[serde(default)]
+	pub created_at: DateTime<Utc>,
+	#[serde(default)]
+	pub last_seen: DateTime<Utc>,
+	#[serde(default)]
+	pub user_agent: Option<String>,
+	#[serde(default)]
+	pub ip: Option<String>,
+	/// Id of the admin using this session on behalf of the user
+	#[serde(default)]
+	pub impersonator: Option<String>,
+}
+
+impl SessionData {
+	#[must_use]
+	pub fn new(user_id: String, restricted: bool, client: &ClientInfo) -> Self {
+		let now = Utc::now();
+
+		Self {
+			user_id,
+			restricted,
+			created_at: now,
+			last_seen: now,
+			user_agent: client.user_agent.clone(),
+			ip: client.ip.map(|ip| ip.to_string()),
+			impersonator: None,
+		}
+	}
+}
+
+/// Kept under `refresh:{session_id}:{token_hash}` until the token is presented.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RefreshData {
+	pub user_type: UserType,
+	pub remember_me: bool,
+}
+
+/// What was found under a presented refresh token.
+#[derive(Debug)]
+pub enum ConsumedRefreshToken {
+	/// First presentation, the token is now used
+	Fresh(RefreshData),
+	/// Presented again after being rotated
+	Reused,
+	Unknown,
+}
+
+/// Left in place of a refresh token's data once it is consumed.
+const USED_REFRESH_TOKEN: &str = "used";
+
+static REDIS: OnceLock<ConnectionManager> = OnceLock::new();
+const REDIS_MAX_RETRY_DELAY_MILLISECONDS: u64 = 1000;
+
+/// Opens the multiplexed connection shared by every request, it reconnects by itself after an
+/// outage.
+pub async fn create_connection_manager(config: &RedisConfig) -> Result<ConnectionManager, String> {
+	let client =
+		redis::Client::open(config.url.expose()).map_err(|e| format!("Invalid REDIS_URL: {e}"))?;
+	let manager_config = ConnectionManagerConfig::new()
+		.set_connection_timeout(Duration::from_secs(config.connect_timeout_seconds))
+		.set_response_timeout(Duration::from_secs(config.response_timeout_seconds))
+		// The default backoff multiplies the delay by 100 on each retry, the launch would hang
+		.set_max_delay(REDIS_MAX_RETRY_DELAY_MILLISECONDS);
+
+	ConnectionManager::new_with_config(client, manager_config)
+		.await
+		.map_err(|e| format!("Can't connect to redis: {e}"))
+}
+
+/// Installs the connection shared by the helpers of this module, once at launch.
+pub fn install_connection_manager(manager: ConnectionManager) {
+	if REDIS.set(manager).is_err() {
+		warn!("Redis connection manager already installed");
+	}
+}
+
+/// Shared connection whose commands run in a `redis` span, with their name but not their keys
+/// and values.
+#[derive(Clone)]
+struct TracedConnection(ConnectionManager);
+
+impl ConnectionLike for TracedConnection {
+	fn req_packed_command<'a>(&'a mut self, cmd: &'a Cmd) -> RedisFuture<'a, Value> {
+		let command = match cmd.args_iter().next() {
+			Some(Arg::Simple(name)) => String::from_utf8_lossy(name).to_uppercase(),
+			_ => String::new(),
+		};
+
+		Box::pin(timed(
+			debug_span!("redis", command),
+			self.0.req_packed_command(cmd),
+		))
+	}
+
+	fn req_packed_commands<'a>(
+		&'a mut self,
+		pipeline: &'a Pipeline,
+		offset: usize,
+		count: usize,
+	) -> RedisFuture<'a, Vec<Value>> {
+		Box::pin(timed(
+			debug_span!("redis", command = "PIPELINE", count),
+			self.0.req_packed_commands(pipeline, offset, count),
+		))
+	}
+
+	fn get_db(&self) -> i64 {
+		self.0.get_db()
+	}
 }
 
-fn setup_redis() -> Result<Connection, Status> {
-	let client = redis::Client::open("redis://default:@redis/")
-		.internal_server_error("Error failed to connect to redis")?;
-	client
-		.get_connection()
-		.internal_server_error("Error failed to get connection")
+fn setup_redis() -> Result<TracedConnection, Status> {
+	REDIS
+		.get()
+		.cloned()
+		.map(TracedConnection)
+		.internal_server_error("Redis connection manager is not installed")
 }
 
-pub fn get_transactionid(user_id: &str, code: String) -> Result<String, Status> {
+/// Readiness probe, the shared connection has to answer `PING`.
+pub async fn ping_redis() -> Result<(), String> {
+	let mut con = REDIS
+		.get()
+		.cloned()
+		.map(TracedConnection)
+		.ok_or("Redis connection manager is not installed")?;
+
+	con.ping().await.map(|_| ()).map_err(|e| e.to_string())
+}
+
+trait RedisResultHandling<T> {
+	fn redis_error<M: ToString>(self, message: M) -> Result<T, Status>;
+}
+
+impl<T> RedisResultHandling<T> for RedisResult<T> {
+	/// Redis being unreachable is an outage rather than a bug, the client is told to come back
+	/// later.
+	fn redis_error<M: ToString>(self, message: M) -> Result<T, Status> {
+		self.map_err(|e| {
+			if e.is_io_error()
+				|| e.is_timeout()
+				|| e.is_connection_dropped()
+				|| e.is_connection_refusal()
+			{
+				Status::ServiceUnavailable
+			} else {
+				error!("{} : {e:?}", message.to_string());
+				Status::InternalServerError
+			}
+		})
+	}
+}
+
+pub async fn get_transactionid(
+	user_id: &str,
+	user_type: UserType,
+	method: TwofaMethod,
+	code: String,
+) -> Result<String, Status> {
 	let mut con = setup_redis()?;
 	let transaction_id = Uuid::new_v4();
 
 	let value = serde_json::to_string(&LoginTransaction {
 		user_id: user_id.to_string(),
+		user_type,
+		method,
 		code,
 	})
 	.internal_server_error("Error failed to deserialize LoginTransaction")?;
+	let ttl_seconds = ttl().login_transaction_seconds;
 
-	con.set_ex(format!("login:{transaction_id}"), value, 900)
-		.internal_server_error("Error failed to set login:transaction to redis")?;
+	con.set_ex(format!("login:{transaction_id}"), value, ttl_seconds)
+		.await
+		.redis_error("Error failed to set login:transaction to redis")?;
 
 	Ok(transaction_id.to_string())
 }
 
-pub fn check_2fa_code(twofa: &TwofaPayload) -> Result<bool, Status> {
+pub async fn check_2fa_code(twofa: &TwofaPayload) -> Result<bool, Status> {
 	let mut con = setup_redis()?;
 
 	let val = con
 		.get(format!("login:{}", twofa.transaction_id))
-		.internal_server_error("Failed to get login:transaction_id from redis")?
+		.await
+		.redis_error("Failed to get login:transaction_id from redis")?
 		.internal_server_error("Transaction id is empty")?;
 
 	let check: LoginTransaction = serde_json::from_str(&val)
 		.internal_server_error("Failed to deserialize LoginTransaction")?;
 
-	Ok(check.code == twofa.code)
+	Ok(check.method == TwofaMethod::Mail && check.code == twofa.code)
+}
+
+pub async fn get_twofa_method_from_twofa(twofa: &TwofaPayload) -> Result<TwofaMethod, Status> {
+	let mut con = setup_redis()?;
+
+	let val = con
+		.get(format!("login:{}", twofa.transaction_id))
+		.await
+		.redis_error("Error while trying to get twofa method from twofa")?
+		.internal_server_error("Transaction id is empty")?;
+
+	let check: LoginTransaction = serde_json::from_str(&val).internal_server_error(
+		"Error while trying to convert twofa method from redis to LoginTransaction",
+	)?;
+
+	Ok(check.method)
 }
 
-pub fn invalidate_transactionid(twofa: &TwofaPayload) -> Result<(), Status> {
+pub async fn invalidate_transactionid(twofa: &TwofaPayload) -> Result<(), Status> {
 	let mut con = setup_redis()?;
 
 	con.del(format!("login:{}", twofa.transaction_id))
-		.internal_server_error("Failed to delete login:transaction_id from redis")?;
+		.await
+		.redis_error("Failed to delete login:transaction_id from redis")?;
 
 	Ok(())
 }
 
-pub fn get_user_id_from_twofa(twofa: &TwofaPayload) -> Result<String, Status> {
+pub async fn transaction_exist(twofa: &TwofaPayload) -> Result<bool, Status> {
+	let mut con = setup_redis()?;
+
+	con.exists(format!("login:{}", twofa.transaction_id))
+		.await
+		.redis_error("Failed to check login:transaction_id in redis")
+}
+
+pub async fn get_user_type_from_twofa(twofa: &TwofaPayload) -> Result<UserType, Status> {
 	let mut con = setup_redis()?;
 
 	let val = con
 		.get(format!("login:{}", twofa.transaction_id))
-		.internal_server_error("Error while trying to get user_id from twofa")?
+		.await
+		.redis_error("Error while trying to get user_type from twofa")?
 		.internal_server_error("Transaction id is empty")?;
 
-	if val.is_empty() {
-		return Ok(val);
-	}
+	let check: LoginTransaction = serde_json::from_str(&val).internal_server_error(
+		"Error while trying to convert user_type from redis to LoginTransaction",
+	)?;
+
+	Ok(check.user_type)
+}
+
+/// `None` when the transaction expired or names no user, both are an invalid transaction.
+pub async fn get_user_id_from_twofa(twofa: &TwofaPayload) -> Result<Option<String>, Status> {
+	let mut con = setup_redis()?;
+
+	let val: Option<String> = con
+		.get(format!("login:{}", twofa.transaction_id))
+		.await
+		.redis_error("Error while trying to get user_id from twofa")?;
+	let Some(val) = val.filter(|val| !val.is_empty()) else {
+		return Ok(None);
+	};
 
 	let check: LoginTransaction = serde_json::from_str(&val).internal_server_error(
 		"Error while trying to convert user_id from redis to LoginTransaction",
 	)?;
 
-	Ok(check.user_id)
+	Ok(Some(check.user_id).filter(|user_id| !user_id.is_empty()))
 }
 
-pub fn set_session(
+pub async fn set_session(
 	session_id: &str,
 	session_data: &SessionData,
 	ttl_seconds: u64,
 ) -> Result<(), Status> {
 	let mut con = setup_redis()?;
+	let user_id = session_data.user_id.clone();
 	let session_data = serde_json::to_string(session_data).map_err(|e| {
-		eprintln!("Error during serialization : {e}");
+		error!("Error during serialization : {e}");
 		Status::InternalServerError
 	})?;
 	con.set_ex(format!("session:{session_id}"), session_data, ttl_seconds)
-		.internal_server_error("Failed to set session:session_id to redis")?;
+		.await
+		.redis_error("Failed to set session:session_id to redis")?;
+
+	prune_user_sessions(&mut con, &user_id).await?;
+	con.sadd(format!("user_sessions:{user_id}"), session_id)
+		.await
+		.redis_error("Failed to add session to user_sessions:user_id in redis")?;
+
+	Ok(())
+}
+
+// Expired sessions are not removed from the index by redis, drop them here
+async fn prune_user_sessions(con: &mut TracedConnection, user_id: &str) -> Result<(), Status> {
+	let key = format!("user_sessions:{user_id}");
+	let session_ids = con
+		.smembers(&key)
+		.await
+		.redis_error("Failed to get user_sessions:user_id from redis")?;
+
+	for session_id in session_ids {
+		let exists = con
+			.exists(format!("session:{session_id}"))
+			.await
+			.redis_error("Failed to check session:session_id in redis")?;
+		if !exists {
+			con.srem(&key, &session_id)
+				.await
+				.redis_error("Failed to remove from user_sessions:user_id in redis")?;
+		}
+	}
 
 	Ok(())
 }
 
-pub fn invalidate_session(session_id: &str) -> Result<(), Status> {
+pub async fn invalidate_session(session_id: &str) -> Result<(), Status> {
 	let mut con = setup_redis()?;
 
+	let line = con
+		.get(format!("session:{session_id}"))
+		.await
+		.redis_error("Failed to get session:session_id from redis")?;
+	if let Some(line) = line {
+		let session_data: SessionData = serde_json::from_str(&line)
+			.internal_server_error("Error while deserializing session data")?;
+		con.srem(
+			format!("user_sessions:{}", session_data.user_id),
+			session_id,
+		)
+		.await
+		.redis_error("Failed to remove from user_sessions:user_id in redis")?;
+	}
+
 	con.del(format!("session:{session_id}"))
-		.internal_server_error("Failed to delete session:session_id from redis")?;
+		.await
+		.redis_error("Failed to delete session:session_id from redis")?;
+
+	// The refresh token of the session expires by itself, it can't extend a deleted session
+	Ok(())
+}
+
+pub async fn invalidate_user_sessions(user_id: &str) -> Result<(), Status> {
+	let mut con = setup_redis()?;
+	let key = format!("user_sessions:{user_id}");
+
+	let session_ids = con
+		.smembers(&key)
+		.await
+		.redis_error("Failed to get user_sessions:user_id from redis")?;
+
+	for session_id in session_ids {
+		invalidate_session(&session_id).await?;
+	}
+
+	con.del(&key)
+		.await
+		.redis_error("Failed to delete user_sessions:user_id from redis")?;
+
+	Ok(())
+}
+
+pub async fn invalidate_other_sessions(
+	user_id: &str,
+	current_session_id: &str,
+) -> Result<(), Status> {
+	let mut con = setup_redis()?;
+
+	let session_ids = con
+		.smembers(format!("user_sessions:{user_id}"))
+		.await
+		.redis_error("Failed to get user_sessions:user_id from redis")?;
+
+	for session_id in session_ids {
+		if session_id != current_session_id {
+			invalidate_session(&session_id).await?;
+		}
+	}
+
+	Ok(())
+}
+
+pub async fn get_session(session_id: &str) -> Result<Option<SessionData>, Status> {
+	let mut con = setup_redis()?;
+
+	let line = con
+		.get(format!("session:{session_id}"))
+		.await
+		.redis_error("Failed to get session:session_id from redis")?;
+
+	line.map(|line| {
+		serde_json::from_str(&line).internal_server_error("Error while deserializing session data")
+	})
+	.transpose()
+}
+
+pub async fn get_user_sessions(user_id: &str) -> Result<Vec<(String, SessionData)>, Status> {
+	let mut con = setup_redis()?;
+
+	prune_user_sessions(&mut con, user_id).await?;
+	let session_ids = con
+		.smembers(format!("user_sessions:{user_id}"))
+		.await
+		.redis_error("Failed to get user_sessions:user_id from redis")?;
+
+	let mut res = vec![];
+
+	for session_id in session_ids {
+		if let Some(session_data) = get_session(&session_id).await? {
+			res.push((session_id, session_data));
+		}
+	}
+
+	Ok(res)
+}
+
+/// Updates the last activity of the session.
+pub async fn touch_session(session_id: &str, mut session_data: SessionData) -> Result<(), Status> {
+	let now = Utc::now();
+
+	if (now - session_data.last_seen).num_seconds() < SESSION_TOUCH_INTERVAL_SECONDS {
+		return Ok(());
+	}
+
+	session_data.last_seen = now;
+	update_session(session_id, &session_data).await
+}
+
+/// Replaces the data of an existing session without touching its TTL.
+pub async fn update_session(session_id: &str, session_data: &SessionData) -> Result<(), Status> {
+	let mut con = setup_redis()?;
+	let session_data = serde_json::to_string(session_data)
+		.internal_server_error("Failed to serialize SessionData")?;
+
+	redis::cmd("SET")
+		.arg(format!("session:{session_id}"))
+		.arg(session_data)
+		.arg("XX")
+		.arg("KEEPTTL")
+		.exec_async(&mut con)
+		.await
+		.redis_error("Failed to update session:session_id in redis")?;
+
+	Ok(())
+}
+
+pub async fn extend_session(session_id: &str, ttl_seconds: u64) -> Result<bool, Status> {
+	let mut con = setup_redis()?;
+
+	con.expire(
+		format!("session:{session_id}"),
+		ttl_seconds.try_into().internal_server_error("Session TTL overflow")?,
+	)
+	.await
+	.redis_error("Failed to extend session:session_id in redis")
+}
+
+pub async fn set_refresh_token(
+	session_id: &str,
+	token_hash: &str,
+	refresh_data: &RefreshData,
+	ttl_seconds: u64,
+) -> Result<(), Status> {
+	let mut con = setup_redis()?;
+	let refresh_data = serde_json::to_string(refresh_data)
+		.internal_server_error("Failed to serialize RefreshData")?;
+
+	con.set_ex(
+		format!("refresh:{session_id}:{token_hash}"),
+		refresh_data,
+		ttl_seconds,
+	)
+	.await
+	.redis_error("Failed to set refresh:session_id:token_hash to redis")?;
 
 	Ok(())
 }
 
-pub fn get_user_id_from_session_id(session_id: String) -> Result<String, Status> {
+/// Swaps the data of a refresh token for a marker kept `ttl_seconds`.
+///
+/// A single `SET XX GET` does it, so only one of the requests presenting the same token gets
+/// its data and the others see it as reused.
+pub async fn consume_refresh_token(
+	session_id: &str,
+	token_hash: &str,
+	ttl_seconds: u64,
+) -> Result<ConsumedRefreshToken, Status> {
+	let mut con = setup_redis()?;
+
+	let previous: Option<String> = redis::cmd("SET")
+		.arg(format!("refresh:{session_id}:{token_hash}"))
+		.arg(USED_REFRESH_TOKEN)
+		.arg("XX")
+		.arg("GET")
+		.arg("EX")
+		.arg(ttl_seconds)
+		.query_async(&mut con)
+		.await
+		.redis_error("Failed to consume refresh:session_id:token_hash in redis")?;
+
+	match previous.as_deref() {
+		None => Ok(ConsumedRefreshToken::Unknown),
+		Some(USED_REFRESH_TOKEN) => Ok(ConsumedRefreshToken::Reused),
+		Some(refresh_data) => serde_json::from_str(refresh_data)
+			.map(ConsumedRefreshToken::Fresh)
+			.internal_server_error("Failed to deserialize RefreshData"),
+	}
+}
+
+pub async fn get_user_id_from_session_id(session_id: String) -> Result<String, Status> {
 	let mut con = setup_redis()?;
 	let line = con
 		.get(format!("session:{session_id}"))
-		.internal_server_error("Error while getting line")?;
+		.await
+		.redis_error("Error while getting line")?;
 	let line = line.internal_server_error("No value found to get user_id from session_id")?;
 	let session_data: SessionData = serde_json::from_str(&line)
 		.internal_server_error("Error while deserializing session data")?;
@@ -122,12 +549,173 @@ pub fn get_user_id_from_session_id(session_id: String) -> Result<String, Status>
 	Ok(session_data.user_id)
 }
 
-pub fn session_exist(session_id: &str) -> Result<bool, Status> {
+pub async fn session_exist(session_id: &str) -> Result<bool, Status> {
 	let mut con = setup_redis()?;
 
 	let res = con
 		.get(format!("session:{session_id}"))
-		.internal_server_error("Failed to get session:session_id from redis")?;
+		.await
+		.redis_error("Failed to get session:session_id from redis")?;
 
 	Ok(res.is_some())
 }
+
+pub async fn increment_attempts(key: &str, window_seconds: u64) -> Result<u64, Status> {
+	let mut con = setup_redis()?;
+	let key = format!("attempts:{key}");
+
+	let attempts = con
+		.incr(&key, 1)
+		.await
+		.redis_error("Failed to increment attempts in redis")?;
+
+	if attempts == 1 {
+		con.expire(
+			&key,
+			window_seconds
+				.try_into()
+				.internal_server_error("Attempts window overflow")?,
+		)
+		.await
+		.redis_error("Failed to set TTL of attempts in redis")?;
+	}
+
+	attempts
+		.try_into()
+		.internal_server_error("Negative attempts count in redis")
+}
+
+pub async fn reset_attempts(key: &str) -> Result<(), Status> {
+	let mut con = setup_redis()?;
+
+	con.del(format!("attempts:{key}"))
+		.await
+		.redis_error("Failed to delete attempts from redis")?;
+	con.del(format!("lock:{key}"))
+		.await
+		.redis_error("Failed to delete lock from redis")?;
+
+	Ok(())
+}
+
+pub async fn set_lock(key: &str, ttl_seconds: u64) -> Result<(), Status> {
+	let mut con = setup_redis()?;
+
+	con.set_ex(format!("lock:{key}"), 1, ttl_seconds)
+		.await
+		.redis_error("Failed to set lock to redis")?;
+
+	Ok(())
+}
+
+pub async fn get_lock_ttl(key: &str) -> Result<Option<u64>, Status> {
+	let mut con = setup_redis()?;
+
+	let ttl = con
+		.ttl(format!("lock:{key}"))
+		.await
+		.redis_error("Failed to get lock TTL from redis")?;
+
+	match ttl {
+		IntegerReplyOrNoOp::IntegerReply(ttl) => Ok(u64::try_from(ttl).ok()),
+		IntegerReplyOrNoOp::NotExists | IntegerReplyOrNoOp::ExistsButNotRelevant => Ok(None),
+	}
+}
+
+pub async fn set_password_reset(jti: &str, ttl_seconds: u64) -> Result<(), Status> {
+	let mut con = setup_redis()?;
+
+	con.set_ex(format!("password_reset:{jti}"), 1, ttl_seconds)
+		.await
+		.redis_error("Failed to set password_reset:jti to redis")?;
+
+	Ok(())
+}
+
+/// Returns whether the reset token was still unused, it can't be used again afterwards.
+pub async fn consume_password_reset(jti: &str) -> Result<bool, Status> {
+	let mut con = setup_redis()?;
+
+	let deleted = con
+		.del(format!("password_reset:{jti}"))
+		.await
+		.redis_error("Failed to delete password_reset:jti from redis")?;
+
+	Ok(deleted == 1)
+}
+
+/// Only the last verification link sent to a user is valid, a new one replaces it.
+pub async fn set_mail_verification(
+	user_id: &str,
+	jti: &str,
+	ttl_seconds: u64,
+) -> Result<(), Status> {
+	let mut con = setup_redis()?;
+
+	con.set_ex(format!("mail_verification:{user_id}"), jti, ttl_seconds)
+		.await
+		.redis_error("Failed to set mail_verification:user_id to redis")?;
+
+	Ok(())
+}
+
+/// Returns whether `jti` is the pending verification link of the user, it can't be used again.
+pub async fn consume_mail_verification(user_id: &str, jti: &str) -> Result<bool, Status> {
+	let mut con = setup_redis()?;
+	let key = format!("mail_verification:{user_id}");
+
+	let pending = con
+		.get(&key)
+		.await
+		.redis_error("Failed to get mail_verification:user_id from redis")?;
+	if pending.as_deref() != Some(jti) {
+		return Ok(false);
+	}
+
+	let deleted = con
+		.del(&key)
+		.await
+		.redis_error("Failed to delete mail_verification:user_id from redis")?;
+
+	Ok(deleted == 1)
+}
+
+/// What the OIDC callback needs to finish a login started with `state`.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct OidcLoginState {
+	pub university_id: String,
+	pub nonce: String,
+	pub code_verifier: String,
+	pub remember_me: bool,
+}
+
+pub async fn set_oidc_state(
+	state: &str,
+	login_state: &OidcLoginState,
+	ttl_seconds: u64,
+) -> Result<(), Status> {
+	let mut con = setup_redis()?;
+	let login_state = serde_json::to_string(login_state)
+		.internal_server_error("Error while serializing oidc state")?;
+
+	con.set_ex(format!("oidc:{state}"), login_state, ttl_seconds)
+		.await
+		.redis_error("Failed to set oidc:state to redis")?;
+
+	Ok(())
+}
+
+/// Returns the login state once, a callback can't be replayed.
+pub async fn consume_oidc_state(state: &str) -> Result<Option<OidcLoginState>, Status> {
+	let mut con = setup_redis()?;
+
+	let line = con
+		.get_del(format!("oidc:{state}"))
+		.await
+		.redis_error("Failed to get oidc:state from redis")?;
+
+	line.map(|line| {
+		serde_json::from_str(&line).internal_server_error("Error while deserializing oidc state")
+	})
+	.transpose()
+}
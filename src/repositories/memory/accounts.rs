@@ -0,0 +1,242 @@
+use rocket::http::Status;
+
+use crate::{
+	models::auth::{AccountStatus, UserType},
+	repositories::{AccountLookup, AccountRepository},
+};
+
+use super::{Data, InMemoryRepository};
+
+impl Data {
+	/// Id of the account whose login, mail or id has this value.
+	pub(super) fn find_account_id(
+		&self,
+		user_type: UserType,
+		lookup: AccountLookup,
+		value: &str,
+	) -> Option<String> {
+		let matches = |id: &str, login: &str, mail: &str| match lookup {
+			AccountLookup::Id => id == value,
+			AccountLookup::Login => login == value,
+			AccountLookup::Mail => mail == value,
+		};
+
+		match user_type {
+			UserType::Admin => self
+				.admins
+				.iter()
+				.find(|admin| matches(&admin.id, &admin.login, &admin.mail))
+				.map(|admin| admin.id.clone()),
+			UserType::University => self
+				.universities
+				.iter()
+				.find(|university| matches(&university.id, &university.login, &university.mail))
+				.map(|university| university.id.clone()),
+			UserType::Student => self
+				.students
+				.iter()
+				.find(|stored| {
+					matches(
+						&stored.student.id,
+						&stored.student.login,
+						&stored.student.mail,
+					)
+				})
+				.map(|stored| stored.student.id.clone()),
+			UserType::Company => self
+				.companies
+				.iter()
+				.find(|company| matches(&company.id, &company.login, &company.mail))
+				.map(|company| company.id.clone()),
+		}
+	}
+
+	/// Mail and password hash of the account.
+	fn mail_and_password(&self, user_type: UserType, id: &str) -> Option<(&str, &str)> {
+		match user_type {
+			UserType::Admin => self
+				.admins
+				.iter()
+				.find(|admin| admin.id == id)
+				.map(|admin| (admin.mail.as_str(), admin.password.as_str())),
+			UserType::University => self
+				.universities
+				.iter()
+				.find(|university| university.id == id)
+				.map(|university| (university.mail.as_str(), university.password.as_str())),
+			UserType::Student => self
+				.students
+				.iter()
+				.find(|stored| stored.student.id == id)
+				.map(|stored| {
+					(
+						stored.student.mail.as_str(),
+						stored.student.password.as_str(),
+					)
+				}),
+			UserType::Company => self
+				.companies
+				.iter()
+				.find(|company| company.id == id)
+				.map(|company| (company.mail.as_str(), company.password.as_str())),
+		}
+	}
+
+	fn password_mut(&mut self, user_type: UserType, id: &str) -> Option<&mut String> {
+		match user_type {
+			UserType::Admin => self
+				.admins
+				.iter_mut()
+				.find(|admin| admin.id == id)
+				.map(|admin| &mut admin.password),
+			UserType::University => self
+				.universities
+				.iter_mut()
+				.find(|university| university.id == id)
+				.map(|university| &mut university.password),
+			UserType::Student => self
+				.students
+				.iter_mut()
+				.find(|stored| stored.student.id == id)
+				.map(|stored| &mut stored.student.password),
+			UserType::Company => self
+				.companies
+				.iter_mut()
+				.find(|company| company.id == id)
+				.map(|company| &mut company.password),
+		}
+	}
+}
+
+#[async_trait]
+impl AccountRepository for InMemoryRepository {
+	async fn find_id_by_mail(
+		&self,
+		user_type: UserType,
+		mail: &str,
+	) -> Result<Option<String>, Status> {
+		Ok(self
+			.read()?
+			.find_account_id(user_type, AccountLookup::Mail, mail))
+	}
+
+	async fn get_status(
+		&self,
+		user_type: UserType,
+		lookup: AccountLookup,
+		value: &str,
+	) -> Result<Option<(AccountStatus, Option<String>)>, Status> {
+		let data = self.read()?;
+
+		Ok(data
+			.find_account_id(user_type, lookup, value)
+			.and_then(|id| data.account(user_type, &id))
+			.map(|account| (account.status, account.status_reason.clone())))
+	}
+
+	async fn set_status(
+		&self,
+		user_type: UserType,
+		user_id: &str,
+		status: AccountStatus,
+		reason: Option<&str>,
+	) -> Result<bool, Status> {
+		let mut data = self.write()?;
+
+		let Some(account) = data.account_mut(user_type, user_id) else {
+			return Ok(false);
+		};
+		account.status = status;
+		account.status_reason = reason.map(ToString::to_string);
+
+		Ok(true)
+	}
+
+	async fn get_mail_verification(
+		&self,
+		user_type: UserType,
+		user_id: &str,
+	) -> Result<Option<(String, bool)>, Status> {
+		let data = self.read()?;
+
+		let mail = data
+			.mail_and_password(user_type, user_id)
+			.map(|(mail, _)| mail.to_string());
+
+		Ok(mail.zip(
+			data.account(user_type, user_id)
+				.map(|account| account.mail_verified),
+		))
+	}
+
+	async fn set_mail_verified(&self, user_type: UserType, user_id: &str) -> Result<bool, Status> {
+		if user_type == UserType::Admin {
+			return Ok(false);
+		}
+
+		let mut data = self.write()?;
+
+		let Some(account) = data.account_mut(user_type, user_id) else {
+			return Ok(false);
+		};
+		account.mail_verified = true;
+
+		// The models of these two carry the column
+		if let Some(university) = data
+			.universities
+			.iter_mut()
+			.find(|university| university.id == user_id)
+		{
+			university.mail_verified = true;
+		}
+		if let Some(company) = data
+			.companies
+			.iter_mut()
+			.find(|company| company.id == user_id)
+		{
+			company.mail_verified = true;
+		}
+
+		Ok(true)
+	}
+
+	async fn get_password_hash(
+		&self,
+		user_type: UserType,
+		user_id: &str,
+	) -> Result<Option<String>, Status> {
+		Ok(self
+			.read()?
+			.mail_and_password(user_type, user_id)
+			.map(|(_, password_hash)| password_hash.to_string()))
+	}
+
+	async fn update_password(
+		&self,
+		user_type: UserType,
+		user_id: &str,
+		password_hash: &str,
+	) -> Result<(), Status> {
+		let mut data = self.write()?;
+
+		if let Some(password) = data.password_mut(user_type, user_id) {
+			*password = password_hash.to_string();
+		}
+		if let Some(account) = data.account_mut(user_type, user_id) {
+			account.must_change_password = false;
+		}
+
+		Ok(())
+	}
+
+	async fn must_change_password(
+		&self,
+		user_type: UserType,
+		user_id: &str,
+	) -> Result<bool, Status> {
+		Ok(self
+			.read()?
+			.account(user_type, user_id)
+			.is_some_and(|account| account.must_change_password))
+	}
+}
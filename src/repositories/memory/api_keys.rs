@@ -0,0 +1,74 @@
+use std::cmp::Reverse;
+
+use chrono::Utc;
+use rocket::http::Status;
+
+use crate::{models::auth::ApiKey, repositories::ApiKeyRepository};
+
+use super::{InMemoryRepository, StoredApiKey};
+
+#[async_trait]
+impl ApiKeyRepository for InMemoryRepository {
+	async fn insert(&self, api_key: &ApiKey, key_hash: &str) -> Result<(), Status> {
+		let mut data = self.write()?;
+
+		// Same as the foreign key of the table
+		if !data
+			.companies
+			.iter()
+			.any(|company| company.id == api_key.company_id)
+		{
+			return Err(Status::InternalServerError);
+		}
+
+		data.api_keys.push(StoredApiKey {
+			api_key: api_key.clone(),
+			key_hash: key_hash.to_string(),
+		});
+
+		Ok(())
+	}
+
+	async fn get_by_company(&self, company_id: &str) -> Result<Vec<ApiKey>, Status> {
+		let mut api_keys: Vec<ApiKey> = self
+			.read()?
+			.api_keys
+			.iter()
+			.filter(|stored| stored.api_key.company_id == company_id)
+			.map(|stored| stored.api_key.clone())
+			.collect();
+		api_keys.sort_by_key(|api_key| Reverse(api_key.created_at));
+
+		Ok(api_keys)
+	}
+
+	async fn authenticate(&self, key_hash: &str) -> Result<Option<ApiKey>, Status> {
+		let now = Utc::now();
+
+		Ok(self
+			.write()?
+			.api_keys
+			.iter_mut()
+			.find(|stored| {
+				stored.key_hash == key_hash
+					&& stored
+						.api_key
+						.expires_at
+						.is_none_or(|expires_at| expires_at > now)
+			})
+			.map(|stored| {
+				stored.api_key.last_used_at = Some(now);
+				stored.api_key.clone()
+			}))
+	}
+
+	async fn delete(&self, company_id: &str, id: &str) -> Result<bool, Status> {
+		let mut data = self.write()?;
+
+		let count = data.api_keys.len();
+		data.api_keys
+			.retain(|stored| stored.api_key.id != id || stored.api_key.company_id != company_id);
+
+		Ok(data.api_keys.len() < count)
+	}
+}
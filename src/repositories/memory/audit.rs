@@ -0,0 +1,80 @@
+use chrono::Utc;
+use rocket::http::Status;
+
+use crate::{
+	models::audit::{AuditEvent, AuditFilter, NewAuditEvent},
+	repositories::AuditRepository,
+};
+
+use super::InMemoryRepository;
+
+impl AuditFilter {
+	fn matches(&self, event: &AuditEvent) -> bool {
+		self.actor_id
+			.as_ref()
+			.is_none_or(|actor_id| event.actor_id.as_ref() == Some(actor_id))
+			&& self
+				.actor_role
+				.is_none_or(|actor_role| event.actor_role == Some(actor_role))
+			&& self.impersonator_id.as_ref().is_none_or(|impersonator_id| {
+				event.impersonator_id.as_ref() == Some(impersonator_id)
+			}) && self.action.is_none_or(|action| event.action == action)
+			&& self
+				.target_id
+				.as_ref()
+				.is_none_or(|target_id| event.target_id.as_ref() == Some(target_id))
+			&& self.outcome.is_none_or(|outcome| event.outcome == outcome)
+			&& self.from.is_none_or(|from| event.occurred_at >= from)
+			&& self.to.is_none_or(|to| event.occurred_at < to)
+	}
+}
+
+#[async_trait]
+impl AuditRepository for InMemoryRepository {
+	async fn record(&self, event: &NewAuditEvent) -> Result<(), Status> {
+		let mut data = self.write()?;
+
+		let id = data.audit_events.last().map_or(1, |last| last.id + 1);
+		data.audit_events.push(AuditEvent {
+			id,
+			occurred_at: Utc::now(),
+			actor_id: event.actor_id.clone(),
+			actor_role: event.actor_role,
+			impersonator_id: event.impersonator_id.clone(),
+			action: event.action,
+			target_id: event.target_id.clone(),
+			ip: event.ip.map(|ip| ip.to_string()),
+			outcome: event.outcome,
+		});
+
+		Ok(())
+	}
+
+	async fn search(
+		&self,
+		filter: &AuditFilter,
+		limit: i64,
+		offset: i64,
+	) -> Result<(Vec<AuditEvent>, i64), Status> {
+		let data = self.read()?;
+
+		// Recorded in order, so the newest are the last ones
+		let matching: Vec<&AuditEvent> = data
+			.audit_events
+			.iter()
+			.rev()
+			.filter(|event| filter.matches(event))
+			.collect();
+		let total = i64::try_from(matching.len()).unwrap_or(i64::MAX);
+
+		Ok((
+			matching
+				.into_iter()
+				.skip(usize::try_from(offset).unwrap_or(usize::MAX))
+				.take(usize::try_from(limit).unwrap_or(0))
+				.cloned()
+				.collect(),
+			total,
+		))
+	}
+}
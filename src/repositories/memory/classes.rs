@@ -0,0 +1,64 @@
+use rocket::http::Status;
+
+use crate::{
+	models::{courses::Class, users::Student},
+	repositories::ClassRepository,
+};
+
+use super::InMemoryRepository;
+
+#[async_trait]
+impl ClassRepository for InMemoryRepository {
+	async fn get(&self, id: &str) -> Result<Option<Class>, Status> {
+		Ok(self
+			.read()?
+			.classes
+			.iter()
+			.find(|class| class.id == id)
+			.cloned())
+	}
+
+	async fn get_by_university(&self, university_id: &str) -> Result<Vec<Class>, Status> {
+		Ok(self.read()?.classes_of_university(university_id))
+	}
+
+	async fn get_by_student(&self, student_id: &str) -> Result<Option<Class>, Status> {
+		Ok(self.read()?.class_of_student(student_id).cloned())
+	}
+
+	async fn get_students(&self, class_id: &str) -> Result<Vec<Student>, Status> {
+		Ok(self
+			.read()?
+			.students
+			.iter()
+			.filter(|stored| stored.class_id == class_id)
+			.map(|stored| stored.student.clone())
+			.collect())
+	}
+
+	async fn insert(&self, class: &Class) -> Result<(), Status> {
+		let mut data = self.write()?;
+
+		// Same as the foreign key of the table
+		if !data
+			.universities
+			.iter()
+			.any(|university| university.id == class.university_id)
+		{
+			return Err(Status::InternalServerError);
+		}
+
+		data.classes.push(class.clone());
+
+		Ok(())
+	}
+
+	async fn delete(&self, id: &str) -> Result<Vec<String>, Status> {
+		let mut data = self.write()?;
+
+		let student_ids = data.delete_students_of_classes(&[id.to_string()]);
+		data.classes.retain(|class| class.id != id);
+
+		Ok(student_ids)
+	}
+}
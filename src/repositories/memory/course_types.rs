@@ -0,0 +1,28 @@
+use rocket::http::Status;
+
+use crate::{models::courses::CourseType, repositories::CourseTypeRepository};
+
+use super::InMemoryRepository;
+
+#[async_trait]
+impl CourseTypeRepository for InMemoryRepository {
+	async fn get_all(&self) -> Result<Vec<CourseType>, Status> {
+		Ok(self.read()?.course_types.clone())
+	}
+
+	async fn get_by_university(&self, university_id: &str) -> Result<Vec<CourseType>, Status> {
+		Ok(self
+			.read()?
+			.classes_of_university(university_id)
+			.into_iter()
+			.map(|class| class.course_type)
+			.collect())
+	}
+
+	async fn get_by_student(&self, student_id: &str) -> Result<Option<CourseType>, Status> {
+		Ok(self
+			.read()?
+			.class_of_student(student_id)
+			.map(|class| class.course_type))
+	}
+}
@@ -0,0 +1,92 @@
+use rocket::http::Status;
+
+use crate::{
+	models::courses::{CourseType, Internship},
+	repositories::InternshipRepository,
+};
+
+use super::{InMemoryRepository, StoredInternship};
+
+impl InMemoryRepository {
+	fn find_internships<F>(&self, filter: F) -> Result<Vec<Internship>, Status>
+	where
+		F: Fn(&StoredInternship) -> bool,
+	{
+		Ok(self
+			.read()?
+			.internships
+			.iter()
+			.filter(|stored| filter(stored))
+			.map(|stored| stored.internship.clone())
+			.collect())
+	}
+}
+
+#[async_trait]
+impl InternshipRepository for InMemoryRepository {
+	async fn get_all(&self) -> Result<Vec<Internship>, Status> {
+		self.find_internships(|_| true)
+	}
+
+	async fn get_by_company(&self, company_id: &str) -> Result<Vec<Internship>, Status> {
+		self.find_internships(|stored| stored.company_id.as_deref() == Some(company_id))
+	}
+
+	async fn get_by_university(&self, university_id: &str) -> Result<Vec<Internship>, Status> {
+		self.find_internships(|stored| stored.university_id.as_deref() == Some(university_id))
+	}
+
+	async fn get_by_course_types(
+		&self,
+		course_types: &[CourseType],
+	) -> Result<Vec<Internship>, Status> {
+		self.find_internships(|stored| course_types.contains(&stored.internship.course_type))
+	}
+
+	async fn is_owned_by_company(
+		&self,
+		internship_id: &str,
+		company_id: &str,
+	) -> Result<bool, Status> {
+		Ok(self.read()?.internships.iter().any(|stored| {
+			stored.internship.id == internship_id
+				&& stored.company_id.as_deref() == Some(company_id)
+		}))
+	}
+
+	async fn insert_for_company(
+		&self,
+		internship: &Internship,
+		company_id: &str,
+	) -> Result<(), Status> {
+		self.write()?.internships.push(StoredInternship {
+			internship: internship.clone(),
+			company_id: Some(company_id.to_string()),
+			university_id: None,
+		});
+
+		Ok(())
+	}
+
+	async fn insert_for_university(
+		&self,
+		internship: &Internship,
+		university_id: &str,
+	) -> Result<(), Status> {
+		self.write()?.internships.push(StoredInternship {
+			internship: internship.clone(),
+			company_id: None,
+			university_id: Some(university_id.to_string()),
+		});
+
+		Ok(())
+	}
+
+	async fn delete(&self, id: &str) -> Result<(), Status> {
+		self.write()?
+			.internships
+			.retain(|stored| stored.internship.id != id);
+
+		Ok(())
+	}
+}
@@ -0,0 +1,227 @@
+use std::sync::{RwLock, RwLockReadGuard, RwLockWriteGuard};
+
+use rocket::http::Status;
+
+use crate::{
+	error_handling::StatusResultHandling,
+	models::{
+		audit::AuditEvent,
+		auth::{AccountStatus, ApiKey, OidcProvider, TwofaSettings, UserType},
+		courses::{Class, CourseType, Internship},
+		users::{Company, Student, University, admin::Admin},
+	},
+};
+
+mod accounts;
+mod api_keys;
+mod audit;
+mod classes;
+mod course_types;
+mod internships;
+mod oidc_providers;
+mod twofa;
+mod users;
+
+/// Backend keeping everything in the process, passwords are stored hashed as in postgres.
+///
+/// The rows are linked by id and deleted in cascade like the tables of the schema.
+#[derive(Debug)]
+pub struct InMemoryRepository {
+	data: RwLock<Data>,
+}
+
+#[derive(Debug)]
+struct Data {
+	admins: Vec<Admin>,
+	/// Stored without their classes, which are looked up in `classes`
+	universities: Vec<University>,
+	companies: Vec<Company>,
+	students: Vec<StoredStudent>,
+	classes: Vec<Class>,
+	internships: Vec<StoredInternship>,
+	course_types: Vec<CourseType>,
+	/// Columns shared by the tables of every user type
+	accounts: Vec<StoredAccount>,
+	twofa: Vec<TwofaSettings>,
+	recovery_codes: Vec<StoredRecoveryCode>,
+	api_keys: Vec<StoredApiKey>,
+	oidc_providers: Vec<OidcProvider>,
+	oidc_identities: Vec<StoredOidcIdentity>,
+	audit_events: Vec<AuditEvent>,
+}
+
+#[derive(Debug)]
+struct StoredAccount {
+	user_type: UserType,
+	id: String,
+	status: AccountStatus,
+	status_reason: Option<String>,
+	must_change_password: bool,
+	mail_verified: bool,
+}
+
+impl StoredAccount {
+	/// Same defaults as the columns of the schema.
+	fn new(user_type: UserType, id: &str) -> Self {
+		Self {
+			user_type,
+			id: id.to_string(),
+			status: AccountStatus::Active,
+			status_reason: None,
+			must_change_password: user_type != UserType::Admin,
+			mail_verified: user_type == UserType::Admin,
+		}
+	}
+}
+
+#[derive(Debug)]
+struct StoredRecoveryCode {
+	user_id: String,
+	code_hash: String,
+}
+
+#[derive(Debug)]
+struct StoredApiKey {
+	api_key: ApiKey,
+	key_hash: String,
+}
+
+#[derive(Debug)]
+struct StoredOidcIdentity {
+	university_id: String,
+	subject: String,
+	user_id: String,
+	user_type: UserType,
+}
+
+#[derive(Debug)]
+struct StoredStudent {
+	student: Student,
+	class_id: String,
+}
+
+#[derive(Debug)]
+struct StoredInternship {
+	internship: Internship,
+	company_id: Option<String>,
+	university_id: Option<String>,
+}
+
+impl Default for InMemoryRepository {
+	fn default() -> Self {
+		Self {
+			data: RwLock::new(Data {
+				admins: vec![],
+				universities: vec![],
+				companies: vec![],
+				students: vec![],
+				classes: vec![],
+				internships: vec![],
+				// Seeded by the first migration
+				course_types: vec![CourseType::Info],
+				accounts: vec![],
+				twofa: vec![],
+				recovery_codes: vec![],
+				api_keys: vec![],
+				oidc_providers: vec![],
+				oidc_identities: vec![],
+				audit_events: vec![],
+			}),
+		}
+	}
+}
+
+impl InMemoryRepository {
+	/// Admins are only created in the database by hand, this is their equivalent.
+	pub fn insert_admin(&self, admin: &Admin, password_hash: &str) -> Result<(), Status> {
+		let mut data = self.write()?;
+
+		data.admins.push(Admin {
+			password: password_hash.to_string(),
+			..admin.clone()
+		});
+		data.accounts
+			.push(StoredAccount::new(UserType::Admin, &admin.id));
+
+		Ok(())
+	}
+
+	fn read(&self) -> Result<RwLockReadGuard<'_, Data>, Status> {
+		self.data
+			.read()
+			.internal_server_error("In-memory repository poisoned")
+	}
+
+	fn write(&self) -> Result<RwLockWriteGuard<'_, Data>, Status> {
+		self.data
+			.write()
+			.internal_server_error("In-memory repository poisoned")
+	}
+}
+
+impl Data {
+	fn classes_of_university(&self, university_id: &str) -> Vec<Class> {
+		self.classes
+			.iter()
+			.filter(|class| class.university_id == university_id)
+			.cloned()
+			.collect()
+	}
+
+	fn with_classes(&self, university: &University) -> University {
+		University {
+			class_list: self.classes_of_university(&university.id),
+			..university.clone()
+		}
+	}
+
+	fn class_of_student(&self, student_id: &str) -> Option<&Class> {
+		let stored = self
+			.students
+			.iter()
+			.find(|stored| stored.student.id == student_id)?;
+
+		self.classes
+			.iter()
+			.find(|class| class.id == stored.class_id)
+	}
+
+	fn account(&self, user_type: UserType, id: &str) -> Option<&StoredAccount> {
+		self.accounts
+			.iter()
+			.find(|account| account.user_type == user_type && account.id == id)
+	}
+
+	fn account_mut(&mut self, user_type: UserType, id: &str) -> Option<&mut StoredAccount> {
+		self.accounts
+			.iter_mut()
+			.find(|account| account.user_type == user_type && account.id == id)
+	}
+
+	/// Removes the students of the classes and returns their ids.
+	fn delete_students_of_classes(&mut self, class_ids: &[String]) -> Vec<String> {
+		let (deleted, kept) = std::mem::take(&mut self.students)
+			.into_iter()
+			.partition(|stored| class_ids.contains(&stored.class_id));
+		self.students = kept;
+
+		let student_ids: Vec<String> = deleted
+			.into_iter()
+			.map(|stored: StoredStudent| stored.student.id)
+			.collect();
+		self.accounts.retain(|account| {
+			account.user_type != UserType::Student || !student_ids.contains(&account.id)
+		});
+		for student_id in &student_ids {
+			self.delete_twofa(student_id);
+		}
+
+		student_ids
+	}
+
+	/// Same as the deletion of the `twofa` row, the recovery codes go with it.
+	fn delete_twofa(&mut self, user_id: &str) {
+		self.twofa.retain(|settings| settings.user_id != user_id);
+		self.recovery_codes.retain(|code| code.user_id != user_id);
+	}
+}
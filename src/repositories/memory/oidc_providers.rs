@@ -0,0 +1,127 @@
+use rocket::http::Status;
+
+use crate::{
+	models::auth::{OidcProvider, UserType},
+	repositories::{AccountLookup, OidcProviderRepository},
+};
+
+use super::{InMemoryRepository, StoredOidcIdentity};
+
+#[async_trait]
+impl OidcProviderRepository for InMemoryRepository {
+	async fn get(&self, university_id: &str) -> Result<Option<OidcProvider>, Status> {
+		Ok(self
+			.read()?
+			.oidc_providers
+			.iter()
+			.find(|provider| provider.university_id == university_id)
+			.cloned())
+	}
+
+	async fn get_universities(&self) -> Result<Vec<(String, String)>, Status> {
+		let data = self.read()?;
+
+		let mut universities: Vec<(String, String)> = data
+			.oidc_providers
+			.iter()
+			.filter_map(|provider| {
+				data.universities
+					.iter()
+					.find(|university| university.id == provider.university_id)
+			})
+			.map(|university| (university.id.clone(), university.name.clone()))
+			.collect();
+		universities.sort_by(|a, b| a.1.cmp(&b.1));
+
+		Ok(universities)
+	}
+
+	async fn save(&self, provider: &OidcProvider) -> Result<(), Status> {
+		let mut data = self.write()?;
+
+		// Same as the foreign key of the table
+		if !data
+			.universities
+			.iter()
+			.any(|university| university.id == provider.university_id)
+		{
+			return Err(Status::InternalServerError);
+		}
+
+		data.oidc_providers
+			.retain(|stored| stored.university_id != provider.university_id);
+		data.oidc_providers.push(provider.clone());
+
+		Ok(())
+	}
+
+	async fn delete(&self, university_id: &str) -> Result<(), Status> {
+		let mut data = self.write()?;
+
+		data.oidc_providers
+			.retain(|provider| provider.university_id != university_id);
+		data.oidc_identities
+			.retain(|identity| identity.university_id != university_id);
+
+		Ok(())
+	}
+
+	async fn find_identity(
+		&self,
+		university_id: &str,
+		subject: &str,
+	) -> Result<Option<(String, UserType)>, Status> {
+		Ok(self
+			.read()?
+			.oidc_identities
+			.iter()
+			.find(|identity| identity.university_id == university_id && identity.subject == subject)
+			.map(|identity| (identity.user_id.clone(), identity.user_type)))
+	}
+
+	async fn link_identity(
+		&self,
+		university_id: &str,
+		subject: &str,
+		user_id: &str,
+		user_type: UserType,
+	) -> Result<(), Status> {
+		let mut data = self.write()?;
+
+		data.oidc_identities.retain(|identity| {
+			identity.university_id != university_id || identity.subject != subject
+		});
+		data.oidc_identities.push(StoredOidcIdentity {
+			university_id: university_id.to_string(),
+			subject: subject.to_string(),
+			user_id: user_id.to_string(),
+			user_type,
+		});
+
+		Ok(())
+	}
+
+	async fn find_member(
+		&self,
+		university_id: &str,
+		lookup: AccountLookup,
+		value: &str,
+	) -> Result<Option<(String, UserType)>, Status> {
+		let data = self.read()?;
+
+		if data
+			.find_account_id(UserType::University, lookup, value)
+			.is_some_and(|id| id == university_id)
+		{
+			return Ok(Some((university_id.to_string(), UserType::University)));
+		}
+
+		Ok(data
+			.find_account_id(UserType::Student, lookup, value)
+			.filter(|student_id| {
+				data.class_of_student(student_id)
+					.is_some_and(|class| class.university_id == university_id)
+			})
+			.map(|student_id| (student_id, UserType::Student)))
+	}
+}
@@ -0,0 +1,129 @@
+use rocket::http::Status;
+
+use crate::{
+	models::auth::{TwofaMethod, TwofaSettings},
+	repositories::TwofaRepository,
+};
+
+use super::{InMemoryRepository, StoredRecoveryCode};
+
+#[async_trait]
+impl TwofaRepository for InMemoryRepository {
+	async fn get_settings(&self, user_id: &str) -> Result<TwofaSettings, Status> {
+		Ok(self
+			.read()?
+			.twofa
+			.iter()
+			.find(|settings| settings.user_id == user_id)
+			.cloned()
+			.unwrap_or_else(|| TwofaSettings {
+				user_id: user_id.to_string(),
+				..Default::default()
+			}))
+	}
+
+	async fn set_pending_secret(
+		&self,
+		user_id: &str,
+		method: TwofaMethod,
+		secret: &str,
+	) -> Result<(), Status> {
+		let mut data = self.write()?;
+
+		match data
+			.twofa
+			.iter_mut()
+			.find(|settings| settings.user_id == user_id)
+		{
+			Some(settings) => settings.totp_pending_secret = Some(secret.to_string()),
+			None => data.twofa.push(TwofaSettings {
+				user_id: user_id.to_string(),
+				method,
+				totp_secret: None,
+				totp_pending_secret: Some(secret.to_string()),
+				totp_last_step: None,
+			}),
+		}
+
+		Ok(())
+	}
+
+	async fn activate_totp(&self, user_id: &str, secret: &str, step: i64) -> Result<(), Status> {
+		if let Some(settings) = self
+			.write()?
+			.twofa
+			.iter_mut()
+			.find(|settings| settings.user_id == user_id)
+		{
+			settings.method = TwofaMethod::Totp;
+			settings.totp_secret = Some(secret.to_string());
+			settings.totp_pending_secret = None;
+			settings.totp_last_step = Some(step);
+		}
+
+		Ok(())
+	}
+
+	async fn use_totp_step(&self, user_id: &str, step: i64) -> Result<bool, Status> {
+		let mut data = self.write()?;
+
+		let Some(settings) = data
+			.twofa
+			.iter_mut()
+			.find(|settings| settings.user_id == user_id)
+		else {
+			return Ok(false);
+		};
+		if settings
+			.totp_last_step
+			.is_some_and(|last_step| last_step >= step)
+		{
+			return Ok(false);
+		}
+		settings.totp_last_step = Some(step);
+
+		Ok(true)
+	}
+
+	async fn delete(&self, user_id: &str) -> Result<(), Status> {
+		self.write()?.delete_twofa(user_id);
+
+		Ok(())
+	}
+
+	async fn use_recovery_code(&self, user_id: &str, code_hash: &str) -> Result<bool, Status> {
+		let mut data = self.write()?;
+
+		let count = data.recovery_codes.len();
+		data.recovery_codes
+			.retain(|code| code.user_id != user_id || code.code_hash != code_hash);
+
+		Ok(data.recovery_codes.len() < count)
+	}
+
+	async fn replace_recovery_codes(
+		&self,
+		user_id: &str,
+		code_hashes: &[String],
+	) -> Result<(), Status> {
+		let mut data = self.write()?;
+
+		// Same as the foreign key of the table
+		if !data
+			.twofa
+			.iter()
+			.any(|settings| settings.user_id == user_id)
+		{
+			return Err(Status::InternalServerError);
+		}
+
+		data.recovery_codes.retain(|code| code.user_id != user_id);
+		data.recovery_codes
+			.extend(code_hashes.iter().map(|code_hash| StoredRecoveryCode {
+				user_id: user_id.to_string(),
+				code_hash: code_hash.clone(),
+			}));
+
+		Ok(())
+	}
+}
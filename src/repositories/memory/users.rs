@@ -0,0 +1,247 @@
+use rocket::http::Status;
+
+use crate::{
+	models::{
+		auth::UserType,
+		users::{Company, Student, University, admin::Admin},
+	},
+	repositories::{Credentials, UserRepository},
+};
+
+use super::{InMemoryRepository, StoredAccount, StoredStudent};
+
+fn credentials(id: &str, mail: &str, password_hash: &str) -> Credentials {
+	Credentials {
+		id: id.to_string(),
+		mail: mail.to_string(),
+		password_hash: password_hash.to_string(),
+	}
+}
+
+#[async_trait]
+impl UserRepository for InMemoryRepository {
+	async fn get_admin(&self, id: &str) -> Result<Option<Admin>, Status> {
+		Ok(self
+			.read()?
+			.admins
+			.iter()
+			.find(|admin| admin.id == id)
+			.cloned())
+	}
+
+	async fn get_university(&self, id: &str) -> Result<Option<University>, Status> {
+		let data = self.read()?;
+
+		Ok(data
+			.universities
+			.iter()
+			.find(|university| university.id == id)
+			.map(|university| data.with_classes(university)))
+	}
+
+	async fn get_company(&self, id: &str) -> Result<Option<Company>, Status> {
+		Ok(self
+			.read()?
+			.companies
+			.iter()
+			.find(|company| company.id == id)
+			.cloned())
+	}
+
+	async fn get_student(&self, id: &str) -> Result<Option<Student>, Status> {
+		Ok(self
+			.read()?
+			.students
+			.iter()
+			.find(|stored| stored.student.id == id)
+			.map(|stored| stored.student.clone()))
+	}
+
+	async fn get_universities(&self) -> Result<Vec<University>, Status> {
+		let data = self.read()?;
+
+		Ok(data
+			.universities
+			.iter()
+			.map(|university| data.with_classes(university))
+			.collect())
+	}
+
+	async fn get_companies(&self) -> Result<Vec<Company>, Status> {
+		Ok(self.read()?.companies.clone())
+	}
+
+	async fn get_credentials(
+		&self,
+		user_type: UserType,
+		login: &str,
+	) -> Result<Option<Credentials>, Status> {
+		let data = self.read()?;
+
+		Ok(match user_type {
+			UserType::Admin => data
+				.admins
+				.iter()
+				.find(|admin| admin.login == login)
+				.map(|admin| credentials(&admin.id, &admin.mail, &admin.password)),
+			UserType::University => data
+				.universities
+				.iter()
+				.find(|university| university.login == login)
+				.map(|university| {
+					credentials(&university.id, &university.mail, &university.password)
+				}),
+			UserType::Student => data
+				.students
+				.iter()
+				.find(|stored| stored.student.login == login)
+				.map(|stored| {
+					credentials(
+						&stored.student.id,
+						&stored.student.mail,
+						&stored.student.password,
+					)
+				}),
+			UserType::Company => data
+				.companies
+				.iter()
+				.find(|company| company.login == login)
+				.map(|company| credentials(&company.id, &company.mail, &company.password)),
+		})
+	}
+
+	async fn is_login_taken(&self, login: &str) -> Result<bool, Status> {
+		Ok(self
+			.read()?
+			.students
+			.iter()
+			.any(|stored| stored.student.login == login))
+	}
+
+	async fn insert_university(
+		&self,
+		university: &University,
+		password_hash: &str,
+	) -> Result<(), Status> {
+		let mut data = self.write()?;
+
+		if data
+			.universities
+			.iter()
+			.any(|stored| stored.login == university.login || stored.mail == university.mail)
+		{
+			return Err(Status::Conflict);
+		}
+
+		data.universities.push(University {
+			password: password_hash.to_string(),
+			class_list: vec![],
+			intership_list: vec![],
+			..university.clone()
+		});
+		data.accounts.push(StoredAccount {
+			mail_verified: university.mail_verified,
+			..StoredAccount::new(UserType::University, &university.id)
+		});
+
+		Ok(())
+	}
+
+	async fn insert_company(&self, company: &Company, password_hash: &str) -> Result<(), Status> {
+		let mut data = self.write()?;
+
+		if data
+			.companies
+			.iter()
+			.any(|stored| stored.login == company.login || stored.mail == company.mail)
+		{
+			return Err(Status::Conflict);
+		}
+
+		data.companies.push(Company {
+			password: password_hash.to_string(),
+			internship_list: vec![],
+			..company.clone()
+		});
+		data.accounts.push(StoredAccount {
+			mail_verified: company.mail_verified,
+			..StoredAccount::new(UserType::Company, &company.id)
+		});
+
+		Ok(())
+	}
+
+	async fn insert_student(
+		&self,
+		student: &Student,
+		password_hash: &str,
+		class_id: &str,
+	) -> Result<(), Status> {
+		let mut data = self.write()?;
+
+		// Same as the foreign key of the table
+		if !data.classes.iter().any(|class| class.id == class_id) {
+			return Err(Status::InternalServerError);
+		}
+
+		// Same as the unique constraints on the login and the mail
+		if data.students.iter().any(|stored| {
+			stored.student.login == student.login || stored.student.mail == student.mail
+		}) {
+			return Err(Status::Conflict);
+		}
+
+		data.students.push(StoredStudent {
+			student: Student {
+				password: password_hash.to_string(),
+				..student.clone()
+			},
+			class_id: class_id.to_string(),
+		});
+		data.accounts
+			.push(StoredAccount::new(UserType::Student, &student.id));
+
+		Ok(())
+	}
+
+	async fn delete_university(&self, id: &str) -> Result<Vec<String>, Status> {
+		let mut data = self.write()?;
+
+		let class_ids: Vec<String> = data
+			.classes
+			.iter()
+			.filter(|class| class.university_id == id)
+			.map(|class| class.id.clone())
+			.collect();
+		let student_ids = data.delete_students_of_classes(&class_ids);
+
+		data.classes.retain(|class| class.university_id != id);
+		data.internships
+			.retain(|stored| stored.university_id.as_deref() != Some(id));
+		data.universities.retain(|university| university.id != id);
+		data.oidc_identities
+			.retain(|identity| identity.university_id != id);
+		data.oidc_providers
+			.retain(|provider| provider.university_id != id);
+		data.accounts
+			.retain(|account| account.user_type != UserType::University || account.id != id);
+		data.delete_twofa(id);
+
+		Ok(student_ids)
+	}
+
+	async fn delete_company(&self, id: &str) -> Result<(), Status> {
+		let mut data = self.write()?;
+
+		data.internships
+			.retain(|stored| stored.company_id.as_deref() != Some(id));
+		data.companies.retain(|company| company.id != id);
+		data.api_keys
+			.retain(|stored| stored.api_key.company_id != id);
+		data.accounts
+			.retain(|account| account.user_type != UserType::Company || account.id != id);
+		data.delete_twofa(id);
+
+		Ok(())
+	}
+}
@@ -0,0 +1,390 @@
+//! Storage of the API behind one trait per aggregate: users and their accounts, classes,
+//! internships, course types, 2FA settings, API keys, OIDC providers and the audit log.
+//!
+//! The API manages `Repositories::postgres()` built on its pool of connections. A Rocket built
+//! with `Repositories::in_memory()` keeps everything in the process, e.g. for
+//! `rocket::local::asynchronous::Client`. Only the sessions and the other short-lived data stay
+//! in Redis.
+
+use std::{fmt::Debug, sync::Arc};
+
+use deadpool_postgres::Pool;
+use rocket::{Request, http::Status};
+
+use crate::{
+	error_handling::StatusOptionHandling,
+	models::{
+		audit::{AuditEvent, AuditFilter, NewAuditEvent},
+		auth::{AccountStatus, ApiKey, OidcProvider, TwofaMethod, TwofaSettings, UserType},
+		courses::{Class, CourseType, Internship},
+		users::{Company, Student, University, admin::Admin},
+	},
+};
+
+mod memory;
+mod postgres;
+
+pub use memory::InMemoryRepository;
+pub use postgres::PostgresRepository;
+
+/// What is needed to check the password of an account.
+#[derive(Debug, Clone)]
+pub struct Credentials {
+	pub id: String,
+	pub mail: String,
+	pub password_hash: String,
+}
+
+#[async_trait]
+pub trait UserRepository: Debug + Send + Sync {
+	async fn get_admin(&self, id: &str) -> Result<Option<Admin>, Status>;
+
+	/// The university comes with its classes.
+	async fn get_university(&self, id: &str) -> Result<Option<University>, Status>;
+
+	async fn get_company(&self, id: &str) -> Result<Option<Company>, Status>;
+
+	async fn get_student(&self, id: &str) -> Result<Option<Student>, Status>;
+
+	async fn get_universities(&self) -> Result<Vec<University>, Status>;
+
+	async fn get_companies(&self) -> Result<Vec<Company>, Status>;
+
+	async fn get_credentials(
+		&self,
+		user_type: UserType,
+		login: &str,
+	) -> Result<Option<Credentials>, Status>;
+
+	/// Whether a student already uses this login, they are generated from the names.
+	async fn is_login_taken(&self, login: &str) -> Result<bool, Status>;
+
+	async fn insert_university(
+		&self,
+		university: &University,
+		password_hash: &str,
+	) -> Result<(), Status>;
+
+	async fn insert_company(&self, company: &Company, password_hash: &str) -> Result<(), Status>;
+
+	async fn insert_student(
+		&self,
+		student: &Student,
+		password_hash: &str,
+		class_id: &str,
+	) -> Result<(), Status>;
+
+	/// Deletes the university with its classes, returns the ids of the students deleted with them.
+	async fn delete_university(&self, id: &str) -> Result<Vec<String>, Status>;
+
+	async fn delete_company(&self, id: &str) -> Result<(), Status>;
+}
+
+/// Column an account is looked up by.
+#[derive(Debug, Clone, Copy)]
+pub enum AccountLookup {
+	Id,
+	Login,
+	Mail,
+}
+
+impl AccountLookup {
+	#[must_use]
+	pub const fn column(self) -> &'static str {
+		match self {
+			Self::Id => "id",
+			Self::Login => "login",
+			Self::Mail => "mail",
+		}
+	}
+}
+
+/// State of the accounts of every user type: status, password and mail verification.
+#[async_trait]
+pub trait AccountRepository: Debug + Send + Sync {
+	async fn find_id_by_mail(
+		&self,
+		user_type: UserType,
+		mail: &str,
+	) -> Result<Option<String>, Status>;
+
+	/// Status of the account and the reason given by the admin.
+	async fn get_status(
+		&self,
+		user_type: UserType,
+		lookup: AccountLookup,
+		value: &str,
+	) -> Result<Option<(AccountStatus, Option<String>)>, Status>;
+
+	/// Returns whether an account was updated.
+	async fn set_status(
+		&self,
+		user_type: UserType,
+		user_id: &str,
+		status: AccountStatus,
+		reason: Option<&str>,
+	) -> Result<bool, Status>;
+
+	/// Mail address of the account and whether it was verified, admins are always verified.
+	async fn get_mail_verification(
+		&self,
+		user_type: UserType,
+		user_id: &str,
+	) -> Result<Option<(String, bool)>, Status>;
+
+	/// Returns whether an account was updated, never the case of admins.
+	async fn set_mail_verified(&self, user_type: UserType, user_id: &str) -> Result<bool, Status>;
+
+	async fn get_password_hash(
+		&self,
+		user_type: UserType,
+		user_id: &str,
+	) -> Result<Option<String>, Status>;
+
+	/// Sets a password chosen by the user, which lifts the forced change on next login.
+	async fn update_password(
+		&self,
+		user_type: UserType,
+		user_id: &str,
+		password_hash: &str,
+	) -> Result<(), Status>;
+
+	/// Accounts created with a generated password must choose their own before using the API.
+	async fn must_change_password(
+		&self,
+		user_type: UserType,
+		user_id: &str,
+	) -> Result<bool, Status>;
+
+	/// Refuses accounts which are not active with `403 Forbidden`, checked at each login.
+	async fn ensure_active(&self, user_type: UserType, user_id: &str) -> Result<(), Status> {
+		match self
+			.get_status(user_type, AccountLookup::Id, user_id)
+			.await?
+		{
+			Some((AccountStatus::Active, _)) => Ok(()),
+			_ => Err(Status::Forbidden),
+		}
+	}
+}
+
+#[async_trait]
+pub trait ClassRepository: Debug + Send + Sync {
+	async fn get(&self, id: &str) -> Result<Option<Class>, Status>;
+
+	async fn get_by_university(&self, university_id: &str) -> Result<Vec<Class>, Status>;
+
+	async fn get_by_student(&self, student_id: &str) -> Result<Option<Class>, Status>;
+
+	async fn get_students(&self, class_id: &str) -> Result<Vec<Student>, Status>;
+
+	async fn insert(&self, class: &Class) -> Result<(), Status>;
+
+	/// Returns the ids of the students deleted with the class.
+	async fn delete(&self, id: &str) -> Result<Vec<String>, Status>;
+}
+
+#[async_trait]
+pub trait InternshipRepository: Debug + Send + Sync {
+	async fn get_all(&self) -> Result<Vec<Internship>, Status>;
+
+	async fn get_by_company(&self, company_id: &str) -> Result<Vec<Internship>, Status>;
+
+	async fn get_by_university(&self, university_id: &str) -> Result<Vec<Internship>, Status>;
+
+	async fn get_by_course_types(
+		&self,
+		course_types: &[CourseType],
+	) -> Result<Vec<Internship>, Status>;
+
+	async fn is_owned_by_company(
+		&self,
+		internship_id: &str,
+		company_id: &str,
+	) -> Result<bool, Status>;
+
+	async fn insert_for_company(
+		&self,
+		internship: &Internship,
+		company_id: &str,
+	) -> Result<(), Status>;
+
+	async fn insert_for_university(
+		&self,
+		internship: &Internship,
+		university_id: &str,
+	) -> Result<(), Status>;
+
+	async fn delete(&self, id: &str) -> Result<(), Status>;
+}
+
+#[async_trait]
+pub trait CourseTypeRepository: Debug + Send + Sync {
+	async fn get_all(&self) -> Result<Vec<CourseType>, Status>;
+
+	/// Course type of each class of the university.
+	async fn get_by_university(&self, university_id: &str) -> Result<Vec<CourseType>, Status>;
+
+	async fn get_by_student(&self, student_id: &str) -> Result<Option<CourseType>, Status>;
+}
+
+/// 2FA method of the users and their TOTP recovery codes, stored hashed.
+#[async_trait]
+pub trait TwofaRepository: Debug + Send + Sync {
+	/// Users without settings use the emailed code.
+	async fn get_settings(&self, user_id: &str) -> Result<TwofaSettings, Status>;
+
+	/// Keeps the secret of a TOTP enrollment until it is confirmed, without changing the method.
+	async fn set_pending_secret(
+		&self,
+		user_id: &str,
+		method: TwofaMethod,
+		secret: &str,
+	) -> Result<(), Status>;
+
+	/// Switches to TOTP with the pending secret, `step` is the one of the confirmation code.
+	async fn activate_totp(&self, user_id: &str, secret: &str, step: i64) -> Result<(), Status>;
+
+	/// Records the time step of an accepted TOTP code, returns `false` if it is not after the
+	/// last one recorded.
+	async fn use_totp_step(&self, user_id: &str, step: i64) -> Result<bool, Status>;
+
+	/// Back to the emailed code, the recovery codes go with the settings.
+	async fn delete(&self, user_id: &str) -> Result<(), Status>;
+
+	/// Deletes the recovery code and returns whether it existed.
+	async fn use_recovery_code(&self, user_id: &str, code_hash: &str) -> Result<bool, Status>;
+
+	async fn replace_recovery_codes(
+		&self,
+		user_id: &str,
+		code_hashes: &[String],
+	) -> Result<(), Status>;
+}
+
+#[async_trait]
+pub trait ApiKeyRepository: Debug + Send + Sync {
+	async fn insert(&self, api_key: &ApiKey, key_hash: &str) -> Result<(), Status>;
+
+	/// Newest first.
+	async fn get_by_company(&self, company_id: &str) -> Result<Vec<ApiKey>, Status>;
+
+	/// Finds the key with this hash if it is not expired, and records that it was used.
+	async fn authenticate(&self, key_hash: &str) -> Result<Option<ApiKey>, Status>;
+
+	/// Deletes the key if it belongs to the company, returns whether a key was deleted.
+	async fn delete(&self, company_id: &str, id: &str) -> Result<bool, Status>;
+}
+
+/// Identity providers of the universities and the subjects linked to their accounts.
+#[async_trait]
+pub trait OidcProviderRepository: Debug + Send + Sync {
+	async fn get(&self, university_id: &str) -> Result<Option<OidcProvider>, Status>;
+
+	/// Universities offering OIDC login by name, as `(university_id, university_name)`.
+	async fn get_universities(&self) -> Result<Vec<(String, String)>, Status>;
+
+	/// Creates or replaces the provider of the university.
+	async fn save(&self, provider: &OidcProvider) -> Result<(), Status>;
+
+	/// The subjects linked through the provider go with it.
+	async fn delete(&self, university_id: &str) -> Result<(), Status>;
+
+	async fn find_identity(
+		&self,
+		university_id: &str,
+		subject: &str,
+	) -> Result<Option<(String, UserType)>, Status>;
+
+	async fn link_identity(
+		&self,
+		university_id: &str,
+		subject: &str,
+		user_id: &str,
+		user_type: UserType,
+	) -> Result<(), Status>;
+
+	/// Finds the university itself or one of its students.
+	async fn find_member(
+		&self,
+		university_id: &str,
+		lookup: AccountLookup,
+		value: &str,
+	) -> Result<Option<(String, UserType)>, Status>;
+}
+
+/// Append-only audit log.
+#[async_trait]
+pub trait AuditRepository: Debug + Send + Sync {
+	async fn record(&self, event: &NewAuditEvent) -> Result<(), Status>;
+
+	/// Returns a page of the events matching the filter, newest first, and the total match count.
+	async fn search(
+		&self,
+		filter: &AuditFilter,
+		limit: i64,
+		offset: i64,
+	) -> Result<(Vec<AuditEvent>, i64), Status>;
+}
+
+/// Managed by Rocket, routes take it as `&State<Repositories>`.
+#[derive(Debug, Clone)]
+pub struct Repositories {
+	pub users: Arc<dyn UserRepository>,
+	pub accounts: Arc<dyn AccountRepository>,
+	pub classes: Arc<dyn ClassRepository>,
+	pub internships: Arc<dyn InternshipRepository>,
+	pub course_types: Arc<dyn CourseTypeRepository>,
+	pub twofa: Arc<dyn TwofaRepository>,
+	pub api_keys: Arc<dyn ApiKeyRepository>,
+	pub oidc_providers: Arc<dyn OidcProviderRepository>,
+	pub audit: Arc<dyn AuditRepository>,
+}
+
+impl Repositories {
+	#[must_use]
+	pub fn postgres(pool: Pool) -> Self {
+		Self::from_backend(&Arc::new(PostgresRepository::new(pool)))
+	}
+
+	/// Empty store apart from the course types, filled through the repositories themselves.
+	#[must_use]
+	pub fn in_memory() -> Self {
+		Self::from_backend(&Arc::new(InMemoryRepository::default()))
+	}
+
+	/// Every aggregate served by the same backend, e.g. an `InMemoryRepository` given admins.
+	pub fn from_backend<T>(backend: &Arc<T>) -> Self
+	where
+		T: UserRepository
+			+ AccountRepository
+			+ ClassRepository
+			+ InternshipRepository
+			+ CourseTypeRepository
+			+ TwofaRepository
+			+ ApiKeyRepository
+			+ OidcProviderRepository
+			+ AuditRepository
+			+ 'static,
+	{
+		Self {
+			users: backend.clone(),
+			accounts: backend.clone(),
+			classes: backend.clone(),
+			internships: backend.clone(),
+			course_types: backend.clone(),
+			twofa: backend.clone(),
+			api_keys: backend.clone(),
+			oidc_providers: backend.clone(),
+			audit: backend.clone(),
+		}
+	}
+
+	/// Repositories of the Rocket serving the request, for the request guards.
+	pub fn from_request<'r>(request: &'r Request<'_>) -> Result<&'r Self, Status> {
+		request
+			.rocket()
+			.state::<Self>()
+			.internal_server_error("Repositories are not managed")
+	}
+}
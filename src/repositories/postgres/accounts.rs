@@ -0,0 +1,192 @@
+use rocket::http::Status;
+
+use crate::{
+	error_handling::StatusResultHandling,
+	models::auth::{AccountStatus, UserType},
+	repositories::{AccountLookup, AccountRepository},
+};
+
+use super::PostgresRepository;
+
+#[async_trait]
+impl AccountRepository for PostgresRepository {
+	async fn find_id_by_mail(
+		&self,
+		user_type: UserType,
+		mail: &str,
+	) -> Result<Option<String>, Status> {
+		let client = self.client().await?;
+
+		let row = client
+			.query_opt(
+				&format!("SELECT id FROM {} WHERE mail=$1;", user_type.table_name()),
+				&[&mail],
+			)
+			.await
+			.internal_server_error("Error during selection of user by mail")?;
+
+		Ok(row.map(|row| row.get(0)))
+	}
+
+	async fn get_status(
+		&self,
+		user_type: UserType,
+		lookup: AccountLookup,
+		value: &str,
+	) -> Result<Option<(AccountStatus, Option<String>)>, Status> {
+		let client = self.client().await?;
+
+		let row = client
+			.query_opt(
+				&format!(
+					"SELECT status, status_reason FROM {} WHERE {}=$1;",
+					user_type.table_name(),
+					lookup.column()
+				),
+				&[&value],
+			)
+			.await
+			.internal_server_error("Error during selection of account status")?;
+
+		row.map(|row| {
+			let status: String = row.get(0);
+			Ok((status.parse()?, row.get(1)))
+		})
+		.transpose()
+	}
+
+	async fn set_status(
+		&self,
+		user_type: UserType,
+		user_id: &str,
+		status: AccountStatus,
+		reason: Option<&str>,
+	) -> Result<bool, Status> {
+		let client = self.client().await?;
+
+		let updated = client
+			.execute(
+				&format!(
+					"UPDATE {} SET status=$2, status_reason=$3 WHERE id=$1;",
+					user_type.table_name()
+				),
+				&[&user_id, &status.to_string(), &reason],
+			)
+			.await
+			.internal_server_error("Error during update of account status")?;
+
+		Ok(updated == 1)
+	}
+
+	async fn get_mail_verification(
+		&self,
+		user_type: UserType,
+		user_id: &str,
+	) -> Result<Option<(String, bool)>, Status> {
+		let client = self.client().await?;
+		let query = match user_type {
+			UserType::Admin => "SELECT mail, TRUE FROM admin WHERE id=$1;".to_string(),
+			_ => format!(
+				"SELECT mail, mail_verified FROM {} WHERE id=$1;",
+				user_type.table_name()
+			),
+		};
+
+		let row = client
+			.query_opt(&query, &[&user_id])
+			.await
+			.internal_server_error("Error during selection of mail_verified")?;
+
+		Ok(row.map(|row| (row.get(0), row.get(1))))
+	}
+
+	async fn set_mail_verified(&self, user_type: UserType, user_id: &str) -> Result<bool, Status> {
+		if user_type == UserType::Admin {
+			return Ok(false);
+		}
+
+		let client = self.client().await?;
+
+		let updated = client
+			.execute(
+				&format!(
+					"UPDATE {} SET mail_verified=TRUE WHERE id=$1;",
+					user_type.table_name()
+				),
+				&[&user_id],
+			)
+			.await
+			.internal_server_error("Error during update of mail_verified")?;
+
+		Ok(updated == 1)
+	}
+
+	async fn get_password_hash(
+		&self,
+		user_type: UserType,
+		user_id: &str,
+	) -> Result<Option<String>, Status> {
+		let client = self.client().await?;
+
+		let row = client
+			.query_opt(
+				&format!(
+					"SELECT password FROM {} WHERE id=$1;",
+					user_type.table_name()
+				),
+				&[&user_id],
+			)
+			.await
+			.internal_server_error("Error during selection of password")?;
+
+		Ok(row.map(|row| row.get(0)))
+	}
+
+	async fn update_password(
+		&self,
+		user_type: UserType,
+		user_id: &str,
+		password_hash: &str,
+	) -> Result<(), Status> {
+		let client = self.client().await?;
+		let query = match user_type {
+			UserType::Admin => "UPDATE admin SET password=$2 WHERE id=$1;".to_string(),
+			_ => format!(
+				"UPDATE {} SET password=$2, must_change_password=FALSE WHERE id=$1;",
+				user_type.table_name()
+			),
+		};
+
+		client
+			.execute(&query, &[&user_id, &password_hash])
+			.await
+			.internal_server_error("Error during password update")?;
+
+		Ok(())
+	}
+
+	async fn must_change_password(
+		&self,
+		user_type: UserType,
+		user_id: &str,
+	) -> Result<bool, Status> {
+		if user_type == UserType::Admin {
+			return Ok(false);
+		}
+
+		let client = self.client().await?;
+
+		let row = client
+			.query_one(
+				&format!(
+					"SELECT must_change_password FROM {} WHERE id=$1;",
+					user_type.table_name()
+				),
+				&[&user_id],
+			)
+			.await
+			.internal_server_error("Error during selection of must_change_password")?;
+
+		Ok(row.get(0))
+	}
+}
@@ -0,0 +1,106 @@
+use std::str::FromStr;
+
+use rocket::http::Status;
+use tokio_postgres::Row;
+
+use crate::{
+	error_handling::StatusResultHandling,
+	models::auth::{ApiKey, ApiKeyScope},
+	repositories::ApiKeyRepository,
+};
+
+use super::PostgresRepository;
+
+const API_KEY_COLUMNS: &str =
+	"id, company_id, name, prefix, scopes, created_at, expires_at, last_used_at";
+
+fn api_key_from_row(row: &Row) -> Result<ApiKey, Status> {
+	let scopes: Vec<String> = row.get(4);
+
+	Ok(ApiKey {
+		id: row.get(0),
+		company_id: row.get(1),
+		name: row.get(2),
+		prefix: row.get(3),
+		scopes: scopes
+			.iter()
+			.map(|scope| ApiKeyScope::from_str(scope))
+			.collect::<Result<_, _>>()?,
+		created_at: row.get(5),
+		expires_at: row.get(6),
+		last_used_at: row.get(7),
+	})
+}
+
+#[async_trait]
+impl ApiKeyRepository for PostgresRepository {
+	async fn insert(&self, api_key: &ApiKey, key_hash: &str) -> Result<(), Status> {
+		let client = self.client().await?;
+		let scopes: Vec<String> = api_key.scopes.iter().map(ToString::to_string).collect();
+
+		client
+			.execute(
+				"INSERT INTO api_key (id, company_id, name, prefix, key_hash, scopes, created_at, expires_at) VALUES ($1, $2, $3, $4, $5, $6, $7, $8);",
+				&[
+					&api_key.id,
+					&api_key.company_id,
+					&api_key.name,
+					&api_key.prefix,
+					&key_hash,
+					&scopes,
+					&api_key.created_at,
+					&api_key.expires_at,
+				],
+			)
+			.await
+			.internal_server_error("Error during api_key insert")?;
+
+		Ok(())
+	}
+
+	async fn get_by_company(&self, company_id: &str) -> Result<Vec<ApiKey>, Status> {
+		let client = self.client().await?;
+
+		let rows = client
+			.query(
+				&format!(
+					"SELECT {API_KEY_COLUMNS} FROM api_key WHERE company_id=$1 ORDER BY created_at DESC;"
+				),
+				&[&company_id],
+			)
+			.await
+			.internal_server_error("SELECT api_key error")?;
+
+		rows.iter().map(api_key_from_row).collect()
+	}
+
+	async fn authenticate(&self, key_hash: &str) -> Result<Option<ApiKey>, Status> {
+		let client = self.client().await?;
+
+		let row = client
+			.query_opt(
+				&format!(
+					"UPDATE api_key SET last_used_at = NOW() WHERE key_hash=$1 AND (expires_at IS NULL OR expires_at > NOW()) RETURNING {API_KEY_COLUMNS};"
+				),
+				&[&key_hash],
+			)
+			.await
+			.internal_server_error("Error while checking api_key")?;
+
+		row.as_ref().map(api_key_from_row).transpose()
+	}
+
+	async fn delete(&self, company_id: &str, id: &str) -> Result<bool, Status> {
+		let client = self.client().await?;
+
+		let deleted = client
+			.execute(
+				"DELETE FROM api_key WHERE id=$1 AND company_id=$2;",
+				&[&id, &company_id],
+			)
+			.await
+			.internal_server_error("Error during api_key deletion")?;
+
+		Ok(deleted == 1)
+	}
+}
@@ -0,0 +1,123 @@
+use std::str::FromStr;
+
+use rocket::http::Status;
+use tokio_postgres::Row;
+
+use crate::{
+	error_handling::StatusResultHandling,
+	models::{
+		audit::{AuditAction, AuditEvent, AuditFilter, AuditOutcome, NewAuditEvent},
+		auth::UserType,
+	},
+	repositories::AuditRepository,
+};
+
+use super::PostgresRepository;
+
+fn audit_event_from_row(row: &Row) -> Result<AuditEvent, Status> {
+	let actor_role: Option<String> = row.get(3);
+	let action: String = row.get(5);
+	let outcome: String = row.get(8);
+
+	Ok(AuditEvent {
+		id: row.get(0),
+		occurred_at: row.get(1),
+		actor_id: row.get(2),
+		actor_role: actor_role
+			.map(|actor_role| UserType::from_str(&actor_role))
+			.transpose()?,
+		impersonator_id: row.get(4),
+		action: AuditAction::from_str(&action)
+			.internal_server_error("Unknown action in audit_log")?,
+		target_id: row.get(6),
+		ip: row.get(7),
+		outcome: AuditOutcome::from_str(&outcome)
+			.internal_server_error("Unknown outcome in audit_log")?,
+	})
+}
+
+#[async_trait]
+impl AuditRepository for PostgresRepository {
+	async fn record(&self, event: &NewAuditEvent) -> Result<(), Status> {
+		let client = self.client().await?;
+
+		client
+			.execute(
+				"INSERT INTO audit_log (actor_id, actor_role, impersonator_id, action, target_id, ip, outcome) VALUES ($1, $2, $3, $4, $5, $6, $7);",
+				&[
+					&event.actor_id,
+					&event.actor_role.map(|actor_role| actor_role.to_string()),
+					&event.impersonator_id,
+					&event.action.to_string(),
+					&event.target_id,
+					&event.ip.map(|ip| ip.to_string()),
+					&event.outcome.to_string(),
+				],
+			)
+			.await
+			.internal_server_error("Error during audit_log insert")?;
+
+		Ok(())
+	}
+
+	async fn search(
+		&self,
+		filter: &AuditFilter,
+		limit: i64,
+		offset: i64,
+	) -> Result<(Vec<AuditEvent>, i64), Status> {
+		let client = self.client().await?;
+
+		// A NULL parameter disables the corresponding condition
+		let conditions = "($1::TEXT IS NULL OR actor_id = $1) AND ($2::TEXT IS NULL OR actor_role = $2) AND ($3::TEXT IS NULL OR action = $3) AND ($4::TEXT IS NULL OR target_id = $4) AND ($5::TEXT IS NULL OR outcome = $5) AND ($6::TIMESTAMPTZ IS NULL OR occurred_at >= $6) AND ($7::TIMESTAMPTZ IS NULL OR occurred_at < $7) AND ($8::TEXT IS NULL OR impersonator_id = $8)";
+		let actor_role = filter.actor_role.map(|actor_role| actor_role.to_string());
+		let action = filter.action.map(|action| action.to_string());
+		let outcome = filter.outcome.map(|outcome| outcome.to_string());
+
+		let total: i64 = client
+			.query_one(
+				&format!("SELECT COUNT(*) FROM audit_log WHERE {conditions};"),
+				&[
+					&filter.actor_id,
+					&actor_role,
+					&action,
+					&filter.target_id,
+					&outcome,
+					&filter.from,
+					&filter.to,
+					&filter.impersonator_id,
+				],
+			)
+			.await
+			.internal_server_error("COUNT audit_log error")?
+			.get(0);
+
+		let rows = client
+			.query(
+				&format!(
+					"SELECT id, occurred_at, actor_id, actor_role, impersonator_id, action, target_id, ip, outcome FROM audit_log WHERE {conditions} ORDER BY occurred_at DESC, id DESC LIMIT $9 OFFSET $10;"
+				),
+				&[
+					&filter.actor_id,
+					&actor_role,
+					&action,
+					&filter.target_id,
+					&outcome,
+					&filter.from,
+					&filter.to,
+					&filter.impersonator_id,
+					&limit,
+					&offset,
+				],
+			)
+			.await
+			.internal_server_error("SELECT audit_log error")?;
+
+		Ok((
+			rows.iter()
+				.map(audit_event_from_row)
+				.collect::<Result<_, _>>()?,
+			total,
+		))
+	}
+}
@@ -0,0 +1,139 @@
+use rocket::http::Status;
+use tokio_postgres::Row;
+
+use crate::{
+	error_handling::StatusResultHandling,
+	models::{
+		courses::{Class, CourseType},
+		users::Student,
+	},
+	repositories::ClassRepository,
+};
+
+use super::PostgresRepository;
+
+pub(super) const CLASS_COLUMNS: &str =
+	"id, name, course_type, start_date, end_date, min_length, max_length, university_id";
+
+// The maximum length is stored in `min_length` and the minimum in `max_length`, as in `insert`
+pub(super) fn class_from_row(row: &Row) -> Result<Class, Status> {
+	Ok(Class {
+		id: row.get(0),
+		name: row.get(1),
+		course_type: CourseType::from_sql(row.get(2))?,
+		date_internship_start: row.get(3),
+		date_internship_end: row.get(4),
+		maximum_internship_length: row.get(5),
+		minimum_internship_length: row.get(6),
+		university_id: row.get(7),
+	})
+}
+
+#[async_trait]
+impl ClassRepository for PostgresRepository {
+	async fn get(&self, id: &str) -> Result<Option<Class>, Status> {
+		let client = self.client().await?;
+
+		let row = client
+			.query_opt(
+				&format!("SELECT {CLASS_COLUMNS} FROM class WHERE id=$1;"),
+				&[&id],
+			)
+			.await
+			.internal_server_error("Error during class select")?;
+
+		row.as_ref().map(class_from_row).transpose()
+	}
+
+	async fn get_by_university(&self, university_id: &str) -> Result<Vec<Class>, Status> {
+		let client = self.client().await?;
+
+		let rows = client
+			.query(
+				&format!("SELECT {CLASS_COLUMNS} FROM class WHERE university_id=$1;"),
+				&[&university_id],
+			)
+			.await
+			.internal_server_error("Error getting classes")?;
+
+		rows.iter().map(class_from_row).collect()
+	}
+
+	async fn get_by_student(&self, student_id: &str) -> Result<Option<Class>, Status> {
+		let client = self.client().await?;
+
+		let row = client
+			.query_opt(
+				&format!(
+					"SELECT {CLASS_COLUMNS} FROM class WHERE id=(SELECT class_id FROM student WHERE id=$1);"
+				),
+				&[&student_id],
+			)
+			.await
+			.internal_server_error("Error getting class of student")?;
+
+		row.as_ref().map(class_from_row).transpose()
+	}
+
+	async fn get_students(&self, class_id: &str) -> Result<Vec<Student>, Status> {
+		let client = self.client().await?;
+
+		let rows = client
+			.query(
+				"SELECT id, first_name, last_name, login, password, mail FROM student WHERE class_id=$1;",
+				&[&class_id],
+			)
+			.await
+			.internal_server_error("Error getting students")?;
+
+		Ok(rows
+			.iter()
+			.map(|row| Student {
+				id: row.get(0),
+				first_name: row.get(1),
+				last_name: row.get(2),
+				login: row.get(3),
+				password: row.get(4),
+				mail: row.get(5),
+			})
+			.collect())
+	}
+
+	async fn insert(&self, class: &Class) -> Result<(), Status> {
+		let client = self.client().await?;
+
+		client
+			.execute(
+				"INSERT INTO class (id, name, course_type, start_date, end_date, min_length, max_length, university_id) VALUES ($1, $2, $3, $4, $5, $6, $7, $8);",
+				&[
+					&class.id,
+					&class.name,
+					&class.course_type.to_sql(),
+					&class.date_internship_start,
+					&class.date_internship_end,
+					&class.maximum_internship_length,
+					&class.minimum_internship_length,
+					&class.university_id,
+				],
+			)
+			.await
+			.internal_server_error("Error during class insert")?;
+
+		Ok(())
+	}
+
+	async fn delete(&self, id: &str) -> Result<Vec<String>, Status> {
+		let client = self.client().await?;
+
+		// Same statement for the `twofa` rows of the students, which have no foreign key
+		let students = client
+			.query(
+				"WITH students AS (SELECT id FROM student WHERE class_id=$1), deleted_twofa AS (DELETE FROM twofa WHERE user_id IN (SELECT id FROM students)), deleted_class AS (DELETE FROM class WHERE id=$1) SELECT id FROM students;",
+				&[&id],
+			)
+			.await
+			.internal_server_error("Error while deleting a class")?;
+
+		Ok(students.iter().map(|row| row.get(0)).collect())
+	}
+}
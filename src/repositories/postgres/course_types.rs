@@ -0,0 +1,54 @@
+use rocket::http::Status;
+
+use crate::{
+	error_handling::StatusResultHandling, models::courses::CourseType,
+	repositories::CourseTypeRepository,
+};
+
+use super::PostgresRepository;
+
+#[async_trait]
+impl CourseTypeRepository for PostgresRepository {
+	async fn get_all(&self) -> Result<Vec<CourseType>, Status> {
+		let client = self.client().await?;
+
+		let rows = client
+			.query("SELECT id FROM course_type ORDER BY id;", &[])
+			.await
+			.internal_server_error("Error getting course types")?;
+
+		rows.iter()
+			.map(|row| CourseType::from_sql(row.get(0)))
+			.collect()
+	}
+
+	async fn get_by_university(&self, university_id: &str) -> Result<Vec<CourseType>, Status> {
+		let client = self.client().await?;
+
+		let rows = client
+			.query(
+				"SELECT course_type FROM class WHERE university_id=$1;",
+				&[&university_id],
+			)
+			.await
+			.internal_server_error("Error getting course types of university")?;
+
+		rows.iter()
+			.map(|row| CourseType::from_sql(row.get(0)))
+			.collect()
+	}
+
+	async fn get_by_student(&self, student_id: &str) -> Result<Option<CourseType>, Status> {
+		let client = self.client().await?;
+
+		let row = client
+			.query_opt(
+				"SELECT class.course_type FROM class JOIN student ON student.class_id = class.id WHERE student.id=$1;",
+				&[&student_id],
+			)
+			.await
+			.internal_server_error("Error getting course type of student")?;
+
+		row.map(|row| CourseType::from_sql(row.get(0))).transpose()
+	}
+}
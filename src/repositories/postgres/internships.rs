@@ -0,0 +1,170 @@
+use rocket::http::Status;
+use tokio_postgres::Row;
+
+use crate::{
+	error_handling::StatusResultHandling,
+	models::courses::{CourseType, Internship},
+	repositories::InternshipRepository,
+};
+
+use super::PostgresRepository;
+
+const INTERNSHIP_COLUMNS: &str = "id, course_type, start_date, end_date, min_internship_length, max_internship_length, title, description, place";
+
+fn internship_from_row(row: &Row) -> Result<Internship, Status> {
+	Ok(Internship {
+		id: row.get(0),
+		course_type: CourseType::from_sql(row.get(1))?,
+		date_start: row.get(2),
+		date_end: row.get(3),
+		min_internship_length: row.get(4),
+		max_internship_length: row.get(5),
+		title: row.get(6),
+		description: row.get(7),
+		place: row.get(8),
+	})
+}
+
+impl PostgresRepository {
+	async fn insert_internship(
+		&self,
+		internship: &Internship,
+		owner_column: &str,
+		owner_id: &str,
+	) -> Result<(), Status> {
+		let client = self.client().await?;
+
+		client
+			.execute(
+				&format!(
+					"INSERT INTO internship (id, course_type, {owner_column}, start_date, end_date, min_internship_length, max_internship_length, title, description, place) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10);"
+				),
+				&[
+					&internship.id,
+					&internship.course_type.to_sql(),
+					&owner_id,
+					&internship.date_start,
+					&internship.date_end,
+					&internship.min_internship_length,
+					&internship.max_internship_length,
+					&internship.title,
+					&internship.description,
+					&internship.place,
+				],
+			)
+			.await
+			.internal_server_error("Failed to insert internship")?;
+
+		Ok(())
+	}
+}
+
+#[async_trait]
+impl InternshipRepository for PostgresRepository {
+	async fn get_all(&self) -> Result<Vec<Internship>, Status> {
+		let client = self.client().await?;
+
+		let rows = client
+			.query(
+				&format!("SELECT {INTERNSHIP_COLUMNS} FROM internship;"),
+				&[],
+			)
+			.await
+			.internal_server_error("SELECT error")?;
+
+		rows.iter().map(internship_from_row).collect()
+	}
+
+	async fn get_by_company(&self, company_id: &str) -> Result<Vec<Internship>, Status> {
+		let client = self.client().await?;
+
+		let rows = client
+			.query(
+				&format!("SELECT {INTERNSHIP_COLUMNS} FROM internship WHERE company_id=$1;"),
+				&[&company_id],
+			)
+			.await
+			.internal_server_error("SELECT error")?;
+
+		rows.iter().map(internship_from_row).collect()
+	}
+
+	async fn get_by_university(&self, university_id: &str) -> Result<Vec<Internship>, Status> {
+		let client = self.client().await?;
+
+		let rows = client
+			.query(
+				&format!("SELECT {INTERNSHIP_COLUMNS} FROM internship WHERE university_id=$1;"),
+				&[&university_id],
+			)
+			.await
+			.internal_server_error("SELECT error")?;
+
+		rows.iter().map(internship_from_row).collect()
+	}
+
+	async fn get_by_course_types(
+		&self,
+		course_types: &[CourseType],
+	) -> Result<Vec<Internship>, Status> {
+		let client = self.client().await?;
+		let course_types: Vec<i32> = course_types.iter().map(CourseType::to_sql).collect();
+
+		let rows = client
+			.query(
+				&format!("SELECT {INTERNSHIP_COLUMNS} FROM internship WHERE course_type=ANY($1);"),
+				&[&course_types],
+			)
+			.await
+			.internal_server_error("SELECT error")?;
+
+		rows.iter().map(internship_from_row).collect()
+	}
+
+	async fn is_owned_by_company(
+		&self,
+		internship_id: &str,
+		company_id: &str,
+	) -> Result<bool, Status> {
+		let client = self.client().await?;
+
+		let row = client
+			.query_opt(
+				"SELECT 1 FROM internship WHERE id=$1 AND company_id=$2;",
+				&[&internship_id, &company_id],
+			)
+			.await
+			.internal_server_error("Error checking internship owner")?;
+
+		Ok(row.is_some())
+	}
+
+	async fn insert_for_company(
+		&self,
+		internship: &Internship,
+		company_id: &str,
+	) -> Result<(), Status> {
+		self.insert_internship(internship, "company_id", company_id)
+			.await
+	}
+
+	async fn insert_for_university(
+		&self,
+		internship: &Internship,
+		university_id: &str,
+	) -> Result<(), Status> {
+		self.insert_internship(internship, "university_id", university_id)
+			.await
+	}
+
+	async fn delete(&self, id: &str) -> Result<(), Status> {
+		let client = self.client().await?;
+
+		client
+			.execute("DELETE FROM internship WHERE id=$1;", &[&id])
+			.await
+			.internal_server_error("Error while deleting an internship")?;
+
+		Ok(())
+	}
+}
@@ -0,0 +1,43 @@
+use deadpool_postgres::Pool;
+use rocket::http::Status;
+use tokio_postgres::error::SqlState;
+use tracing::error;
+
+use crate::postgres::{Database, setup_database};
+
+mod accounts;
+mod api_keys;
+mod audit;
+mod classes;
+mod course_types;
+mod internships;
+mod oidc_providers;
+mod twofa;
+mod users;
+
+/// Backend of the API, every call takes a connection from the pool.
+#[derive(Debug, Clone)]
+pub struct PostgresRepository {
+	pool: Pool,
+}
+
+impl PostgresRepository {
+	#[must_use]
+	pub const fn new(pool: Pool) -> Self {
+		Self { pool }
+	}
+
+	async fn client(&self) -> Result<Database, Status> {
+		setup_database(&self.pool).await
+	}
+}
+
+/// `409 Conflict` when a unique column such as the login or the mail is already used.
+fn insert_error(error: &tokio_postgres::Error, message: &str) -> Status {
+	if error.code() == Some(&SqlState::UNIQUE_VIOLATION) {
+		Status::Conflict
+	} else {
+		error!("{message} : {error:?}");
+		Status::InternalServerError
+	}
+}
@@ -0,0 +1,154 @@
+use rocket::http::Status;
+
+use crate::{
+	error_handling::StatusResultHandling,
+	models::auth::{OidcProvider, UserType},
+	repositories::{AccountLookup, OidcProviderRepository},
+};
+
+use super::PostgresRepository;
+
+#[async_trait]
+impl OidcProviderRepository for PostgresRepository {
+	async fn get(&self, university_id: &str) -> Result<Option<OidcProvider>, Status> {
+		let client = self.client().await?;
+
+		let row = client
+			.query_opt(
+				"SELECT university_id, issuer, client_id, client_secret FROM university_oidc WHERE university_id=$1;",
+				&[&university_id],
+			)
+			.await
+			.internal_server_error("SELECT university_oidc error")?;
+
+		Ok(row.map(|row| OidcProvider {
+			university_id: row.get(0),
+			issuer: row.get(1),
+			client_id: row.get(2),
+			client_secret: row.get(3),
+		}))
+	}
+
+	async fn get_universities(&self) -> Result<Vec<(String, String)>, Status> {
+		let client = self.client().await?;
+
+		let rows = client
+			.query(
+				"SELECT u.id, u.name FROM university_oidc o JOIN university u ON u.id = o.university_id ORDER BY u.name;",
+				&[],
+			)
+			.await
+			.internal_server_error("SELECT university_oidc error")?;
+
+		Ok(rows.iter().map(|row| (row.get(0), row.get(1))).collect())
+	}
+
+	async fn save(&self, provider: &OidcProvider) -> Result<(), Status> {
+		let client = self.client().await?;
+
+		client
+			.execute(
+				"INSERT INTO university_oidc (university_id, issuer, client_id, client_secret) VALUES ($1, $2, $3, $4) ON CONFLICT (university_id) DO UPDATE SET issuer = EXCLUDED.issuer, client_id = EXCLUDED.client_id, client_secret = EXCLUDED.client_secret;",
+				&[
+					&provider.university_id,
+					&provider.issuer,
+					&provider.client_id,
+					&provider.client_secret,
+				],
+			)
+			.await
+			.internal_server_error("Error during university_oidc upsert")?;
+
+		Ok(())
+	}
+
+	async fn delete(&self, university_id: &str) -> Result<(), Status> {
+		let client = self.client().await?;
+
+		client
+			.execute(
+				"DELETE FROM university_oidc WHERE university_id=$1;",
+				&[&university_id],
+			)
+			.await
+			.internal_server_error("Error during university_oidc deletion")?;
+
+		Ok(())
+	}
+
+	async fn find_identity(
+		&self,
+		university_id: &str,
+		subject: &str,
+	) -> Result<Option<(String, UserType)>, Status> {
+		let client = self.client().await?;
+
+		let row = client
+			.query_opt(
+				"SELECT user_id, user_type FROM oidc_identity WHERE university_id=$1 AND subject=$2;",
+				&[&university_id, &subject],
+			)
+			.await
+			.internal_server_error("SELECT oidc_identity error")?;
+
+		row.map(|row| {
+			let user_type: String = row.get(1);
+			Ok((row.get(0), user_type.parse()?))
+		})
+		.transpose()
+	}
+
+	async fn link_identity(
+		&self,
+		university_id: &str,
+		subject: &str,
+		user_id: &str,
+		user_type: UserType,
+	) -> Result<(), Status> {
+		let client = self.client().await?;
+
+		client
+			.execute(
+				"INSERT INTO oidc_identity (university_id, subject, user_id, user_type) VALUES ($1, $2, $3, $4) ON CONFLICT (university_id, subject) DO UPDATE SET user_id = EXCLUDED.user_id, user_type = EXCLUDED.user_type;",
+				&[&university_id, &subject, &user_id, &user_type.to_string()],
+			)
+			.await
+			.internal_server_error("Error during oidc_identity upsert")?;
+
+		Ok(())
+	}
+
+	async fn find_member(
+		&self,
+		university_id: &str,
+		lookup: AccountLookup,
+		value: &str,
+	) -> Result<Option<(String, UserType)>, Status> {
+		let client = self.client().await?;
+		let column = lookup.column();
+
+		let university = client
+			.query_opt(
+				&format!("SELECT id FROM university WHERE id=$1 AND {column}=$2;"),
+				&[&university_id, &value],
+			)
+			.await
+			.internal_server_error("SELECT university error")?;
+
+		if let Some(row) = university {
+			return Ok(Some((row.get(0), UserType::University)));
+		}
+
+		let student = client
+			.query_opt(
+				&format!(
+					"SELECT s.id FROM student s JOIN class c ON c.id = s.class_id WHERE c.university_id=$1 AND s.{column}=$2;"
+				),
+				&[&university_id, &value],
+			)
+			.await
+			.internal_server_error("SELECT student error")?;
+
+		Ok(student.map(|row| (row.get(0), UserType::Student)))
+	}
+}
@@ -0,0 +1,142 @@
+use std::str::FromStr;
+
+use rocket::http::Status;
+
+use crate::{
+	error_handling::StatusResultHandling,
+	models::auth::{TwofaMethod, TwofaSettings},
+	repositories::TwofaRepository,
+};
+
+use super::PostgresRepository;
+
+#[async_trait]
+impl TwofaRepository for PostgresRepository {
+	async fn get_settings(&self, user_id: &str) -> Result<TwofaSettings, Status> {
+		let client = self.client().await?;
+
+		let row = client
+			.query_opt(
+				"SELECT method, totp_secret, totp_pending_secret, totp_last_step FROM twofa WHERE user_id=$1;",
+				&[&user_id],
+			)
+			.await
+			.internal_server_error("SELECT twofa error")?;
+
+		let Some(row) = row else {
+			return Ok(TwofaSettings {
+				user_id: user_id.to_string(),
+				..Default::default()
+			});
+		};
+
+		let method: String = row.get(0);
+
+		Ok(TwofaSettings {
+			user_id: user_id.to_string(),
+			method: TwofaMethod::from_str(&method)?,
+			totp_secret: row.get(1),
+			totp_pending_secret: row.get(2),
+			totp_last_step: row.get(3),
+		})
+	}
+
+	async fn set_pending_secret(
+		&self,
+		user_id: &str,
+		method: TwofaMethod,
+		secret: &str,
+	) -> Result<(), Status> {
+		let client = self.client().await?;
+
+		client
+			.execute(
+				"INSERT INTO twofa (user_id, method, totp_pending_secret) VALUES ($1, $2, $3) ON CONFLICT (user_id) DO UPDATE SET totp_pending_secret = EXCLUDED.totp_pending_secret;",
+				&[&user_id, &method.to_string(), &secret],
+			)
+			.await
+			.internal_server_error("Error during twofa upsert")?;
+
+		Ok(())
+	}
+
+	async fn activate_totp(&self, user_id: &str, secret: &str, step: i64) -> Result<(), Status> {
+		let client = self.client().await?;
+
+		client
+			.execute(
+				"UPDATE twofa SET method = $2, totp_secret = $3, totp_pending_secret = NULL, totp_last_step = $4 WHERE user_id=$1;",
+				&[&user_id, &TwofaMethod::Totp.to_string(), &secret, &step],
+			)
+			.await
+			.internal_server_error("Error while activating TOTP")?;
+
+		Ok(())
+	}
+
+	async fn use_totp_step(&self, user_id: &str, step: i64) -> Result<bool, Status> {
+		let client = self.client().await?;
+
+		// Conditional update so two requests with the same code can't both pass
+		let updated = client
+			.execute(
+				"UPDATE twofa SET totp_last_step = $2 WHERE user_id=$1 AND (totp_last_step IS NULL OR totp_last_step < $2);",
+				&[&user_id, &step],
+			)
+			.await
+			.internal_server_error("Error while recording TOTP step")?;
+
+		Ok(updated == 1)
+	}
+
+	async fn delete(&self, user_id: &str) -> Result<(), Status> {
+		let client = self.client().await?;
+
+		client
+			.execute("DELETE FROM twofa WHERE user_id=$1;", &[&user_id])
+			.await
+			.internal_server_error("Error while deleting twofa")?;
+
+		Ok(())
+	}
+
+	async fn use_recovery_code(&self, user_id: &str, code_hash: &str) -> Result<bool, Status> {
+		let client = self.client().await?;
+
+		let deleted = client
+			.execute(
+				"DELETE FROM twofa_recovery_code WHERE user_id=$1 AND code_hash=$2;",
+				&[&user_id, &code_hash],
+			)
+			.await
+			.internal_server_error("Error while using recovery code")?;
+
+		Ok(deleted == 1)
+	}
+
+	async fn replace_recovery_codes(
+		&self,
+		user_id: &str,
+		code_hashes: &[String],
+	) -> Result<(), Status> {
+		let client = self.client().await?;
+
+		client
+			.execute(
+				"DELETE FROM twofa_recovery_code WHERE user_id=$1;",
+				&[&user_id],
+			)
+			.await
+			.internal_server_error("Error while deleting recovery codes")?;
+
+		client
+			.execute(
+				"INSERT INTO twofa_recovery_code (user_id, code_hash) SELECT $1, UNNEST($2::VARCHAR[]);",
+				&[&user_id, &code_hashes],
+			)
+			.await
+			.internal_server_error("Error during recovery code insert")?;
+
+		Ok(())
+	}
+}
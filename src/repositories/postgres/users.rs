@@ -0,0 +1,311 @@
+use rocket::http::Status;
+use tokio_postgres::Row;
+
+use crate::{
+	error_handling::StatusResultHandling,
+	models::{
+		auth::UserType,
+		users::{Company, Student, University, admin::Admin},
+	},
+	postgres::Database,
+	repositories::{Credentials, UserRepository},
+};
+
+use super::{
+	PostgresRepository,
+	classes::{CLASS_COLUMNS, class_from_row},
+	insert_error,
+};
+
+const UNIVERSITY_COLUMNS: &str = "id, name, login, password, mail, mail_verified";
+
+/// Universities of the rows with their classes, all fetched by a single query on the same
+/// connection.
+async fn universities_with_classes(
+	client: &Database,
+	rows: &[Row],
+) -> Result<Vec<University>, Status> {
+	let ids: Vec<String> = rows.iter().map(|row| row.get(0)).collect();
+
+	let class_rows = client
+		.query(
+			&format!("SELECT {CLASS_COLUMNS} FROM class WHERE university_id = ANY($1);"),
+			&[&ids],
+		)
+		.await
+		.internal_server_error("Error getting classes")?;
+	let mut classes = class_rows
+		.iter()
+		.map(class_from_row)
+		.collect::<Result<Vec<_>, _>>()?;
+
+	Ok(rows
+		.iter()
+		.map(|row| {
+			let id: String = row.get(0);
+			let (class_list, others) = std::mem::take(&mut classes)
+				.into_iter()
+				.partition(|class| class.university_id == id);
+			classes = others;
+
+			University {
+				id,
+				name: row.get(1),
+				login: row.get(2),
+				password: row.get(3),
+				mail: row.get(4),
+				mail_verified: row.get(5),
+				class_list,
+				intership_list: Vec::new(), //WIP
+			}
+		})
+		.collect())
+}
+
+fn company_from_row(row: &Row) -> Company {
+	Company {
+		id: row.get(0),
+		login: row.get(1),
+		password: row.get(2),
+		mail: row.get(3),
+		name: row.get(4),
+		mail_verified: row.get(5),
+		internship_list: vec![],
+	}
+}
+
+#[async_trait]
+impl UserRepository for PostgresRepository {
+	async fn get_admin(&self, id: &str) -> Result<Option<Admin>, Status> {
+		let client = self.client().await?;
+
+		let row = client
+			.query_opt(
+				"SELECT login, password, mail FROM admin WHERE id=$1;",
+				&[&id],
+			)
+			.await
+			.internal_server_error("SELECT admin error")?;
+
+		Ok(row.map(|row| Admin {
+			id: id.to_string(),
+			login: row.get(0),
+			password: row.get(1),
+			mail: row.get(2),
+		}))
+	}
+
+	async fn get_university(&self, id: &str) -> Result<Option<University>, Status> {
+		let client = self.client().await?;
+
+		let rows = client
+			.query(
+				&format!("SELECT {UNIVERSITY_COLUMNS} FROM university WHERE id=$1;"),
+				&[&id],
+			)
+			.await
+			.internal_server_error("SELECT university error")?;
+
+		Ok(universities_with_classes(&client, &rows).await?.pop())
+	}
+
+	async fn get_company(&self, id: &str) -> Result<Option<Company>, Status> {
+		let client = self.client().await?;
+
+		let row = client
+			.query_opt(
+				"SELECT id, login, password, mail, name, mail_verified FROM company WHERE id=$1;",
+				&[&id],
+			)
+			.await
+			.internal_server_error("SELECT company error")?;
+
+		Ok(row.as_ref().map(company_from_row))
+	}
+
+	async fn get_student(&self, id: &str) -> Result<Option<Student>, Status> {
+		let client = self.client().await?;
+
+		let row = client
+			.query_opt(
+				"SELECT first_name, last_name, login, password, mail FROM student WHERE id=$1;",
+				&[&id],
+			)
+			.await
+			.internal_server_error("SELECT student error")?;
+
+		Ok(row.map(|row| Student {
+			id: id.to_string(),
+			first_name: row.get(0),
+			last_name: row.get(1),
+			login: row.get(2),
+			password: row.get(3),
+			mail: row.get(4),
+		}))
+	}
+
+	async fn get_universities(&self) -> Result<Vec<University>, Status> {
+		let client = self.client().await?;
+
+		let rows = client
+			.query(
+				&format!("SELECT {UNIVERSITY_COLUMNS} FROM university;"),
+				&[],
+			)
+			.await
+			.internal_server_error("Error getting universities")?;
+
+		universities_with_classes(&client, &rows).await
+	}
+
+	async fn get_companies(&self) -> Result<Vec<Company>, Status> {
+		let client = self.client().await?;
+
+		let rows = client
+			.query(
+				"SELECT id, login, password, mail, name, mail_verified FROM company;",
+				&[],
+			)
+			.await
+			.internal_server_error("Error getting companies")?;
+
+		Ok(rows.iter().map(company_from_row).collect())
+	}
+
+	async fn get_credentials(
+		&self,
+		user_type: UserType,
+		login: &str,
+	) -> Result<Option<Credentials>, Status> {
+		let client = self.client().await?;
+
+		let row = client
+			.query_opt(
+				&format!(
+					"SELECT id, mail, password FROM {} WHERE login=$1;",
+					user_type.table_name()
+				),
+				&[&login],
+			)
+			.await
+			.internal_server_error("SELECT password error")?;
+
+		Ok(row.map(|row| Credentials {
+			id: row.get(0),
+			mail: row.get(1),
+			password_hash: row.get(2),
+		}))
+	}
+
+	async fn is_login_taken(&self, login: &str) -> Result<bool, Status> {
+		let client = self.client().await?;
+
+		let row = client
+			.query_opt("SELECT 1 FROM student WHERE login=$1;", &[&login])
+			.await
+			.internal_server_error("Error during selection of login to check if login is taken")?;
+
+		Ok(row.is_some())
+	}
+
+	async fn insert_university(
+		&self,
+		university: &University,
+		password_hash: &str,
+	) -> Result<(), Status> {
+		let client = self.client().await?;
+
+		client
+			.execute(
+				"INSERT INTO university (id, name, mail, login, password) VALUES ($1, $2, $3, $4, $5);",
+				&[
+					&university.id,
+					&university.name,
+					&university.mail,
+					&university.login,
+					&password_hash,
+				],
+			)
+			.await
+			.map_err(|e| insert_error(&e, "Error during insert of university"))?;
+
+		Ok(())
+	}
+
+	async fn insert_company(&self, company: &Company, password_hash: &str) -> Result<(), Status> {
+		let client = self.client().await?;
+
+		client
+			.execute(
+				"INSERT INTO company (id, name, login, password, mail) VALUES ($1, $2, $3, $4, $5);",
+				&[
+					&company.id,
+					&company.name,
+					&company.login,
+					&password_hash,
+					&company.mail,
+				],
+			)
+			.await
+			.map_err(|e| insert_error(&e, "Error during company insert"))?;
+
+		Ok(())
+	}
+
+	async fn insert_student(
+		&self,
+		student: &Student,
+		password_hash: &str,
+		class_id: &str,
+	) -> Result<(), Status> {
+		let client = self.client().await?;
+
+		client
+			.execute(
+				"INSERT INTO student (id, first_name, last_name, login, password, mail, class_id) VALUES ($1, $2, $3, $4, $5, $6, $7);",
+				&[
+					&student.id,
+					&student.first_name,
+					&student.last_name,
+					&student.login,
+					&password_hash,
+					&student.mail,
+					&class_id,
+				],
+			)
+			.await
+			.map_err(|e| insert_error(&e, "INSERT student Error"))?;
+
+		Ok(())
+	}
+
+	async fn delete_university(&self, id: &str) -> Result<Vec<String>, Status> {
+		let client = self.client().await?;
+
+		// `twofa` has no foreign key, its rows are deleted in the same statement as the users.
+		// Every part of the statement sees the students as they were before the cascade.
+		let students = client
+			.query(
+				"WITH students AS (SELECT student.id FROM student JOIN class ON student.class_id = class.id WHERE class.university_id=$1), deleted_twofa AS (DELETE FROM twofa WHERE user_id=$1 OR user_id IN (SELECT id FROM students)), deleted_university AS (DELETE FROM university WHERE id=$1) SELECT id FROM students;",
+				&[&id],
+			)
+			.await
+			.internal_server_error("Error during university deletion")?;
+
+		Ok(students.iter().map(|row| row.get(0)).collect())
+	}
+
+	async fn delete_company(&self, id: &str) -> Result<(), Status> {
+		let client = self.client().await?;
+
+		client
+			.execute(
+				"WITH deleted_twofa AS (DELETE FROM twofa WHERE user_id=$1) DELETE FROM company WHERE id=$1;",
+				&[&id],
+			)
+			.await
+			.internal_server_error("Error during company deletion")?;
+
+		Ok(())
+	}
+}
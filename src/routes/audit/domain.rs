@@ -0,0 +1,28 @@
+use serde::Serialize;
+
+use crate::models::audit::AuditEvent;
+
+// Events
+
+/// Every field is optional, dates are RFC 3339 and `to` is exclusive.
+#[derive(Debug, FromForm)]
+pub struct GetAuditEventsQuery {
+	pub actor_id: Option<String>,
+	pub actor_role: Option<String>,
+	pub impersonator_id: Option<String>,
+	pub action: Option<String>,
+	pub target_id: Option<String>,
+	pub outcome: Option<String>,
+	pub from: Option<String>,
+	pub to: Option<String>,
+	pub page: Option<i64>,
+	pub per_page: Option<i64>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct GetAuditEventsResponse {
+	pub events: Vec<AuditEvent>,
+	pub page: i64,
+	pub per_page: i64,
+	pub total: i64,
+}
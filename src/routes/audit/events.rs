@@ -0,0 +1,76 @@
+use chrono::{DateTime, Utc};
+use rocket::{State, serde::json::Json};
+
+use crate::{
+	error_handling::{ApiError, FieldError},
+	models::{audit::AuditFilter, auth::AdminUser},
+	repositories::Repositories,
+};
+
+use super::domain::{GetAuditEventsQuery, GetAuditEventsResponse};
+
+const DEFAULT_PER_PAGE: i64 = 50;
+const MAX_PER_PAGE: i64 = 200;
+
+#[get("/audit/events?<query..>")]
+#[allow(clippy::needless_pass_by_value)]
+#[allow(clippy::missing_errors_doc)]
+pub async fn get_audit_events(
+	_admin: AdminUser,
+	query: GetAuditEventsQuery,
+	repositories: &State<Repositories>,
+) -> Result<Json<GetAuditEventsResponse>, ApiError> {
+	let page = query.page.unwrap_or(1).max(1);
+	let per_page = query
+		.per_page
+		.unwrap_or(DEFAULT_PER_PAGE)
+		.clamp(1, MAX_PER_PAGE);
+
+	let filter = AuditFilter {
+		actor_id: query.actor_id,
+		actor_role: query
+			.actor_role
+			.map(|actor_role| actor_role.parse().map_err(|_| invalid_filter("actor_role")))
+			.transpose()?,
+		impersonator_id: query.impersonator_id,
+		action: query
+			.action
+			.map(|action| action.parse().map_err(|_| invalid_filter("action")))
+			.transpose()?,
+		target_id: query.target_id,
+		outcome: query
+			.outcome
+			.map(|outcome| outcome.parse().map_err(|_| invalid_filter("outcome")))
+			.transpose()?,
+		from: query
+			.from
+			.as_deref()
+			.map(|from| parse_date("from", from))
+			.transpose()?,
+		to: query
+			.to
+			.as_deref()
+			.map(|to| parse_date("to", to))
+			.transpose()?,
+	};
+
+	let offset = (page - 1).saturating_mul(per_page);
+	let (events, total) = repositories.audit.search(&filter, per_page, offset).await?;
+
+	Ok(Json(GetAuditEventsResponse {
+		events,
+		page,
+		per_page,
+		total,
+	}))
+}
+
+fn parse_date(field: &str, date: &str) -> Result<DateTime<Utc>, ApiError> {
+	DateTime::parse_from_rfc3339(date)
+		.map(|date| date.with_timezone(&Utc))
+		.map_err(|_| ApiError::Validation(vec![FieldError::new(field, "Must be an RFC 3339 date")]))
+}
+
+fn invalid_filter(field: &str) -> ApiError {
+	ApiError::Validation(vec![FieldError::new(field, "Unknown value")])
+}
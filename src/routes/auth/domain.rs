@@ -1,5 +1,8 @@
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
+use crate::models::auth::{TwofaMethod, UserType};
+
 // Login
 
 #[derive(Debug, Deserialize)]
@@ -12,9 +15,9 @@ pub struct LoginPayload {
 
 #[derive(Debug, Serialize)]
 pub struct LoginResponse {
-	pub valid: bool,
-	pub transaction_id: Option<String>,
-	pub remember_me: Option<bool>,
+	pub transaction_id: String,
+	pub remember_me: bool,
+	pub twofa_method: TwofaMethod,
 }
 
 // Twofa
@@ -25,25 +28,152 @@ pub struct TwofaPayload {
 	pub transaction_id: String,
 	pub user_type: String,
 	pub remember_me: bool,
+	/// Keeps the tokens in private cookies instead of returning them
+	#[serde(default)]
+	pub use_cookie: bool,
 }
 
 #[derive(Debug, Serialize)]
 pub struct TwofaResponse {
-	pub valid: bool,
+	/// Unset when the tokens are kept in cookies
 	pub jwt: Option<String>,
+	pub refresh_token: Option<String>,
+	pub must_change_password: bool,
+	/// Value of the `X-CSRF-Token` header when the session is kept in cookies
+	pub csrf_token: Option<String>,
 }
 
-// CheckSession
+// Refresh
+
+#[derive(Debug, Deserialize)]
+pub struct RefreshPayload {
+	/// Read from the cookie when missing
+	pub refresh_token: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RefreshResponse {
+	/// Unset when the tokens are kept in cookies
+	pub jwt: Option<String>,
+	pub refresh_token: Option<String>,
+	pub csrf_token: Option<String>,
+}
+
+// Totp
+
+#[derive(Debug, Serialize)]
+pub struct TotpEnrollResponse {
+	pub secret: String,
+	pub otpauth_uri: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct TotpCodePayload {
+	pub code: String,
+}
 
 #[derive(Debug, Serialize)]
-pub struct CheckSessionResponse {
-	pub valid: bool,
-	pub error: Option<String>,
+pub struct TotpConfirmResponse {
+	pub recovery_codes: Vec<String>,
+}
+
+// Password reset
+
+#[derive(Debug, Deserialize)]
+pub struct ForgotPasswordPayload {
+	pub mail: String,
+	pub user_type: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ResetPasswordPayload {
+	pub token: String,
+	pub new_password: String,
+}
+
+// Mail verification
+
+#[derive(Debug, Deserialize)]
+pub struct VerifyMailPayload {
+	pub token: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ResendMailVerificationPayload {
+	pub user_id: String,
+	pub user_type: String,
 }
 
-// Disconnect
+// Sessions
+
+#[derive(Debug, Serialize)]
+pub struct SessionDto {
+	pub id: String,
+	pub created_at: DateTime<Utc>,
+	pub last_seen: DateTime<Utc>,
+	pub user_agent: Option<String>,
+	pub ip: Option<String>,
+	pub current: bool,
+	/// Opened by an admin through impersonation
+	pub impersonated: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct GetSessionsResponse {
+	pub sessions: Vec<SessionDto>,
+}
+
+// OpenID Connect
+
+#[derive(Debug, Serialize)]
+pub struct OidcProviderDto {
+	pub university_id: String,
+	pub university_name: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct GetOidcProvidersResponse {
+	pub providers: Vec<OidcProviderDto>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct OidcAuthorizePayload {
+	pub university_id: String,
+	pub remember_me: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct OidcAuthorizeResponse {
+	pub authorization_url: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct OidcCallbackPayload {
+	pub state: String,
+	pub code: String,
+	#[serde(default)]
+	pub use_cookie: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct OidcCallbackResponse {
+	/// Unset when the tokens are kept in cookies
+	pub jwt: Option<String>,
+	pub refresh_token: Option<String>,
+	pub user_type: UserType,
+	pub csrf_token: Option<String>,
+}
+
+// Impersonation
+
+#[derive(Debug, Deserialize)]
+pub struct ImpersonatePayload {
+	pub user_id: String,
+	pub user_type: String,
+}
 
 #[derive(Debug, Serialize)]
-pub struct DisconnectResponse {
-	pub success: bool,
+pub struct ImpersonateResponse {
+	pub jwt: String,
+	pub expires_in: u64,
 }
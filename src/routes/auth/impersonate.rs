@@ -0,0 +1,69 @@
+use std::str::FromStr;
+
+use rocket::{State, serde::json::Json};
+use uuid::Uuid;
+
+use crate::{
+	config::ttl,
+	error_handling::{ApiError, StatusOptionHandling},
+	models::{
+		audit::{AuditAction, AuditEvent, AuditOutcome},
+		auth::{AccountStatus, AdminUser, AuthGuard, ClientInfo, JwtKeyring, UserType},
+	},
+	redis::{SessionData, set_session},
+	repositories::{AccountLookup, Repositories},
+};
+
+use super::domain::{ImpersonatePayload, ImpersonateResponse};
+
+/// Opens a session as another user for the support team.
+///
+/// Admins and accounts which are not active can't be impersonated.
+#[post("/auth/impersonate", data = "<impersonate_payload>")]
+#[allow(clippy::needless_pass_by_value)]
+#[allow(clippy::missing_errors_doc)]
+pub async fn impersonate(
+	admin_user: AdminUser,
+	impersonate_payload: Json<ImpersonatePayload>,
+	client: ClientInfo,
+	keyring: &State<JwtKeyring>,
+	repositories: &State<Repositories>,
+) -> Result<Json<ImpersonateResponse>, ApiError> {
+	let payload = impersonate_payload.into_inner();
+	let user_type = UserType::from_str(&payload.user_type)?;
+	let audit = AuditEvent::builder(AuditAction::Impersonate, AuditOutcome::Failure)
+		.actor(&admin_user.admin.id, UserType::Admin)
+		.target(&payload.user_id)
+		.ip(client.ip);
+
+	let status = repositories
+		.accounts
+		.get_status(user_type, AccountLookup::Id, &payload.user_id)
+		.await?;
+	if user_type == UserType::Admin || !matches!(status, Some((AccountStatus::Active, _))) {
+		audit.record(repositories.audit.as_ref()).await?;
+		return Err(ApiError::ImpersonationRefused);
+	}
+
+	let session_id = Uuid::new_v4().to_string();
+	let mut session_data = SessionData::new(payload.user_id, false, &client);
+	session_data.impersonator = Some(admin_user.admin.id.clone());
+
+	// No refresh token is issued, the session ends with its first access token
+	let ttl_seconds = ttl().impersonation_seconds;
+	set_session(&session_id, &session_data, ttl_seconds).await?;
+
+	let jwt = AuthGuard::new_raw_jwt_from_data(keyring, session_id, user_type)
+		.await?
+		.internal_server_error("JWT is somehow not valid")?;
+
+	audit
+		.outcome(AuditOutcome::Success)
+		.record(repositories.audit.as_ref())
+		.await?;
+
+	Ok(Json(ImpersonateResponse {
+		jwt,
+		expires_in: ttl_seconds,
+	}))
+}
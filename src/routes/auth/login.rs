@@ -1,15 +1,20 @@
-use std::str::FromStr;
+use std::{net::IpAddr, str::FromStr};
 
-use rocket::{http::Status, serde::json::Json};
+use rocket::{State, serde::json::Json};
 
 use crate::{
+	error_handling::ApiError,
+	metrics::record_login,
 	models::{
-		auth::UserType,
-		users::{Company, Student, University},
+		audit::{AuditAction, AuditEvent, AuditOutcome},
+		auth::{AccountStatus, Throttle, TwofaMethod, UserType},
 	},
-	postgres::Db,
 	redis::get_transactionid,
-	utils::mail::send_2fa_mail,
+	repositories::{AccountLookup, Repositories},
+	utils::{
+		crypto::verify_password,
+		mail::{Mailer, send_2fa_mail},
+	},
 };
 
 use super::domain::{LoginPayload, LoginResponse};
@@ -17,67 +22,118 @@ use super::domain::{LoginPayload, LoginResponse};
 #[post("/auth/login", data = "<login_payload>")]
 #[allow(clippy::needless_pass_by_value)]
 #[allow(clippy::missing_errors_doc)]
-pub async fn login(login_payload: Json<LoginPayload>) -> Result<Json<LoginResponse>, Status> {
+pub async fn login(
+	login_payload: Json<LoginPayload>,
+	ip: Option<IpAddr>,
+	mailer: &State<Mailer>,
+	repositories: &State<Repositories>,
+) -> Result<Json<LoginResponse>, ApiError> {
 	let login = login_payload.into_inner();
 	let user_type = UserType::from_str(&login.user_type)?;
+	let throttle = Throttle::for_login(user_type, &login.login, ip);
+	let audit = AuditEvent::builder(AuditAction::Login, AuditOutcome::Failure)
+		.role(user_type)
+		.target(&login.login)
+		.ip(ip);
 
-	match user_type {
-		UserType::Admin => todo!(),
-		UserType::University => login_university(login).await,
-		UserType::Student => login_student(login).await,
-		UserType::Company => login_company(login).await,
+	if let Some(retry_after) = throttle.locked_for().await? {
+		audit.record(repositories.audit.as_ref()).await?;
+		return Err(ApiError::Locked {
+			retry_after: Some(retry_after),
+		});
 	}
-}
 
-pub async fn login_university(login: LoginPayload) -> Result<Json<LoginResponse>, Status> {
-	let university = University::login(&login.login, &login.password).await?;
-
-	match university {
-		Some(university) => set_transaction_id(&university.mail, &university.id, login.remember_me),
-		None => Ok(Json(LoginResponse {
-			valid: false,
-			transaction_id: None,
-			remember_me: None,
-		})),
+	let response = login_user(repositories, mailer, user_type, login).await;
+	if matches!(response, Err(ApiError::Internal | ApiError::Unavailable(_))) {
+		return response;
 	}
-}
 
-pub async fn login_company(login: LoginPayload) -> Result<Json<LoginResponse>, Status> {
-	let company = Company::login(&login.login, &login.password).await?;
+	record_login(user_type, response.is_ok());
+	audit
+		.outcome(AuditOutcome::from_success(response.is_ok()))
+		.record(repositories.audit.as_ref())
+		.await?;
 
-	match company {
-		Some(company) => set_transaction_id(&company.mail, &company.id, login.remember_me),
-		None => Ok(Json(LoginResponse {
-			valid: false,
-			transaction_id: None,
-			remember_me: None,
-		})),
+	match response {
+		// An unverified address comes with the right credentials, it is not a failed guess
+		Ok(_) | Err(ApiError::MailNotVerified) => throttle.reset().await?,
+		Err(ApiError::InvalidCredentials) => {
+			if let Some(retry_after) = throttle.register_failure().await? {
+				return Err(ApiError::Locked {
+					retry_after: Some(retry_after),
+				});
+			}
+		}
+		Err(_) => {}
 	}
+
+	response
 }
 
-pub async fn login_student(login: LoginPayload) -> Result<Json<LoginResponse>, Status> {
-	let student = Student::login(&login.login, &login.password).await?;
+/// Checks the credentials and starts the 2FA step, which needs an active account and a verified
+/// address.
+async fn login_user(
+	repositories: &Repositories,
+	mailer: &Mailer,
+	user_type: UserType,
+	login: LoginPayload,
+) -> Result<Json<LoginResponse>, ApiError> {
+	let credentials = repositories
+		.users
+		.get_credentials(user_type, &login.login)
+		.await?;
 
-	match student {
-		Some(student) => set_transaction_id(&student.mail, &student.id, login.remember_me),
-		None => Ok(Json(LoginResponse {
-			valid: false,
-			transaction_id: None,
-			remember_me: None,
-		})),
+	match credentials {
+		Some(credentials) if verify_password(&login.password, &credentials.password_hash)? => {
+			match repositories
+				.accounts
+				.get_status(user_type, AccountLookup::Id, &credentials.id)
+				.await?
+			{
+				Some((AccountStatus::Active, _)) => {}
+				Some((status, reason)) => return Err(ApiError::AccountInactive { status, reason }),
+				None => return Err(ApiError::InvalidCredentials),
+			}
+			set_transaction_id(
+				repositories,
+				mailer,
+				&credentials.mail,
+				&credentials.id,
+				user_type,
+				login.remember_me,
+			)
+			.await
+		}
+		_ => Err(ApiError::InvalidCredentials),
 	}
 }
 
-pub fn set_transaction_id(
+pub async fn set_transaction_id(
+	repositories: &Repositories,
+	mailer: &Mailer,
 	mail: &str,
 	id: &str,
+	user_type: UserType,
 	remember_me: bool,
-) -> Result<Json<LoginResponse>, Status> {
-	let code = send_2fa_mail(mail)?;
-	let transaction_id = get_transactionid(id, code)?;
+) -> Result<Json<LoginResponse>, ApiError> {
+	// 2FA codes can't be trusted to reach an address nobody confirmed
+	if let Some((_, false)) = repositories
+		.accounts
+		.get_mail_verification(user_type, id)
+		.await?
+	{
+		return Err(ApiError::MailNotVerified);
+	}
+
+	let method = repositories.twofa.get_settings(id).await?.method;
+	let code = match method {
+		TwofaMethod::Mail => send_2fa_mail(mailer, mail).await?,
+		TwofaMethod::Totp => String::new(),
+	};
+	let transaction_id = get_transactionid(id, user_type, method, code).await?;
 	Ok(Json(LoginResponse {
-		valid: true,
-		transaction_id: Some(transaction_id),
-		remember_me: Some(remember_me),
+		transaction_id,
+		remember_me,
+		twofa_method: method,
 	}))
 }
@@ -1,12 +1,38 @@
-use rocket::{http::Status, serde::json::Json};
+use rocket::{State, http::CookieJar, response::status::NoContent};
 
-use crate::models::auth::AuthGuard;
-
-use super::domain::DisconnectResponse;
+use crate::{
+	error_handling::ApiError,
+	models::{
+		audit::{AuditAction, AuditEvent, AuditOutcome},
+		auth::{ClientInfo, PasswordChangeGuard, clear_session_cookies},
+	},
+	repositories::Repositories,
+};
 
+/// Also open to the restricted sessions, the user can leave without changing the password.
 #[delete("/auth/logout")]
 #[allow(clippy::needless_pass_by_value)]
 #[allow(clippy::missing_errors_doc)]
-pub async fn logout(auth: AuthGuard) -> Result<Json<DisconnectResponse>, Status> {
-	auth.get_generic_user().await?.logout()
+pub async fn logout(
+	auth: PasswordChangeGuard,
+	client: ClientInfo,
+	cookies: &CookieJar<'_>,
+	repositories: &State<Repositories>,
+) -> Result<NoContent, ApiError> {
+	let auth = auth.0;
+	let generic_user = auth.get_generic_user(repositories.users.as_ref()).await?;
+	let user_id = generic_user.get_id()?.to_string();
+	let impersonator = generic_user.get_impersonator().map(str::to_string);
+	generic_user.logout().await?;
+	clear_session_cookies(cookies);
+
+	AuditEvent::builder(AuditAction::Logout, AuditOutcome::Success)
+		.actor(user_id, auth.user_type)
+		.impersonator(impersonator.as_deref())
+		.target(&auth.session_id)
+		.ip(client.ip)
+		.record(repositories.audit.as_ref())
+		.await?;
+
+	Ok(NoContent)
 }
@@ -0,0 +1,95 @@
+use std::str::FromStr;
+
+use rocket::{State, response::status::NoContent, serde::json::Json};
+
+use crate::{
+	error_handling::ApiError,
+	models::{
+		audit::{AuditAction, AuditEvent, AuditOutcome},
+		auth::{
+			AdminUser, ClientInfo, JwtKeyring, UserType, send_mail_verification,
+			verify_mail_address,
+		},
+	},
+	repositories::Repositories,
+	utils::mail::Mailer,
+};
+
+use super::domain::{ResendMailVerificationPayload, VerifyMailPayload};
+
+#[post("/auth/mail/verify", data = "<verify_mail_payload>")]
+#[allow(clippy::needless_pass_by_value)]
+#[allow(clippy::missing_errors_doc)]
+pub async fn verify_mail(
+	verify_mail_payload: Json<VerifyMailPayload>,
+	client: ClientInfo,
+	keyring: &State<JwtKeyring>,
+	repositories: &State<Repositories>,
+) -> Result<NoContent, ApiError> {
+	let payload = verify_mail_payload.into_inner();
+
+	let account =
+		verify_mail_address(keyring, repositories.accounts.as_ref(), &payload.token).await?;
+
+	let event = AuditEvent::builder(
+		AuditAction::MailVerification,
+		AuditOutcome::from_success(account.is_some()),
+	)
+	.ip(client.ip);
+	match &account {
+		Some((user_id, user_type)) => event.actor(user_id, *user_type).target(user_id),
+		None => event,
+	}
+	.record(repositories.audit.as_ref())
+	.await?;
+
+	match account {
+		Some(_) => Ok(NoContent),
+		None => Err(ApiError::BadRequest(
+			"Invalid or expired token".to_string(),
+		)),
+	}
+}
+
+/// Sends a new verification link to an account which has not verified its address yet.
+#[post("/auth/mail/verify/resend", data = "<resend_mail_verification_payload>")]
+#[allow(clippy::needless_pass_by_value)]
+#[allow(clippy::missing_errors_doc)]
+pub async fn resend_mail_verification(
+	admin_user: AdminUser,
+	resend_mail_verification_payload: Json<ResendMailVerificationPayload>,
+	client: ClientInfo,
+	keyring: &State<JwtKeyring>,
+	mailer: &State<Mailer>,
+	repositories: &State<Repositories>,
+) -> Result<NoContent, ApiError> {
+	let payload = resend_mail_verification_payload.into_inner();
+	let user_type = UserType::from_str(&payload.user_type)?;
+
+	let sent = send_mail_verification(
+		keyring,
+		repositories.accounts.as_ref(),
+		mailer,
+		user_type,
+		&payload.user_id,
+	)
+	.await?;
+
+	AuditEvent::builder(
+		AuditAction::MailVerificationRequest,
+		AuditOutcome::from_success(sent),
+	)
+	.actor(&admin_user.admin.id, UserType::Admin)
+	.target(&payload.user_id)
+	.ip(client.ip)
+	.record(repositories.audit.as_ref())
+	.await?;
+
+	if sent {
+		Ok(NoContent)
+	} else {
+		Err(ApiError::Conflict(
+			"The account doesn't exist or its address is already verified".to_string(),
+		))
+	}
+}
@@ -1,17 +1,48 @@
 mod domain;
+mod impersonate;
 mod login;
 mod logout;
+mod mail_verification;
+mod oidc;
+mod password;
+mod refresh;
 mod session;
+mod sessions;
+mod totp;
 mod twofa;
 
-pub use domain::CheckSessionResponse;
-pub use domain::DisconnectResponse;
+pub use domain::ForgotPasswordPayload;
+pub use domain::GetOidcProvidersResponse;
+pub use domain::GetSessionsResponse;
+pub use domain::ImpersonatePayload;
+pub use domain::ImpersonateResponse;
 pub use domain::LoginPayload;
 pub use domain::LoginResponse;
+pub use domain::OidcAuthorizePayload;
+pub use domain::OidcAuthorizeResponse;
+pub use domain::OidcCallbackPayload;
+pub use domain::OidcCallbackResponse;
+pub use domain::OidcProviderDto;
+pub use domain::RefreshPayload;
+pub use domain::ResendMailVerificationPayload;
+pub use domain::RefreshResponse;
+pub use domain::ResetPasswordPayload;
+pub use domain::SessionDto;
+pub use domain::TotpCodePayload;
+pub use domain::TotpConfirmResponse;
+pub use domain::TotpEnrollResponse;
 pub use domain::TwofaPayload;
 pub use domain::TwofaResponse;
+pub use domain::VerifyMailPayload;
 
 pub use login::login as login_route;
+pub use impersonate::impersonate as impersonate_route;
 pub use logout::logout as logout_route;
+pub use mail_verification::{resend_mail_verification, verify_mail};
+pub use oidc::{get_oidc_providers, oidc_authorize, oidc_callback};
+pub use password::{forgot_password, reset_password};
+pub use refresh::refresh as refresh_route;
 pub use session::check_session;
+pub use sessions::{get_sessions, revoke_all_sessions, revoke_session};
+pub use totp::{confirm_totp, disable_totp, enroll_totp};
 pub use twofa::twofa as twofa_route;
@@ -0,0 +1,124 @@
+use rocket::{State, http::CookieJar, serde::json::Json};
+use uuid::Uuid;
+
+use crate::{
+	error_handling::{ApiError, StatusOptionHandling},
+	models::{
+		audit::{AuditAction, AuditEvent, AuditOutcome},
+		auth::{
+			AuthGuard, ClientInfo, JwtKeyring, OidcProvider, deliver_session_tokens,
+			issue_refresh_token, refresh_token_ttl,
+		},
+	},
+	redis::{SessionData, set_session},
+	repositories::Repositories,
+};
+
+use super::domain::{
+	GetOidcProvidersResponse, OidcAuthorizePayload, OidcAuthorizeResponse, OidcCallbackPayload,
+	OidcCallbackResponse, OidcProviderDto,
+};
+
+#[get("/auth/oidc/providers")]
+#[allow(clippy::needless_pass_by_value)]
+#[allow(clippy::missing_errors_doc)]
+pub async fn get_oidc_providers(
+	repositories: &State<Repositories>,
+) -> Result<Json<GetOidcProvidersResponse>, ApiError> {
+	let providers = repositories
+		.oidc_providers
+		.get_universities()
+		.await?
+		.into_iter()
+		.map(|(university_id, university_name)| OidcProviderDto {
+			university_id,
+			university_name,
+		})
+		.collect();
+
+	Ok(Json(GetOidcProvidersResponse { providers }))
+}
+
+#[post("/auth/oidc/authorize", data = "<oidc_authorize_payload>")]
+#[allow(clippy::needless_pass_by_value)]
+#[allow(clippy::missing_errors_doc)]
+pub async fn oidc_authorize(
+	oidc_authorize_payload: Json<OidcAuthorizePayload>,
+	repositories: &State<Repositories>,
+) -> Result<Json<OidcAuthorizeResponse>, ApiError> {
+	let payload = oidc_authorize_payload.into_inner();
+
+	let Some(provider) = repositories
+		.oidc_providers
+		.get(&payload.university_id)
+		.await?
+	else {
+		return Err(ApiError::NotFound(
+			"No OIDC provider for this university".to_string(),
+		));
+	};
+
+	Ok(Json(OidcAuthorizeResponse {
+		authorization_url: provider.start_login(payload.remember_me).await?,
+	}))
+}
+
+/// The provider already authenticated the user, so no 2FA step nor forced password change here.
+#[post("/auth/oidc/callback", data = "<oidc_callback_payload>")]
+#[allow(clippy::needless_pass_by_value)]
+#[allow(clippy::missing_errors_doc)]
+pub async fn oidc_callback(
+	oidc_callback_payload: Json<OidcCallbackPayload>,
+	client: ClientInfo,
+	keyring: &State<JwtKeyring>,
+	cookies: &CookieJar<'_>,
+	repositories: &State<Repositories>,
+) -> Result<Json<OidcCallbackResponse>, ApiError> {
+	let payload = oidc_callback_payload.into_inner();
+
+	let audit = AuditEvent::builder(AuditAction::OidcLogin, AuditOutcome::Failure).ip(client.ip);
+
+	let Some(identity) =
+		OidcProvider::finish_login(repositories, &payload.state, &payload.code).await?
+	else {
+		audit.record(repositories.audit.as_ref()).await?;
+		return Err(ApiError::OidcLoginFailed);
+	};
+
+	audit
+		.actor(&identity.user_id, identity.user_type)
+		.outcome(AuditOutcome::Success)
+		.record(repositories.audit.as_ref())
+		.await?;
+
+	let session_id = Uuid::new_v4().to_string();
+	let session_data = SessionData::new(identity.user_id, false, &client);
+
+	set_session(
+		&session_id,
+		&session_data,
+		refresh_token_ttl(identity.remember_me),
+	)
+	.await?;
+
+	let refresh_token =
+		issue_refresh_token(&session_id, identity.user_type, identity.remember_me).await?;
+	let jwt = AuthGuard::new_raw_jwt_from_data(keyring, session_id, identity.user_type)
+		.await?
+		.internal_server_error("JWT is somehow not valid")?;
+
+	let tokens = deliver_session_tokens(
+		cookies,
+		payload.use_cookie,
+		jwt,
+		refresh_token,
+		refresh_token_ttl(identity.remember_me),
+	);
+
+	Ok(Json(OidcCallbackResponse {
+		jwt: tokens.jwt,
+		refresh_token: tokens.refresh_token,
+		user_type: identity.user_type,
+		csrf_token: tokens.csrf_token,
+	}))
+}
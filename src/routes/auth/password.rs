@@ -0,0 +1,116 @@
+use std::str::FromStr;
+
+use rocket::{State, http::Status, response::status::NoContent, serde::json::Json};
+
+use crate::{
+	error_handling::{ApiError, FieldError},
+	models::{
+		audit::{AuditAction, AuditEvent, AuditOutcome},
+		auth::{
+			self, ClientInfo, JwtKeyring, UserType, register_reset_request, request_password_reset,
+		},
+	},
+	repositories::Repositories,
+	utils::{crypto::is_password_valid, mail::Mailer},
+};
+
+use super::domain::{ForgotPasswordPayload, ResetPasswordPayload};
+
+/// Mails a reset link, `429 Too Many Requests` past 3 requests an hour for the address or 20 for
+/// the client IP.
+#[post("/auth/password/forgot", data = "<forgot_password_payload>")]
+#[allow(clippy::needless_pass_by_value)]
+#[allow(clippy::missing_errors_doc)]
+pub async fn forgot_password(
+	forgot_password_payload: Json<ForgotPasswordPayload>,
+	client: ClientInfo,
+	keyring: &State<JwtKeyring>,
+	mailer: &State<Mailer>,
+	repositories: &State<Repositories>,
+) -> Result<NoContent, ApiError> {
+	let payload = forgot_password_payload.into_inner();
+	let user_type = UserType::from_str(&payload.user_type)?;
+
+	if !register_reset_request(&payload.mail, client.ip).await? {
+		AuditEvent::builder(AuditAction::PasswordResetRequest, AuditOutcome::Failure)
+			.role(user_type)
+			.target(&payload.mail)
+			.ip(client.ip)
+			.record(repositories.audit.as_ref())
+			.await?;
+		return Err(ApiError::Http(Status::TooManyRequests));
+	}
+
+	let user_id = request_password_reset(
+		keyring,
+		repositories.accounts.as_ref(),
+		mailer,
+		user_type,
+		&payload.mail,
+	)
+	.await?;
+
+	let event = AuditEvent::builder(
+		AuditAction::PasswordResetRequest,
+		AuditOutcome::from_success(user_id.is_some()),
+	)
+	.role(user_type)
+	.ip(client.ip);
+	match user_id {
+		Some(user_id) => event.target(user_id),
+		None => event.target(payload.mail),
+	}
+	.record(repositories.audit.as_ref())
+	.await?;
+
+	// Same answer whether the address is known or not
+	Ok(NoContent)
+}
+
+#[post("/auth/password/reset", data = "<reset_password_payload>")]
+#[allow(clippy::needless_pass_by_value)]
+#[allow(clippy::missing_errors_doc)]
+pub async fn reset_password(
+	reset_password_payload: Json<ResetPasswordPayload>,
+	client: ClientInfo,
+	keyring: &State<JwtKeyring>,
+	repositories: &State<Repositories>,
+) -> Result<NoContent, ApiError> {
+	let payload = reset_password_payload.into_inner();
+
+	let password_valid = is_password_valid(&payload.new_password);
+	let account = if password_valid {
+		auth::reset_password(
+			keyring,
+			repositories.accounts.as_ref(),
+			&payload.token,
+			&payload.new_password,
+		)
+		.await?
+	} else {
+		None
+	};
+
+	let event = AuditEvent::builder(
+		AuditAction::PasswordReset,
+		AuditOutcome::from_success(account.is_some()),
+	)
+	.ip(client.ip);
+	match &account {
+		Some((user_id, user_type)) => event.actor(user_id, *user_type).target(user_id),
+		None => event,
+	}
+	.record(repositories.audit.as_ref())
+	.await?;
+
+	match account {
+		Some(_) => Ok(NoContent),
+		None if !password_valid => Err(ApiError::Validation(vec![FieldError::new(
+			"new_password",
+			"Does not follow the password policy",
+		)])),
+		None => Err(ApiError::BadRequest(
+			"Invalid or expired token".to_string(),
+		)),
+	}
+}
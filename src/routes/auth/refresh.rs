@@ -0,0 +1,59 @@
+use rocket::{State, http::CookieJar, serde::json::Json};
+
+use crate::{
+	error_handling::ApiError,
+	models::auth::{
+		AuthGuard, CsrfChecked, JwtKeyring, deliver_session_tokens, get_refresh_cookie,
+		refresh_token_ttl, rotate_refresh_token,
+	},
+};
+
+use super::domain::{RefreshPayload, RefreshResponse};
+
+/// Without a refresh token in the body, the one of the cookie is used and the new tokens go back
+/// in cookies.
+#[post("/auth/refresh", data = "<refresh_payload>")]
+#[allow(clippy::needless_pass_by_value)]
+#[allow(clippy::missing_errors_doc)]
+pub async fn refresh(
+	refresh_payload: Json<RefreshPayload>,
+	keyring: &State<JwtKeyring>,
+	cookies: &CookieJar<'_>,
+	csrf: Option<CsrfChecked>,
+) -> Result<Json<RefreshResponse>, ApiError> {
+	let payload = refresh_payload.into_inner();
+	let use_cookie = payload.refresh_token.is_none();
+
+	let refresh_token = match payload.refresh_token {
+		Some(refresh_token) => refresh_token,
+		None if csrf.is_none() => {
+			return Err(ApiError::InvalidCsrfToken);
+		}
+		None => get_refresh_cookie(cookies)
+			.ok_or_else(|| ApiError::Unauthorized("Missing refresh token".to_string()))?,
+	};
+
+	let Some(rotated) = rotate_refresh_token(&refresh_token).await? else {
+		return Err(ApiError::InvalidRefreshToken);
+	};
+
+	let Some(jwt) =
+		AuthGuard::new_raw_jwt_from_data(keyring, rotated.session_id, rotated.user_type).await?
+	else {
+		return Err(ApiError::InvalidRefreshToken);
+	};
+
+	let tokens = deliver_session_tokens(
+		cookies,
+		use_cookie,
+		jwt,
+		rotated.refresh_token,
+		refresh_token_ttl(rotated.remember_me),
+	);
+
+	Ok(Json(RefreshResponse {
+		jwt: tokens.jwt,
+		refresh_token: tokens.refresh_token,
+		csrf_token: tokens.csrf_token,
+	}))
+}
@@ -1,15 +1,11 @@
-use rocket::serde::json::Json;
+use rocket::response::status::NoContent;
 
 use crate::models::auth::AuthGuard;
 
-use super::domain::CheckSessionResponse;
-
+/// Answers `204 No Content` while the session is valid, the guard refuses it otherwise.
 #[get("/auth/check_session")]
 #[allow(clippy::needless_pass_by_value)]
 #[allow(clippy::missing_errors_doc)]
-pub fn check_session(_auth: AuthGuard) -> Json<CheckSessionResponse> {
-	Json(CheckSessionResponse {
-		valid: true,
-		error: None,
-	})
+pub fn check_session(_auth: AuthGuard) -> NoContent {
+	NoContent
 }
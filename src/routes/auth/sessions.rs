@@ -0,0 +1,99 @@
+use std::cmp::Reverse;
+
+use rocket::{State, response::status::NoContent, serde::json::Json};
+
+use crate::{
+	error_handling::ApiError,
+	models::{
+		audit::{AuditAction, AuditEvent, AuditOutcome},
+		auth::{AuthGuard, ClientInfo},
+	},
+	redis::{get_user_sessions, invalidate_session, invalidate_user_sessions},
+	repositories::Repositories,
+};
+
+use super::domain::{GetSessionsResponse, SessionDto};
+
+#[get("/auth/sessions")]
+#[allow(clippy::needless_pass_by_value)]
+#[allow(clippy::missing_errors_doc)]
+pub async fn get_sessions(auth: AuthGuard) -> Result<Json<GetSessionsResponse>, ApiError> {
+	let user_id = auth.get_user_id().await?;
+
+	let mut sessions: Vec<SessionDto> = get_user_sessions(&user_id)
+		.await?
+		.into_iter()
+		.map(|(id, session_data)| SessionDto {
+			current: id == auth.session_id,
+			id,
+			created_at: session_data.created_at,
+			last_seen: session_data.last_seen,
+			user_agent: session_data.user_agent,
+			ip: session_data.ip,
+			impersonated: session_data.impersonator.is_some(),
+		})
+		.collect();
+	sessions.sort_by_key(|session| Reverse(session.last_seen));
+
+	Ok(Json(GetSessionsResponse { sessions }))
+}
+
+#[delete("/auth/sessions/<session_id>")]
+#[allow(clippy::needless_pass_by_value)]
+#[allow(clippy::missing_errors_doc)]
+pub async fn revoke_session(
+	auth: AuthGuard,
+	session_id: &str,
+	client: ClientInfo,
+	repositories: &State<Repositories>,
+) -> Result<NoContent, ApiError> {
+	auth.forbid_impersonation()?;
+	let user_id = auth.get_user_id().await?;
+
+	let owned = get_user_sessions(&user_id)
+		.await?
+		.iter()
+		.any(|(id, _)| id == session_id);
+
+	if owned {
+		invalidate_session(session_id).await?;
+	}
+
+	AuditEvent::builder(
+		AuditAction::SessionRevoke,
+		AuditOutcome::from_success(owned),
+	)
+	.actor(&user_id, auth.user_type)
+	.target(session_id)
+	.ip(client.ip)
+	.record(repositories.audit.as_ref())
+	.await?;
+
+	if owned {
+		Ok(NoContent)
+	} else {
+		Err(ApiError::NotFound("No session with this id".to_string()))
+	}
+}
+
+#[delete("/auth/sessions")]
+#[allow(clippy::needless_pass_by_value)]
+#[allow(clippy::missing_errors_doc)]
+pub async fn revoke_all_sessions(
+	auth: AuthGuard,
+	client: ClientInfo,
+	repositories: &State<Repositories>,
+) -> Result<NoContent, ApiError> {
+	auth.forbid_impersonation()?;
+	let user_id = auth.get_user_id().await?;
+	invalidate_user_sessions(&user_id).await?;
+
+	AuditEvent::builder(AuditAction::SessionRevoke, AuditOutcome::Success)
+		.actor(&user_id, auth.user_type)
+		.target(&user_id)
+		.ip(client.ip)
+		.record(repositories.audit.as_ref())
+		.await?;
+
+	Ok(NoContent)
+}
@@ -0,0 +1,86 @@
+use rocket::{State, response::status::NoContent, serde::json::Json};
+
+use crate::{
+	error_handling::ApiError,
+	models::auth::{AuthGuard, TwofaMethod},
+	repositories::Repositories,
+	utils::crypto::totp_uri,
+};
+
+use super::domain::{TotpCodePayload, TotpConfirmResponse, TotpEnrollResponse};
+
+#[post("/auth/totp/enroll")]
+#[allow(clippy::needless_pass_by_value)]
+#[allow(clippy::missing_errors_doc)]
+pub async fn enroll_totp(
+	auth: AuthGuard,
+	repositories: &State<Repositories>,
+) -> Result<Json<TotpEnrollResponse>, ApiError> {
+	auth.forbid_impersonation()?;
+	let generic_user = auth.get_generic_user(repositories.users.as_ref()).await?;
+	let mut settings = repositories
+		.twofa
+		.get_settings(generic_user.get_id()?)
+		.await?;
+
+	let secret = settings
+		.start_totp_enrollment(repositories.twofa.as_ref())
+		.await?;
+	let otpauth_uri = totp_uri(&secret, generic_user.get_login()?);
+
+	Ok(Json(TotpEnrollResponse {
+		secret,
+		otpauth_uri,
+	}))
+}
+
+#[post("/auth/totp/confirm", data = "<totp_code_payload>")]
+#[allow(clippy::needless_pass_by_value)]
+#[allow(clippy::missing_errors_doc)]
+pub async fn confirm_totp(
+	auth: AuthGuard,
+	totp_code_payload: Json<TotpCodePayload>,
+	repositories: &State<Repositories>,
+) -> Result<Json<TotpConfirmResponse>, ApiError> {
+	auth.forbid_impersonation()?;
+	let generic_user = auth.get_generic_user(repositories.users.as_ref()).await?;
+	let mut settings = repositories
+		.twofa
+		.get_settings(generic_user.get_id()?)
+		.await?;
+
+	let recovery_codes = settings
+		.confirm_totp_enrollment(repositories.twofa.as_ref(), &totp_code_payload.code)
+		.await?
+		.ok_or(ApiError::InvalidTotpCode)?;
+
+	Ok(Json(TotpConfirmResponse { recovery_codes }))
+}
+
+#[delete("/auth/totp", data = "<totp_code_payload>")]
+#[allow(clippy::needless_pass_by_value)]
+#[allow(clippy::missing_errors_doc)]
+pub async fn disable_totp(
+	auth: AuthGuard,
+	totp_code_payload: Json<TotpCodePayload>,
+	repositories: &State<Repositories>,
+) -> Result<NoContent, ApiError> {
+	auth.forbid_impersonation()?;
+	let generic_user = auth.get_generic_user(repositories.users.as_ref()).await?;
+	let mut settings = repositories
+		.twofa
+		.get_settings(generic_user.get_id()?)
+		.await?;
+
+	if settings.method != TwofaMethod::Totp
+		|| !settings
+			.verify_code(repositories.twofa.as_ref(), &totp_code_payload.code)
+			.await?
+	{
+		return Err(ApiError::InvalidTotpCode);
+	}
+
+	settings.disable_totp(repositories.twofa.as_ref()).await?;
+
+	Ok(NoContent)
+}
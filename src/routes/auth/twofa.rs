@@ -1,14 +1,28 @@
 use std::str::FromStr;
 
-use rocket::{http::Status, serde::json::Json};
+use rocket::{
+	State,
+	http::{CookieJar, Status},
+	serde::json::Json,
+};
 use uuid::Uuid;
 
 use crate::{
-	error_handling::StatusOptionHandling,
-	models::auth::{AuthGuard, UserType},
+	error_handling::{ApiError, StatusOptionHandling},
+	metrics::record_twofa,
+	models::{
+		audit::{AuditAction, AuditEvent, AuditOutcome},
+		auth::{
+			AuthGuard, ClientInfo, JwtKeyring, Throttle, TwofaMethod, UserType,
+			deliver_session_tokens, issue_refresh_token, refresh_token_ttl, register_twofa_attempt,
+			reset_twofa_failures,
+		},
+	},
 	redis::{
-		SessionData, check_2fa_code, get_user_id_from_twofa, invalidate_transactionid, set_session,
+		SessionData, check_2fa_code, get_twofa_method_from_twofa, get_user_id_from_twofa,
+		get_user_type_from_twofa, invalidate_transactionid, set_session, transaction_exist,
 	},
+	repositories::{Repositories, TwofaRepository},
 };
 
 use super::domain::{TwofaPayload, TwofaResponse};
@@ -16,40 +30,127 @@ use super::domain::{TwofaPayload, TwofaResponse};
 #[post("/auth/twofa", data = "<twofa_payload>")]
 #[allow(clippy::needless_pass_by_value)]
 #[allow(clippy::missing_errors_doc)]
-pub fn twofa(twofa_payload: Json<TwofaPayload>) -> Result<Json<TwofaResponse>, Status> {
+pub async fn twofa(
+	twofa_payload: Json<TwofaPayload>,
+	client: ClientInfo,
+	keyring: &State<JwtKeyring>,
+	cookies: &CookieJar<'_>,
+	repositories: &State<Repositories>,
+) -> Result<Json<TwofaResponse>, ApiError> {
 	let twofa = twofa_payload.into_inner();
+	let audit = AuditEvent::builder(AuditAction::Twofa, AuditOutcome::Failure).ip(client.ip);
+
+	if let Some(retry_after) = Throttle::for_ip(client.ip).locked_for().await? {
+		audit.record(repositories.audit.as_ref()).await?;
+		return Err(ApiError::Locked {
+			retry_after: Some(retry_after),
+		});
+	}
+
+	if !transaction_exist(&twofa).await? {
+		audit.record(repositories.audit.as_ref()).await?;
+		return Err(ApiError::InvalidTwofaTransaction);
+	}
+
+	let Some(user_id) = get_user_id_from_twofa(&twofa).await? else {
+		audit.record(repositories.audit.as_ref()).await?;
+		return Err(ApiError::InvalidTwofaTransaction);
+	};
+	let user_type = get_user_type_from_twofa(&twofa).await?;
+	let audit = audit.actor(&user_id, user_type);
+
+	// Checked before the code, a locked account gets no guess at all
+	let throttle = Throttle::for_twofa(user_type, &user_id, client.ip);
+	if let Some(retry_after) = throttle.locked_for().await? {
+		audit.record(repositories.audit.as_ref()).await?;
+		return Err(ApiError::Locked {
+			retry_after: Some(retry_after),
+		});
+	}
+
+	let Some(remaining_attempts) = register_twofa_attempt(&twofa.transaction_id).await? else {
+		invalidate_transactionid(&twofa).await?;
+		audit.record(repositories.audit.as_ref()).await?;
+		return Err(ApiError::Locked { retry_after: None });
+	};
+
+	let valid_code = check_code(repositories.twofa.as_ref(), &twofa, &user_id).await?;
+	record_twofa(valid_code);
 
-	if check_2fa_code(&twofa)? {
+	if valid_code {
 		let session_id = Uuid::new_v4().to_string();
-		let user_id = get_user_id_from_twofa(&twofa)?;
-		if user_id.is_empty() {
-			return Ok(Json(TwofaResponse {
-				valid: false,
-				jwt: None,
-			}));
+		if user_type != UserType::from_str(&twofa.user_type)? {
+			audit.record(repositories.audit.as_ref()).await?;
+			return Err(ApiError::InvalidTwofaTransaction);
 		}
-		let session_data = SessionData { user_id };
+		let must_change_password = repositories
+			.accounts
+			.must_change_password(user_type, &user_id)
+			.await?;
+		let session_data = SessionData::new(user_id, must_change_password, &client);
 
-		let ttl_seconds: u64 = if twofa.remember_me {
-			30 * 24 * 3600
-		} else {
-			30 * 60
-		};
-		set_session(&session_id, &session_data, ttl_seconds)?;
-		invalidate_transactionid(&twofa)?;
+		set_session(
+			&session_id,
+			&session_data,
+			refresh_token_ttl(twofa.remember_me),
+		)
+		.await?;
+		invalidate_transactionid(&twofa).await?;
+		reset_twofa_failures(&twofa.transaction_id).await?;
+		throttle.reset().await?;
 
-		let jwt =
-			AuthGuard::new_raw_jwt_from_data(session_id, UserType::from_str(&twofa.user_type)?)?
-				.internal_server_error("JWT is somehow not valid")?;
+		let refresh_token = issue_refresh_token(&session_id, user_type, twofa.remember_me).await?;
+		let jwt = AuthGuard::new_raw_jwt_from_data(keyring, session_id, user_type)
+			.await?
+			.internal_server_error("JWT is somehow not valid")?;
+		audit
+			.outcome(AuditOutcome::Success)
+			.record(repositories.audit.as_ref())
+			.await?;
+
+		let tokens = deliver_session_tokens(
+			cookies,
+			twofa.use_cookie,
+			jwt,
+			refresh_token,
+			refresh_token_ttl(twofa.remember_me),
+		);
 
 		Ok(Json(TwofaResponse {
-			valid: true,
-			jwt: Some(jwt),
+			jwt: tokens.jwt,
+			refresh_token: tokens.refresh_token,
+			must_change_password,
+			csrf_token: tokens.csrf_token,
 		}))
 	} else {
-		Ok(Json(TwofaResponse {
-			valid: false,
-			jwt: None,
-		}))
+		let retry_after = throttle.register_failure().await?;
+		audit.record(repositories.audit.as_ref()).await?;
+
+		// Too many wrong codes, the user has to go through the password step again
+		if remaining_attempts == 0 {
+			invalidate_transactionid(&twofa).await?;
+		}
+
+		Err(ApiError::InvalidTwofaCode {
+			remaining_attempts,
+			retry_after,
+		})
+	}
+}
+
+async fn check_code(
+	twofa_repository: &dyn TwofaRepository,
+	twofa: &TwofaPayload,
+	user_id: &str,
+) -> Result<bool, Status> {
+	match get_twofa_method_from_twofa(twofa).await? {
+		TwofaMethod::Mail => check_2fa_code(twofa).await,
+		TwofaMethod::Totp => {
+			twofa_repository
+				.get_settings(user_id)
+				.await?
+				.verify_code(twofa_repository, &twofa.code)
+				.await
+		}
 	}
 }
@@ -0,0 +1,13 @@
+use rocket::{Request, http::Status};
+
+use crate::error_handling::{ApiError, cached_guard_error};
+
+/// Failures outside of the handlers (guards, unknown routes, unreadable payloads...) get the
+/// same body as the errors returned by the handlers, with the code given by the guard if any.
+#[catch(default)]
+#[allow(clippy::needless_pass_by_value)]
+pub fn default_catcher(status: Status, request: &Request) -> ApiError {
+	cached_guard_error(request)
+		.filter(|error| error.status() == status)
+		.unwrap_or_else(|| ApiError::from(status))
+}
@@ -1,37 +1,45 @@
-use rocket::{http::Status, serde::json::Json};
+use rocket::{State, response::status::NoContent, serde::json::Json};
 
 use crate::{
-	error_handling::StatusOptionHandling,
-	models::{auth::AuthGuard, courses::Class},
+	error_handling::ApiError,
+	models::{
+		audit::{AuditAction, AuditEvent, AuditOutcome},
+		auth::{
+			ClientInfo, UniversityUser, UserType,
+			policy::{ensure, university_owns_class},
+		},
+	},
+	redis::invalidate_user_sessions,
+	repositories::Repositories,
 };
 
-use super::domain::{DeleteClassPayload, DeleteClassResponse};
+use super::domain::DeleteClassPayload;
 
 #[delete("/courses/class", data = "<delete_class_payload>")]
 #[allow(clippy::needless_pass_by_value)]
 #[allow(clippy::missing_errors_doc)]
 pub async fn delete_class(
-	auth: AuthGuard,
+	university_user: UniversityUser,
+	client: ClientInfo,
 	delete_class_payload: Json<DeleteClassPayload>,
-) -> Result<Json<DeleteClassResponse>, Status> {
-	let payload = delete_class_payload.into_inner();
-	let generic_user = auth.get_generic_user().await?;
+	repositories: &State<Repositories>,
+) -> Result<NoContent, ApiError> {
+	let class_id = delete_class_payload.into_inner().class_id;
 
-	if generic_user.is_university() {
-		let class_id = payload.class_id;
-		let university = generic_user.to_university()?;
-		if university.has_class(&class_id) {
-			let class = Class::from_id(class_id)
-				.await?
-				.internal_server_error("No class with this id")?;
+	ensure(university_owns_class(&university_user.university, &class_id))?;
 
-			class.delete().await?;
-
-			Ok(Json(DeleteClassResponse { success: true }))
-		} else {
-			Err(Status::Unauthorized)
-		}
-	} else {
-		Err(Status::Unauthorized)
+	// Students are removed by the cascade, their sessions must go with them
+	for student_id in repositories.classes.delete(&class_id).await? {
+		invalidate_user_sessions(&student_id).await?;
 	}
+
+	AuditEvent::builder(AuditAction::Delete, AuditOutcome::Success)
+		.actor(&university_user.university.id, UserType::University)
+		.impersonator(university_user.auth.impersonator.as_deref())
+		.target(&class_id)
+		.ip(client.ip)
+		.record(repositories.audit.as_ref())
+		.await?;
+
+	Ok(NoContent)
 }
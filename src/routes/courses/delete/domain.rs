@@ -1,11 +1,11 @@
-use serde::{Deserialize, Serialize};
+use serde::Deserialize;
 
 #[derive(Debug, Deserialize)]
 pub struct DeleteClassPayload {
 	pub class_id: String,
 }
 
-#[derive(Debug, Serialize)]
-pub struct DeleteClassResponse {
-	pub success: bool,
+#[derive(Debug, Deserialize)]
+pub struct DeleteInternshipPayload {
+	pub internship_id: String,
 }
@@ -0,0 +1,49 @@
+use rocket::{State, response::status::NoContent, serde::json::Json};
+
+use crate::{
+	error_handling::ApiError,
+	models::{
+		audit::{AuditAction, AuditEvent, AuditOutcome},
+		auth::{
+			ApiKeyScope, ClientInfo, CompanyUser, UserType,
+			policy::{company_owns_internship, ensure},
+		},
+	},
+	repositories::Repositories,
+};
+
+use super::domain::DeleteInternshipPayload;
+
+#[delete("/courses/internship", data = "<delete_internship_payload>")]
+#[allow(clippy::needless_pass_by_value)]
+#[allow(clippy::missing_errors_doc)]
+pub async fn delete_internship(
+	company_user: CompanyUser,
+	client: ClientInfo,
+	delete_internship_payload: Json<DeleteInternshipPayload>,
+	repositories: &State<Repositories>,
+) -> Result<NoContent, ApiError> {
+	company_user.require_scope(ApiKeyScope::InternshipsWrite)?;
+	let internship_id = delete_internship_payload.into_inner().internship_id;
+
+	ensure(
+		company_owns_internship(
+			repositories.internships.as_ref(),
+			&company_user.company,
+			&internship_id,
+		)
+		.await?,
+	)?;
+
+	repositories.internships.delete(&internship_id).await?;
+
+	AuditEvent::builder(AuditAction::Delete, AuditOutcome::Success)
+		.actor(&company_user.company.id, UserType::Company)
+		.impersonator(company_user.impersonator())
+		.target(&internship_id)
+		.ip(client.ip)
+		.record(repositories.audit.as_ref())
+		.await?;
+
+	Ok(NoContent)
+}
@@ -11,6 +11,5 @@ pub struct GetClassStudentsPayload {
 
 #[derive(Debug, Serialize)]
 pub struct GetClassStudentsResponse {
-	pub success: bool,
-	pub students: Option<Vec<StudentDto>>,
+	pub students: Vec<StudentDto>,
 }
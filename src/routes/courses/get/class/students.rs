@@ -1,8 +1,15 @@
-use rocket::{http::Status, serde::json::Json};
+use rocket::{State, serde::json::Json};
 
 use crate::{
-	error_handling::StatusOptionHandling,
-	models::{auth::AuthGuard, courses::Class},
+	error_handling::ApiError,
+	models::{
+		auth::{
+			UniversityUser,
+			policy::{ensure, university_owns_class},
+		},
+		users::dto::StudentDto,
+	},
+	repositories::Repositories,
 };
 
 use super::domain::{GetClassStudentsPayload, GetClassStudentsResponse};
@@ -11,28 +18,21 @@ use super::domain::{GetClassStudentsPayload, GetClassStudentsResponse};
 #[allow(clippy::needless_pass_by_value)]
 #[allow(clippy::missing_errors_doc)]
 pub async fn get_class_students(
-	auth: AuthGuard,
+	university_user: UniversityUser,
 	get_class_students_payload: Json<GetClassStudentsPayload>,
-) -> Result<Json<GetClassStudentsResponse>, Status> {
-	let generic_user = auth.get_generic_user().await?;
-	if auth.get_generic_user().await?.is_university() {
-		let university = generic_user.to_university()?;
-		let class_id = get_class_students_payload.class_id.clone();
-		if university.has_class(&class_id) {
-			let class = Class::from_id(class_id)
-				.await?
-				.internal_server_error("No classes for this id (Not possible ?)")?;
+	repositories: &State<Repositories>,
+) -> Result<Json<GetClassStudentsResponse>, ApiError> {
+	let class_id = get_class_students_payload.into_inner().class_id;
 
-			let students = class.get_students().await?;
+	ensure(university_owns_class(&university_user.university, &class_id))?;
 
-			Ok(Json(GetClassStudentsResponse {
-				success: true,
-				students: Some(students),
-			}))
-		} else {
-			Err(Status::Unauthorized)
-		}
-	} else {
-		Err(Status::Unauthorized)
-	}
+	let students = repositories
+		.classes
+		.get_students(&class_id)
+		.await?
+		.into_iter()
+		.map(StudentDto::from)
+		.collect();
+
+	Ok(Json(GetClassStudentsResponse { students }))
 }
@@ -6,8 +6,7 @@ use crate::models::courses::{CourseType, Internship, dto::class::ClassDto};
 
 #[derive(Debug, Serialize)]
 pub struct GetClassesResponse {
-	pub success: bool,
-	pub classes: Option<Vec<ClassDto>>,
+	pub classes: Vec<ClassDto>,
 }
 
 // Internships
@@ -19,6 +18,5 @@ pub struct GetInternshipsPayload {
 
 #[derive(Debug, Serialize)]
 pub struct GetInternshipsResponse {
-	pub success: bool,
 	pub internships: Vec<Internship>,
 }
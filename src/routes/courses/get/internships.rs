@@ -1,10 +1,11 @@
 use std::ops::Index;
 
-use rocket::{http::Status, serde::json::Json};
+use rocket::{State, serde::json::Json};
 
 use crate::{
-	error_handling::StatusOptionHandling,
-	models::{auth::AuthGuard, courses::Internship},
+	error_handling::{ApiError, StatusOptionHandling},
+	models::auth::{ApiKeyScope, CompanyUser, RoleUser},
+	repositories::Repositories,
 };
 
 use super::domain::{GetInternshipsPayload, GetInternshipsResponse};
@@ -13,42 +14,66 @@ use super::domain::{GetInternshipsPayload, GetInternshipsResponse};
 #[allow(clippy::needless_pass_by_value)]
 #[allow(clippy::missing_errors_doc)]
 pub async fn get_internships(
-	auth: AuthGuard,
+	role_user: RoleUser,
 	get_internships_payload: Json<GetInternshipsPayload>,
-) -> Result<Json<GetInternshipsResponse>, Status> {
-	let generic_user = auth.get_generic_user().await?;
-	let payload = get_internships_payload.into_inner();
-
-	if generic_user.is_university()
-		&& let Some(course_types) = payload.course_types
-	{
-		let internships = Internship::get_all_based_on_course_types(course_types).await?;
-
-		Ok(Json(GetInternshipsResponse {
-			success: true,
-			internships,
-		}))
-	} else if generic_user.is_student()
-		&& let Some(course_types) = payload.course_types
-		&& course_types.len() == 1
-	{
-		let course_type = course_types.index(0);
-		let student = generic_user.to_student()?;
-		let class = student
-			.get_class()
-			.await?
-			.internal_server_error("Student has no class (Should not be possible)")?;
-		if class.course_type == *course_type {
-			let internships = Internship::get_all_based_on_course_types(course_types).await?;
-
-			Ok(Json(GetInternshipsResponse {
-				success: true,
-				internships,
-			}))
-		} else {
-			Err(Status::Unauthorized)
+	repositories: &State<Repositories>,
+) -> Result<Json<GetInternshipsResponse>, ApiError> {
+	let course_types = get_internships_payload.into_inner().course_types;
+
+	let internships = match (role_user, course_types) {
+		(RoleUser::University(_), Some(course_types)) => {
+			repositories
+				.internships
+				.get_by_course_types(&course_types)
+				.await?
+		}
+		(RoleUser::Student(student_user), Some(course_types)) if course_types.len() == 1 => {
+			let class = repositories
+				.classes
+				.get_by_student(&student_user.student.id)
+				.await?
+				.internal_server_error("Student has no class (Should not be possible)")?;
+			if class.course_type != *course_types.index(0) {
+				return Err(ApiError::Forbidden(
+					"Students only see the internships of their course type".to_string(),
+				));
+			}
+
+			repositories
+				.internships
+				.get_by_course_types(&course_types)
+				.await?
 		}
-	} else {
-		Err(Status::Unauthorized)
-	}
+		(RoleUser::Company(company_user), _) => {
+			repositories
+				.internships
+				.get_by_company(&company_user.company.id)
+				.await?
+		}
+		(RoleUser::Admin(_) | RoleUser::University(_) | RoleUser::Student(_), _) => {
+			return Err(ApiError::Forbidden(
+				"Course types are required to list internships".to_string(),
+			));
+		}
+	};
+
+	Ok(Json(GetInternshipsResponse { internships }))
+}
+
+/// Reached when the request carries an API key instead of a session, see `AuthGuard`.
+#[post("/courses/internships", data = "<_get_internships_payload>", rank = 2)]
+#[allow(clippy::needless_pass_by_value)]
+#[allow(clippy::missing_errors_doc)]
+pub async fn get_internships_with_api_key(
+	company_user: CompanyUser,
+	_get_internships_payload: Json<GetInternshipsPayload>,
+	repositories: &State<Repositories>,
+) -> Result<Json<GetInternshipsResponse>, ApiError> {
+	company_user.require_scope(ApiKeyScope::InternshipsRead)?;
+	let internships = repositories
+		.internships
+		.get_by_company(&company_user.company.id)
+		.await?;
+
+	Ok(Json(GetInternshipsResponse { internships }))
 }
@@ -0,0 +1,54 @@
+use rocket::{State, serde::json::Json};
+
+use crate::{
+	error_handling::{ApiError, FieldError},
+	models::{
+		audit::{AuditAction, AuditEvent, AuditOutcome},
+		auth::{ApiKey, ClientInfo, CompanyUser, UserType},
+	},
+	repositories::Repositories,
+};
+
+use super::domain::{CreateApiKeyPayload, CreateApiKeyResponse};
+
+#[post("/create/api_key", data = "<create_api_key_payload>")]
+#[allow(clippy::needless_pass_by_value)]
+#[allow(clippy::missing_errors_doc)]
+pub async fn create_api_key(
+	company_user: CompanyUser,
+	client: ClientInfo,
+	create_api_key_payload: Json<CreateApiKeyPayload>,
+	repositories: &State<Repositories>,
+) -> Result<Json<CreateApiKeyResponse>, ApiError> {
+	company_user.require_session()?;
+	let payload = create_api_key_payload.into_inner();
+
+	let mut invalid_fields = vec![];
+	if payload.name.trim().is_empty() {
+		invalid_fields.push(FieldError::new("name", "Must not be empty"));
+	}
+	if payload.scopes.is_empty() {
+		invalid_fields.push(FieldError::new("scopes", "At least one scope is required"));
+	}
+	if !invalid_fields.is_empty() {
+		return Err(ApiError::Validation(invalid_fields));
+	}
+
+	let (api_key, key) = ApiKey::create(
+		repositories.api_keys.as_ref(),
+		company_user.company.id.clone(),
+		payload.name,
+		payload.scopes,
+		payload.expires_at,
+	)
+	.await?;
+
+	AuditEvent::builder(AuditAction::Create, AuditOutcome::Success)
+		.actor(&company_user.company.id, UserType::Company)
+		.target(&api_key.id)
+		.ip(client.ip)
+		.record(repositories.audit.as_ref())
+		.await?;
+
+	Ok(Json(CreateApiKeyResponse { api_key, key }))
+}
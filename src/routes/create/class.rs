@@ -1,24 +1,42 @@
-use rocket::{http::Status, serde::json::Json};
+use rocket::{State, response::status::NoContent, serde::json::Json};
 
 use crate::{
-	models::{auth::AuthGuard, courses::Class},
-	postgres::Db,
+	error_handling::ApiError,
+	models::{
+		audit::{AuditAction, AuditEvent, AuditOutcome},
+		auth::{ClientInfo, UniversityUser, UserType},
+		courses::Class,
+	},
+	repositories::Repositories,
 };
 
-use super::domain::{CreateClassPayload, CreateClassResponse};
+use super::domain::CreateClassPayload;
 
 #[post("/create/class", data = "<create_class_payload>")]
 #[allow(clippy::needless_pass_by_value)]
 #[allow(clippy::missing_errors_doc)]
 pub async fn create_class(
-	auth: AuthGuard,
+	university_user: UniversityUser,
+	client: ClientInfo,
 	create_class_payload: Json<CreateClassPayload>,
-) -> Result<Json<CreateClassResponse>, Status> {
-	let class = Class::try_from_payload(create_class_payload.into_inner(), auth.session_id)?;
+	repositories: &State<Repositories>,
+) -> Result<NoContent, ApiError> {
+	let class = Class::from_payload(
+		create_class_payload.into_inner(),
+		university_user.university.id.clone(),
+	);
 
-	let is_inserted = class.insert().await;
+	let is_inserted = repositories.classes.insert(&class).await;
 
-	Ok(Json(CreateClassResponse {
-		success: is_inserted.is_ok(),
-	}))
+	AuditEvent::builder(AuditAction::Create, AuditOutcome::from_success(is_inserted.is_ok()))
+		.actor(&university_user.university.id, UserType::University)
+		.impersonator(university_user.auth.impersonator.as_deref())
+		.target(&class.id)
+		.ip(client.ip)
+		.record(repositories.audit.as_ref())
+		.await?;
+
+	is_inserted?;
+
+	Ok(NoContent)
 }
@@ -1,46 +1,69 @@
-use rocket::{http::Status, serde::json::Json};
+use rocket::{State, serde::json::Json};
+use tracing::warn;
 
 use crate::{
-	models::{auth::AuthGuard, users::Company},
-	postgres::Db,
-	utils::mail::verify_mail,
+	error_handling::ApiError,
+	models::{
+		audit::{AuditAction, AuditEvent, AuditOutcome},
+		auth::{AdminUser, ClientInfo, JwtKeyring, UserType, send_mail_verification},
+		users::Company,
+	},
+	repositories::Repositories,
+	utils::{
+		crypto::hash_password,
+		mail::{Mailer, verify_mail},
+	},
 };
 
-use super::domain::{CreateCompanyPayload, CreateUserResponse};
+use super::domain::{CreateCompanyPayload, CreateUserResponse, invalid_mail, user_insert_error};
 
 #[post("/create/company", data = "<create_company_payload>")]
 #[allow(clippy::needless_pass_by_value)]
 #[allow(clippy::missing_errors_doc)]
 pub async fn create_company(
-	auth: AuthGuard,
+	admin_user: AdminUser,
+	client: ClientInfo,
 	create_company_payload: Json<CreateCompanyPayload>,
-) -> Result<Json<CreateUserResponse>, Status> {
-	let generic_user = auth.get_generic_user().await?;
-
-	if generic_user.is_admin() {
-		let company = Company::try_from(create_company_payload.into_inner())?;
-
-		if !verify_mail(&company.mail)? {
-			return Ok(Json(CreateUserResponse {
-				success: false,
-				password: None,
-			}));
-		}
-
-		let is_inserted = company.insert().await;
-
-		if is_inserted.is_ok() {
-			Ok(Json(CreateUserResponse {
-				success: true,
-				password: Some(company.password),
-			}))
-		} else {
-			Ok(Json(CreateUserResponse {
-				success: false,
-				password: None,
-			}))
-		}
-	} else {
-		Err(Status::Unauthorized)
+	keyring: &State<JwtKeyring>,
+	mailer: &State<Mailer>,
+	repositories: &State<Repositories>,
+) -> Result<Json<CreateUserResponse>, ApiError> {
+	let company = Company::try_from(create_company_payload.into_inner())?;
+
+	if !verify_mail(&company.mail)? {
+		return Err(invalid_mail());
 	}
+
+	let password_hash = hash_password(&company.password)?;
+	let is_inserted = repositories
+		.users
+		.insert_company(&company, &password_hash)
+		.await;
+
+	AuditEvent::builder(AuditAction::Create, AuditOutcome::from_success(is_inserted.is_ok()))
+		.actor(&admin_user.admin.id, UserType::Admin)
+		.target(&company.id)
+		.ip(client.ip)
+		.record(repositories.audit.as_ref())
+		.await?;
+
+	is_inserted.map_err(user_insert_error)?;
+
+	// The account is created anyway, an admin can resend the link from the user list
+	if send_mail_verification(
+		keyring,
+		repositories.accounts.as_ref(),
+		mailer,
+		UserType::Company,
+		&company.id,
+	)
+	.await
+	.is_err()
+	{
+		warn!("Verification mail of company {} could not be sent", company.id);
+	}
+
+	Ok(Json(CreateUserResponse {
+		password: company.password,
+	}))
 }
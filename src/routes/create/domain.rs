@@ -1,12 +1,14 @@
 // Company
 
-use chrono::NaiveDate;
+use chrono::{DateTime, NaiveDate, Utc};
 use rocket::{fs::TempFile, http::Status};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
 use crate::{
+	error_handling::{ApiError, FieldError},
 	models::{
+		auth::{ApiKey, ApiKeyScope},
 		courses::CourseType,
 		users::{Company, University},
 	},
@@ -32,6 +34,7 @@ impl TryFrom<CreateCompanyPayload> for Company {
 			password,
 			mail: value.mail,
 			name: value.name,
+			mail_verified: false,
 			internship_list: Vec::new(),
 		})
 	}
@@ -39,8 +42,23 @@ impl TryFrom<CreateCompanyPayload> for Company {
 
 #[derive(Debug, Serialize)]
 pub struct CreateUserResponse {
-	pub success: bool,
-	pub password: Option<String>,
+	pub password: String,
+}
+
+/// Refused mail address of a created user.
+#[must_use]
+pub fn invalid_mail() -> ApiError {
+	ApiError::Validation(vec![FieldError::new("mail", "Invalid mail address")])
+}
+
+/// Error of a user insert, the unique columns give a `409 Conflict`.
+#[must_use]
+pub fn user_insert_error(status: Status) -> ApiError {
+	if status == Status::Conflict {
+		ApiError::Conflict("Login or mail already used".to_string())
+	} else {
+		status.into()
+	}
 }
 
 // Student
@@ -51,11 +69,6 @@ pub struct StudentCsvPayload<'r> {
 	pub class: String,
 }
 
-#[derive(Debug, Serialize)]
-pub struct StudentCsvResponse {
-	pub success: bool,
-}
-
 // University
 
 #[derive(Debug, Deserialize)]
@@ -77,6 +90,7 @@ impl TryFrom<CreateUniversityPayload> for University {
 			password,
 			name: value.name,
 			mail: value.mail,
+			mail_verified: false,
 			class_list: vec![],
 			intership_list: vec![],
 		})
@@ -95,11 +109,6 @@ pub struct CreateClassPayload {
 	pub minimum_internship_length: i32,
 }
 
-#[derive(Debug, Serialize)]
-pub struct CreateClassResponse {
-	pub success: bool,
-}
-
 // Internship
 #[derive(Debug, Deserialize)]
 pub struct CreateIntershipPayload {
@@ -113,7 +122,27 @@ pub struct CreateIntershipPayload {
 	pub place: String,
 }
 
+// Api key
+
+#[derive(Debug, Deserialize)]
+pub struct CreateApiKeyPayload {
+	pub name: String,
+	pub scopes: Vec<ApiKeyScope>,
+	pub expires_at: Option<DateTime<Utc>>,
+}
+
 #[derive(Debug, Serialize)]
-pub struct CreateInternshipResponse {
-	pub success: bool,
+pub struct CreateApiKeyResponse {
+	pub api_key: ApiKey,
+	/// Only returned at creation, the server keeps a hash
+	pub key: String,
+}
+
+// Oidc provider
+
+#[derive(Debug, Deserialize)]
+pub struct CreateOidcProviderPayload {
+	pub issuer: String,
+	pub client_id: String,
+	pub client_secret: Option<String>,
 }
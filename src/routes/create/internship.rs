@@ -1,39 +1,56 @@
-use rocket::{http::Status, serde::json::Json};
+use rocket::{State, response::status::NoContent, serde::json::Json};
 use uuid::Uuid;
 
-use crate::models::{auth::AuthGuard, courses::Internship};
+use crate::{
+	error_handling::ApiError,
+	metrics::record_internship_created,
+	models::{
+		audit::{AuditAction, AuditEvent, AuditOutcome},
+		auth::{ApiKeyScope, ClientInfo, CompanyUser, UserType},
+		courses::Internship,
+	},
+	repositories::Repositories,
+};
 
-use super::domain::{CreateInternshipResponse, CreateIntershipPayload};
+use super::domain::CreateIntershipPayload;
 
 #[post("/create/internship", data = "<create_internship_payload>")]
 #[allow(clippy::needless_pass_by_value)]
 #[allow(clippy::missing_errors_doc)]
 pub async fn create_internship(
-	auth: AuthGuard,
+	company_user: CompanyUser,
+	client: ClientInfo,
 	create_internship_payload: Json<CreateIntershipPayload>,
-) -> Result<Json<CreateInternshipResponse>, Status> {
+	repositories: &State<Repositories>,
+) -> Result<NoContent, ApiError> {
+	company_user.require_scope(ApiKeyScope::InternshipsWrite)?;
 	let payload = create_internship_payload.into_inner();
-	let user = auth.get_generic_user().await?;
 
-	if user.is_company() {
-		let company = user.to_company()?;
+	let internship = Internship {
+		id: Uuid::new_v4().to_string(),
+		course_type: payload.course_type,
+		date_start: payload.start_date,
+		date_end: payload.end_date,
+		min_internship_length: payload.min_internship_length,
+		max_internship_length: payload.max_internship_length,
+		title: payload.title,
+		description: payload.description,
+		place: payload.place,
+	};
 
-		let internship = Internship {
-			id: Uuid::new_v4().to_string(),
-			course_type: payload.course_type,
-			date_start: payload.start_date,
-			date_end: payload.end_date,
-			min_internship_length: payload.min_internship_length,
-			max_internship_length: payload.max_internship_length,
-			title: payload.title,
-			description: payload.description,
-			place: payload.place,
-		};
+	repositories
+		.internships
+		.insert_for_company(&internship, &company_user.company.id)
+		.await?;
+	record_internship_created();
 
-		internship.insert_with_company(company.id.clone()).await?;
+	AuditEvent::builder(AuditAction::Create, AuditOutcome::Success)
+		.actor(&company_user.company.id, UserType::Company)
+		.impersonator(company_user.impersonator())
+		.target(&internship.id)
+		.ip(client.ip)
+		.record(repositories.audit.as_ref())
+		.await?;
 
-		Ok(Json(CreateInternshipResponse { success: true }))
-	} else {
-		Err(Status::Unauthorized)
-	}
+	Ok(NoContent)
 }
@@ -1,6 +1,8 @@
+pub mod api_key;
 pub mod class;
 pub mod company;
 pub mod domain;
 pub mod internship;
+pub mod oidc_provider;
 pub mod students;
 pub mod university;
@@ -0,0 +1,60 @@
+use rocket::{State, response::status::NoContent, serde::json::Json};
+use url::Url;
+
+use crate::{
+	config::oidc_config,
+	error_handling::{ApiError, FieldError},
+	models::{
+		audit::{AuditAction, AuditEvent, AuditOutcome},
+		auth::{ClientInfo, OidcProvider, UniversityUser, UserType},
+	},
+	repositories::Repositories,
+};
+
+use super::domain::CreateOidcProviderPayload;
+
+/// Replaces the identity provider of the university if it already has one.
+#[post("/create/oidc_provider", data = "<create_oidc_provider_payload>")]
+#[allow(clippy::needless_pass_by_value)]
+#[allow(clippy::missing_errors_doc)]
+pub async fn create_oidc_provider(
+	university_user: UniversityUser,
+	client: ClientInfo,
+	create_oidc_provider_payload: Json<CreateOidcProviderPayload>,
+	repositories: &State<Repositories>,
+) -> Result<NoContent, ApiError> {
+	university_user.auth.forbid_impersonation()?;
+	let payload = create_oidc_provider_payload.into_inner();
+
+	let mut invalid_fields = vec![];
+	match Url::parse(&payload.issuer) {
+		Ok(issuer)
+			if issuer.scheme() == "https"
+				|| (issuer.scheme() == "http" && oidc_config().allow_insecure_providers) => {}
+		Ok(_) => invalid_fields.push(FieldError::new("issuer", "Must be an https URL")),
+		Err(_) => invalid_fields.push(FieldError::new("issuer", "Must be a URL")),
+	}
+	if payload.client_id.trim().is_empty() {
+		invalid_fields.push(FieldError::new("client_id", "Must not be empty"));
+	}
+	if !invalid_fields.is_empty() {
+		return Err(ApiError::Validation(invalid_fields));
+	}
+
+	let provider = OidcProvider {
+		university_id: university_user.university.id,
+		issuer: payload.issuer,
+		client_id: payload.client_id,
+		client_secret: payload.client_secret,
+	};
+	repositories.oidc_providers.save(&provider).await?;
+
+	AuditEvent::builder(AuditAction::Create, AuditOutcome::Success)
+		.actor(&provider.university_id, UserType::University)
+		.target(&provider.university_id)
+		.ip(client.ip)
+		.record(repositories.audit.as_ref())
+		.await?;
+
+	Ok(NoContent)
+}
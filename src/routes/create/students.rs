@@ -1,24 +1,44 @@
 use std::io::Cursor;
 
-use rocket::{form::Form, http::Status, serde::json::Json};
+use rocket::{State, form::Form, response::status::NoContent};
 use tokio::io::AsyncReadExt;
+use tracing::warn;
 
 use crate::{
-	error_handling::StatusResultHandling,
-	models::{auth::AuthGuard, users::Student},
+	error_handling::{ApiError, StatusResultHandling},
+	metrics::record_student_imported,
+	models::{
+		audit::{AuditAction, AuditEvent, AuditOutcome},
+		auth::{
+			ClientInfo, JwtKeyring, UniversityUser, UserType, send_mail_verification,
+			policy::{ensure, university_owns_class},
+		},
+		users::Student,
+	},
+	repositories::Repositories,
+	utils::{crypto::hash_password, mail::Mailer},
 };
 
-use super::domain::{StudentCsvPayload, StudentCsvResponse};
+use super::domain::{StudentCsvPayload, user_insert_error};
 
 #[post("/create/students", data = "<student_csv_payload>")]
 #[allow(clippy::needless_pass_by_value)]
 #[allow(clippy::missing_errors_doc)]
 pub async fn create_students(
-	_auth: AuthGuard,
+	university_user: UniversityUser,
+	client: ClientInfo,
 	student_csv_payload: Form<StudentCsvPayload<'_>>,
-) -> Result<Json<StudentCsvResponse>, Status> {
+	keyring: &State<JwtKeyring>,
+	mailer: &State<Mailer>,
+	repositories: &State<Repositories>,
+) -> Result<NoContent, ApiError> {
 	let payload = student_csv_payload.into_inner();
 
+	ensure(university_owns_class(
+		&university_user.university,
+		&payload.class,
+	))?;
+
 	let mut reader = payload
 		.csv
 		.open()
@@ -36,8 +56,35 @@ pub async fn create_students(
 	let mut reader = csv::Reader::from_reader(cursor);
 	for result in reader.records() {
 		let record = result.internal_server_error("Failed to read record")?;
-		let student = Student::from_record(record).await?;
-		student.insert_self(payload.class.clone()).await?;
+		let student = Student::from_record(record, repositories.users.as_ref()).await?;
+		let password_hash = hash_password(&student.password)?;
+		repositories
+			.users
+			.insert_student(&student, &password_hash, &payload.class)
+			.await
+			.map_err(user_insert_error)?;
+		record_student_imported();
+
+		AuditEvent::builder(AuditAction::Create, AuditOutcome::Success)
+			.actor(&university_user.university.id, UserType::University)
+			.impersonator(university_user.auth.impersonator.as_deref())
+			.target(&student.id)
+			.ip(client.ip)
+			.record(repositories.audit.as_ref())
+			.await?;
+
+		if send_mail_verification(
+			keyring,
+			repositories.accounts.as_ref(),
+			mailer,
+			UserType::Student,
+			&student.id,
+		)
+		.await
+		.is_err()
+		{
+			warn!("Verification mail of student {} could not be sent", student.id);
+		}
 	}
-	Ok(Json(StudentCsvResponse { success: true }))
+	Ok(NoContent)
 }
@@ -1,46 +1,69 @@
-use rocket::{http::Status, serde::json::Json};
+use rocket::{State, serde::json::Json};
+use tracing::warn;
 
 use crate::{
-	models::{auth::AuthGuard, users::University},
-	postgres::Db,
-	utils::mail::verify_mail,
+	error_handling::ApiError,
+	models::{
+		audit::{AuditAction, AuditEvent, AuditOutcome},
+		auth::{AdminUser, ClientInfo, JwtKeyring, UserType, send_mail_verification},
+		users::University,
+	},
+	repositories::Repositories,
+	utils::{
+		crypto::hash_password,
+		mail::{Mailer, verify_mail},
+	},
 };
 
-use super::domain::{CreateUniversityPayload, CreateUserResponse};
+use super::domain::{CreateUniversityPayload, CreateUserResponse, invalid_mail, user_insert_error};
 
 #[post("/create/university", data = "<create_university_payload>")]
 #[allow(clippy::needless_pass_by_value)]
 #[allow(clippy::missing_errors_doc)]
 pub async fn create_university(
-	auth: AuthGuard,
+	admin_user: AdminUser,
+	client: ClientInfo,
 	create_university_payload: Json<CreateUniversityPayload>,
-) -> Result<Json<CreateUserResponse>, Status> {
-	let generic_user = auth.get_generic_user().await?;
-
-	if generic_user.is_admin() {
-		let university = University::try_from(create_university_payload.into_inner())?;
-
-		if !verify_mail(&university.mail)? {
-			return Ok(Json(CreateUserResponse {
-				success: false,
-				password: None,
-			}));
-		}
-
-		let is_inserted = university.insert().await;
-
-		if is_inserted.is_ok() {
-			Ok(Json(CreateUserResponse {
-				success: true,
-				password: Some(university.password),
-			}))
-		} else {
-			Ok(Json(CreateUserResponse {
-				success: false,
-				password: None,
-			}))
-		}
-	} else {
-		Err(Status::Unauthorized)
+	keyring: &State<JwtKeyring>,
+	mailer: &State<Mailer>,
+	repositories: &State<Repositories>,
+) -> Result<Json<CreateUserResponse>, ApiError> {
+	let university = University::try_from(create_university_payload.into_inner())?;
+
+	if !verify_mail(&university.mail)? {
+		return Err(invalid_mail());
 	}
+
+	let password_hash = hash_password(&university.password)?;
+	let is_inserted = repositories
+		.users
+		.insert_university(&university, &password_hash)
+		.await;
+
+	AuditEvent::builder(AuditAction::Create, AuditOutcome::from_success(is_inserted.is_ok()))
+		.actor(&admin_user.admin.id, UserType::Admin)
+		.target(&university.id)
+		.ip(client.ip)
+		.record(repositories.audit.as_ref())
+		.await?;
+
+	is_inserted.map_err(user_insert_error)?;
+
+	// The account is created anyway, an admin can resend the link from the user list
+	if send_mail_verification(
+		keyring,
+		repositories.accounts.as_ref(),
+		mailer,
+		UserType::University,
+		&university.id,
+	)
+	.await
+	.is_err()
+	{
+		warn!("Verification mail of university {} could not be sent", university.id);
+	}
+
+	Ok(Json(CreateUserResponse {
+		password: university.password,
+	}))
 }
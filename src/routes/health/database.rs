@@ -0,0 +1,31 @@
+use deadpool_postgres::Pool;
+use rocket::{State, serde::json::Json};
+
+use crate::{error_handling::ApiError, models::auth::MetricsScraper};
+
+use super::domain::DatabaseHealthResponse;
+
+/// Usage of the postgres pool, `healthy` once a pooled connection answered a query.
+///
+/// The pool figures are for the monitoring, which sends the token of `/metrics`.
+#[get("/health/database")]
+#[allow(clippy::needless_pass_by_value)]
+#[allow(clippy::missing_errors_doc)]
+pub async fn get_database_health(
+	_scraper: MetricsScraper,
+	pool: &State<Pool>,
+) -> Result<Json<DatabaseHealthResponse>, ApiError> {
+	let healthy = match pool.get().await {
+		Ok(client) => client.simple_query("SELECT 1;").await.is_ok(),
+		Err(_) => false,
+	};
+	let status = pool.status();
+
+	Ok(Json(DatabaseHealthResponse {
+		healthy,
+		max_size: status.max_size,
+		size: status.size,
+		available: status.available,
+		waiting: status.waiting,
+	}))
+}
@@ -0,0 +1,55 @@
+use serde::Serialize;
+
+// Database
+
+#[derive(Debug, Serialize)]
+pub struct DatabaseHealthResponse {
+	pub healthy: bool,
+	pub max_size: usize,
+	pub size: usize,
+	pub available: usize,
+	pub waiting: usize,
+}
+
+// Probes
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum HealthStatus {
+	Up,
+	Down,
+}
+
+/// Why a dependency is down, the details are only logged.
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ProbeError {
+	Timeout,
+	Unreachable,
+}
+
+#[derive(Debug, Serialize)]
+pub struct LiveResponse {
+	pub status: HealthStatus,
+}
+
+#[derive(Debug, Serialize)]
+pub struct DependencyHealth {
+	pub status: HealthStatus,
+	pub duration_ms: u64,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub error: Option<ProbeError>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ReadyChecks {
+	pub postgres: DependencyHealth,
+	pub redis: DependencyHealth,
+	pub mail: DependencyHealth,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ReadyResponse {
+	pub status: HealthStatus,
+	pub checks: ReadyChecks,
+}
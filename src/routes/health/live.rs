@@ -0,0 +1,12 @@
+use rocket::serde::json::Json;
+
+use super::domain::{HealthStatus, LiveResponse};
+
+/// Liveness probe, answers as long as the process serves requests whatever its dependencies.
+#[get("/health/live")]
+#[must_use]
+pub fn get_liveness() -> Json<LiveResponse> {
+	Json(LiveResponse {
+		status: HealthStatus::Up,
+	})
+}
@@ -0,0 +1,85 @@
+use std::{
+	future::Future,
+	time::{Duration, Instant},
+};
+
+use deadpool_postgres::Pool;
+use rocket::{State, http::Status, serde::json::Json};
+use tokio::time;
+use tracing::warn;
+
+use crate::{
+	config::health_config, logging::millis, postgres::ping_database, redis::ping_redis,
+	utils::mail::Mailer,
+};
+
+use super::domain::{DependencyHealth, HealthStatus, ProbeError, ReadyChecks, ReadyResponse};
+
+/// Readiness probe, `503 Service Unavailable` until Postgres, Redis and the SMTP relay all answer
+/// within `HEALTH_TIMEOUT_SECONDS`. They are checked concurrently.
+#[get("/health/ready")]
+#[allow(clippy::needless_pass_by_value)]
+pub async fn get_readiness(
+	pool: &State<Pool>,
+	mailer: &State<Mailer>,
+) -> (Status, Json<ReadyResponse>) {
+	let timeout = Duration::from_secs(health_config().timeout_seconds);
+
+	let (postgres, redis, mail) = tokio::join!(
+		probe("postgres", timeout, ping_database(pool)),
+		probe("redis", timeout, ping_redis()),
+		probe("mail", timeout, mailer.check_connection()),
+	);
+
+	let checks = ReadyChecks {
+		postgres,
+		redis,
+		mail,
+	};
+	let ready = [&checks.postgres, &checks.redis, &checks.mail]
+		.iter()
+		.all(|check| check.status == HealthStatus::Up);
+
+	let (status, health) = if ready {
+		(Status::Ok, HealthStatus::Up)
+	} else {
+		(Status::ServiceUnavailable, HealthStatus::Down)
+	};
+
+	(
+		status,
+		Json(ReadyResponse {
+			status: health,
+			checks,
+		}),
+	)
+}
+
+async fn probe<F>(dependency: &str, timeout: Duration, check: F) -> DependencyHealth
+where
+	F: Future<Output = Result<(), String>>,
+{
+	let start = Instant::now();
+
+	let error = match time::timeout(timeout, check).await {
+		Ok(Ok(())) => None,
+		Ok(Err(e)) => {
+			warn!(dependency, "Readiness check failed : {e}");
+			Some(ProbeError::Unreachable)
+		}
+		Err(_) => {
+			warn!(dependency, "Readiness check timed out");
+			Some(ProbeError::Timeout)
+		}
+	};
+
+	DependencyHealth {
+		status: if error.is_none() {
+			HealthStatus::Up
+		} else {
+			HealthStatus::Down
+		},
+		duration_ms: millis(start.elapsed()),
+		error,
+	}
+}
@@ -0,0 +1,20 @@
+use rocket::http::ContentType;
+
+use crate::{
+	error_handling::{ApiError, StatusResultHandling},
+	metrics::encode_metrics,
+	models::auth::MetricsScraper,
+};
+
+/// Metrics in the Prometheus text format, see `crate::metrics`.
+#[get("/metrics")]
+#[allow(clippy::needless_pass_by_value)]
+#[allow(clippy::missing_errors_doc)]
+pub fn get_metrics(_scraper: MetricsScraper) -> Result<(ContentType, String), ApiError> {
+	let metrics = encode_metrics().internal_server_error("Can't encode the metrics")?;
+
+	// Version of the exposition format, which scrapers use to pick their parser
+	let content_type = ContentType::new("text", "plain").with_params(("version", "0.0.4"));
+
+	Ok((content_type, metrics))
+}
@@ -1,4 +1,8 @@
+pub mod audit;
 pub mod auth;
+pub mod catchers;
 pub mod courses;
 pub mod create;
+pub mod health;
+pub mod metrics;
 pub mod user;
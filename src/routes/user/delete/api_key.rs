@@ -0,0 +1,42 @@
+use rocket::{State, response::status::NoContent, serde::json::Json};
+
+use crate::{
+	error_handling::ApiError,
+	models::{
+		audit::{AuditAction, AuditEvent, AuditOutcome},
+		auth::{ClientInfo, CompanyUser, UserType},
+	},
+	repositories::Repositories,
+};
+
+use super::domain::DeleteApiKeyPayload;
+
+#[delete("/user/company/api_key", data = "<delete_api_key_payload>")]
+#[allow(clippy::needless_pass_by_value)]
+#[allow(clippy::missing_errors_doc)]
+pub async fn delete_api_key(
+	company_user: CompanyUser,
+	client: ClientInfo,
+	delete_api_key_payload: Json<DeleteApiKeyPayload>,
+	repositories: &State<Repositories>,
+) -> Result<NoContent, ApiError> {
+	company_user.require_session()?;
+
+	let success = repositories
+		.api_keys
+		.delete(&company_user.company.id, &delete_api_key_payload.id)
+		.await?;
+
+	AuditEvent::builder(AuditAction::Delete, AuditOutcome::from_success(success))
+		.actor(&company_user.company.id, UserType::Company)
+		.target(&delete_api_key_payload.id)
+		.ip(client.ip)
+		.record(repositories.audit.as_ref())
+		.await?;
+
+	if success {
+		Ok(NoContent)
+	} else {
+		Err(ApiError::NotFound("No API key with this id".to_string()))
+	}
+}
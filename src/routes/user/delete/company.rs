@@ -1,27 +1,48 @@
-use rocket::{http::Status, serde::json::Json};
+use rocket::{State, http::Status, response::status::NoContent, serde::json::Json};
 
 use crate::{
-	models::{auth::AuthGuard, users::Company},
-	postgres::Db,
+	error_handling::ApiError,
+	models::{
+		audit::{AuditAction, AuditEvent, AuditOutcome},
+		auth::{AdminUser, ClientInfo, UserType},
+	},
+	redis::invalidate_user_sessions,
+	repositories::Repositories,
 };
 
-use super::domain::{DeleteCompanyPayload, DeleteCompanyResponse};
+use super::domain::DeleteCompanyPayload;
 
 #[delete("/user/company", data = "<delete_company_payload>")]
 #[allow(clippy::needless_pass_by_value)]
 #[allow(clippy::missing_errors_doc)]
 pub async fn delete_company(
 	delete_company_payload: Json<DeleteCompanyPayload>,
-	auth: AuthGuard,
-) -> Result<Json<DeleteCompanyResponse>, Status> {
-	let generic_user = auth.get_generic_user().await?;
-
-	if generic_user.is_admin() {
-		let company = Company::from_id(delete_company_payload.id.clone()).await?;
-		Ok(Json(DeleteCompanyResponse {
-			success: company.delete().await.is_ok(),
-		}))
-	} else {
-		Err(Status::Unauthorized)
-	}
+	admin_user: AdminUser,
+	client: ClientInfo,
+	repositories: &State<Repositories>,
+) -> Result<NoContent, ApiError> {
+	let company = repositories
+		.users
+		.get_company(&delete_company_payload.id)
+		.await?
+		.ok_or_else(|| ApiError::NotFound("No company with this id".to_string()))?;
+
+	let removed = remove_company(repositories, &company.id).await;
+	let success = removed.is_ok();
+
+	AuditEvent::builder(AuditAction::Delete, AuditOutcome::from_success(success))
+		.actor(&admin_user.admin.id, UserType::Admin)
+		.target(&company.id)
+		.ip(client.ip)
+		.record(repositories.audit.as_ref())
+		.await?;
+
+	removed?;
+
+	Ok(NoContent)
+}
+
+async fn remove_company(repositories: &Repositories, id: &str) -> Result<(), Status> {
+	repositories.users.delete_company(id).await?;
+	invalidate_user_sessions(id).await
 }
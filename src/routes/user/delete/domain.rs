@@ -1,12 +1,14 @@
-use serde::{Deserialize, Serialize};
+use serde::Deserialize;
 
-// Company
+// Api key
 
-#[derive(Debug, Serialize)]
-pub struct DeleteCompanyResponse {
-	pub success: bool,
+#[derive(Debug, Deserialize)]
+pub struct DeleteApiKeyPayload {
+	pub id: String,
 }
 
+// Company
+
 #[derive(Debug, Deserialize)]
 pub struct DeleteCompanyPayload {
 	pub id: String,
@@ -14,11 +16,6 @@ pub struct DeleteCompanyPayload {
 
 // University
 
-#[derive(Debug, Serialize)]
-pub struct DeleteUniversityResponse {
-	pub success: bool,
-}
-
 #[derive(Debug, Deserialize)]
 pub struct DeleteUniversityPayload {
 	pub id: String,
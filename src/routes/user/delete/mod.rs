@@ -1,3 +1,5 @@
+pub mod api_key;
 pub mod company;
 pub mod domain;
+pub mod oidc_provider;
 pub mod university;
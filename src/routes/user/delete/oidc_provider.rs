@@ -0,0 +1,35 @@
+use rocket::{State, response::status::NoContent};
+
+use crate::{
+	error_handling::ApiError,
+	models::{
+		audit::{AuditAction, AuditEvent, AuditOutcome},
+		auth::{ClientInfo, UniversityUser, UserType},
+	},
+	repositories::Repositories,
+};
+
+/// Users of the university fall back on the password login.
+#[delete("/user/university/oidc_provider")]
+#[allow(clippy::needless_pass_by_value)]
+#[allow(clippy::missing_errors_doc)]
+pub async fn delete_oidc_provider(
+	university_user: UniversityUser,
+	client: ClientInfo,
+	repositories: &State<Repositories>,
+) -> Result<NoContent, ApiError> {
+	university_user.auth.forbid_impersonation()?;
+	repositories
+		.oidc_providers
+		.delete(&university_user.university.id)
+		.await?;
+
+	AuditEvent::builder(AuditAction::Delete, AuditOutcome::Success)
+		.actor(&university_user.university.id, UserType::University)
+		.target(&university_user.university.id)
+		.ip(client.ip)
+		.record(repositories.audit.as_ref())
+		.await?;
+
+	Ok(NoContent)
+}
@@ -1,27 +1,52 @@
-use rocket::{http::Status, serde::json::Json};
+use rocket::{State, http::Status, response::status::NoContent, serde::json::Json};
 
 use crate::{
-	models::{auth::AuthGuard, users::University},
-	postgres::Db,
+	error_handling::ApiError,
+	models::{
+		audit::{AuditAction, AuditEvent, AuditOutcome},
+		auth::{AdminUser, ClientInfo, UserType},
+	},
+	redis::invalidate_user_sessions,
+	repositories::Repositories,
 };
 
-use super::domain::{DeleteUniversityPayload, DeleteUniversityResponse};
+use super::domain::DeleteUniversityPayload;
 
 #[delete("/user/university", data = "<delete_university_payload>")]
 #[allow(clippy::needless_pass_by_value)]
 #[allow(clippy::missing_errors_doc)]
 pub async fn delete_university(
 	delete_university_payload: Json<DeleteUniversityPayload>,
-	auth: AuthGuard,
-) -> Result<Json<DeleteUniversityResponse>, Status> {
-	let generic_user = auth.get_generic_user().await?;
-
-	if generic_user.is_admin() {
-		let university = University::from_id(delete_university_payload.id.clone()).await?;
-		Ok(Json(DeleteUniversityResponse {
-			success: university.delete().await.is_ok(),
-		}))
-	} else {
-		Err(Status::Unauthorized)
+	admin_user: AdminUser,
+	client: ClientInfo,
+	repositories: &State<Repositories>,
+) -> Result<NoContent, ApiError> {
+	let university = repositories
+		.users
+		.get_university(&delete_university_payload.id)
+		.await?
+		.ok_or_else(|| ApiError::NotFound("No university with this id".to_string()))?;
+
+	let removed = remove_university(repositories, &university.id).await;
+	let success = removed.is_ok();
+
+	AuditEvent::builder(AuditAction::Delete, AuditOutcome::from_success(success))
+		.actor(&admin_user.admin.id, UserType::Admin)
+		.target(&university.id)
+		.ip(client.ip)
+		.record(repositories.audit.as_ref())
+		.await?;
+
+	removed?;
+
+	Ok(NoContent)
+}
+
+async fn remove_university(repositories: &Repositories, id: &str) -> Result<(), Status> {
+	// Students are removed by the cascade, their sessions must go with them
+	for student_id in repositories.users.delete_university(id).await? {
+		invalidate_user_sessions(&student_id).await?;
 	}
+
+	invalidate_user_sessions(id).await
 }
@@ -0,0 +1,21 @@
+use rocket::{State, serde::json::Json};
+
+use crate::{error_handling::ApiError, models::auth::CompanyUser, repositories::Repositories};
+
+use super::domain::GetApiKeysResponse;
+
+#[get("/user/company/api_keys")]
+#[allow(clippy::needless_pass_by_value)]
+#[allow(clippy::missing_errors_doc)]
+pub async fn get_api_keys(
+	company_user: CompanyUser,
+	repositories: &State<Repositories>,
+) -> Result<Json<GetApiKeysResponse>, ApiError> {
+	company_user.require_session()?;
+	let api_keys = repositories
+		.api_keys
+		.get_by_company(&company_user.company.id)
+		.await?;
+
+	Ok(Json(GetApiKeysResponse { api_keys }))
+}
@@ -1,30 +1,17 @@
-use rocket::{http::Status, serde::json::Json};
+use rocket::{State, serde::json::Json};
 
-use crate::models::{auth::AuthGuard, users::Company};
+use crate::{error_handling::ApiError, models::auth::AdminUser, repositories::Repositories};
 
 use super::domain::GetCompaniesResponse;
 
 #[get("/user/companies")]
 #[allow(clippy::needless_pass_by_value)]
 #[allow(clippy::missing_errors_doc)]
-pub async fn get_companies(auth: AuthGuard) -> Result<Json<GetCompaniesResponse>, Status> {
-	let generic_user = auth.get_generic_user().await?;
-	if generic_user.is_admin() {
-		let companies = Company::get_all().await;
+pub async fn get_companies(
+	_admin: AdminUser,
+	repositories: &State<Repositories>,
+) -> Result<Json<GetCompaniesResponse>, ApiError> {
+	let companies = repositories.users.get_companies().await?;
 
-		companies.map_or(
-			Ok(Json(GetCompaniesResponse {
-				success: false,
-				companies: None,
-			})),
-			|companies| {
-				Ok(Json(GetCompaniesResponse {
-					success: true,
-					companies: Some(companies),
-				}))
-			},
-		)
-	} else {
-		Err(Status::Unauthorized)
-	}
+	Ok(Json(GetCompaniesResponse { companies }))
 }
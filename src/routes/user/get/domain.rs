@@ -1,7 +1,7 @@
 use serde::{Deserialize, Serialize};
 
 use crate::models::{
-	auth::UserType,
+	auth::{ApiKey, UserType},
 	users::{Company, University},
 };
 
@@ -15,13 +15,17 @@ pub struct GetUserTypeResponse {
 // Universities
 #[derive(Debug, Serialize, Deserialize)]
 pub struct GetUniversitiesResponse {
-	pub success: bool,
-	pub universities: Option<Vec<University>>,
+	pub universities: Vec<University>,
 }
 
 // Companies
 #[derive(Debug, Serialize, Deserialize)]
 pub struct GetCompaniesResponse {
-	pub success: bool,
-	pub companies: Option<Vec<Company>>,
+	pub companies: Vec<Company>,
+}
+
+// Api keys
+#[derive(Debug, Serialize)]
+pub struct GetApiKeysResponse {
+	pub api_keys: Vec<ApiKey>,
 }
@@ -1,3 +1,4 @@
+pub mod api_keys;
 pub mod companies;
 pub mod domain;
 pub mod student;
@@ -1,6 +1,10 @@
-use rocket::{http::Status, serde::json::Json};
+use rocket::{State, serde::json::Json};
 
-use crate::models::auth::AuthGuard;
+use crate::{
+	error_handling::{ApiError, StatusOptionHandling},
+	models::auth::StudentUser,
+	repositories::Repositories,
+};
 
 use super::domain::GetCourseTypeResponse;
 
@@ -8,17 +12,16 @@ use super::domain::GetCourseTypeResponse;
 #[allow(clippy::needless_pass_by_value)]
 #[allow(clippy::missing_errors_doc)]
 pub async fn get_student_course_type(
-	auth: AuthGuard,
-) -> Result<Json<GetCourseTypeResponse>, Status> {
-	let generic_user = auth.get_generic_user().await?;
+	student_user: StudentUser,
+	repositories: &State<Repositories>,
+) -> Result<Json<GetCourseTypeResponse>, ApiError> {
+	let course_type = repositories
+		.course_types
+		.get_by_student(&student_user.student.id)
+		.await?
+		.internal_server_error("Student has no class")?;
 
-	if generic_user.is_student() {
-		let student = generic_user.to_student()?;
-		Ok(Json(GetCourseTypeResponse {
-			success: true,
-			course_type: Some(vec![student.get_course_type().await?]),
-		}))
-	} else {
-		Err(Status::Unauthorized)
-	}
+	Ok(Json(GetCourseTypeResponse {
+		course_type: vec![course_type],
+	}))
 }
@@ -6,18 +6,16 @@ use crate::models::courses::CourseType;
 
 #[derive(Debug, Serialize)]
 pub struct GetInfoResponse {
-	pub success: bool,
-	pub first_name: Option<String>,
-	pub last_name: Option<String>,
-	pub email: Option<String>,
-	pub university: Option<String>,
-	pub class_name: Option<String>,
+	pub first_name: String,
+	pub last_name: String,
+	pub email: String,
+	pub university: String,
+	pub class_name: String,
 }
 
 // get course type
 
 #[derive(Debug, Serialize)]
 pub struct GetCourseTypeResponse {
-	pub success: bool,
-	pub course_type: Option<Vec<CourseType>>,
+	pub course_type: Vec<CourseType>,
 }
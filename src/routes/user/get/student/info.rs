@@ -1,32 +1,37 @@
-use rocket::{http::Status, serde::json::Json};
+use rocket::{State, serde::json::Json};
 
-use crate::{error_handling::StatusOptionHandling, models::auth::AuthGuard};
+use crate::{
+	error_handling::{ApiError, StatusOptionHandling},
+	models::auth::StudentUser,
+	repositories::Repositories,
+};
 
 use super::domain::GetInfoResponse;
 
 #[get("/user/student/info")]
 #[allow(clippy::needless_pass_by_value)]
 #[allow(clippy::missing_errors_doc)]
-pub async fn get_student_info(auth: AuthGuard) -> Result<Json<GetInfoResponse>, Status> {
-	let generic_user = auth.get_generic_user().await?;
+pub async fn get_student_info(
+	student_user: StudentUser,
+	repositories: &State<Repositories>,
+) -> Result<Json<GetInfoResponse>, ApiError> {
+	let student = student_user.student;
+	let class = repositories
+		.classes
+		.get_by_student(&student.id)
+		.await?
+		.internal_server_error("Student exists but has no class")?;
+	let university = repositories
+		.users
+		.get_university(&class.university_id)
+		.await?
+		.internal_server_error("Class exists but has no university")?;
 
-	if generic_user.is_student() {
-		let student = generic_user.to_student()?;
-		Ok(Json(GetInfoResponse {
-			success: true,
-			first_name: Some(student.first_name.clone()),
-			last_name: Some(student.last_name.clone()),
-			email: Some(student.mail.clone()),
-			university: Some(student.get_university().await?.name),
-			class_name: Some(
-				student
-					.get_class()
-					.await?
-					.internal_server_error("Student exists but has no class")?
-					.name,
-			),
-		}))
-	} else {
-		Err(Status::Unauthorized)
-	}
+	Ok(Json(GetInfoResponse {
+		first_name: student.first_name,
+		last_name: student.last_name,
+		email: student.mail,
+		university: university.name,
+		class_name: class.name,
+	}))
 }
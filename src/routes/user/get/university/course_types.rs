@@ -1,6 +1,6 @@
-use rocket::{http::Status, serde::json::Json};
+use rocket::{State, serde::json::Json};
 
-use crate::models::auth::AuthGuard;
+use crate::{error_handling::ApiError, models::auth::UniversityUser, repositories::Repositories};
 
 use super::domain::GetCourseTypesResponse;
 
@@ -8,19 +8,15 @@ use super::domain::GetCourseTypesResponse;
 #[allow(clippy::needless_pass_by_value)]
 #[allow(clippy::missing_errors_doc)]
 pub async fn get_university_course_types(
-	auth: AuthGuard,
-) -> Result<Json<GetCourseTypesResponse>, Status> {
-	let generic_user = auth.get_generic_user().await?;
+	university_user: UniversityUser,
+	repositories: &State<Repositories>,
+) -> Result<Json<GetCourseTypesResponse>, ApiError> {
+	let course_types = repositories
+		.course_types
+		.get_by_university(&university_user.university.id)
+		.await?;
 
-	if generic_user.is_university() {
-		let university = generic_user.to_university()?;
-		let course_types = university.get_course_types().await?;
-
-		Ok(Json(GetCourseTypesResponse {
-			success: true,
-			course_type: course_types, // Bad but more useful for the front
-		}))
-	} else {
-		Err(Status::Unauthorized)
-	}
+	Ok(Json(GetCourseTypesResponse {
+		course_type: course_types, // Bad but more useful for the front
+	}))
 }
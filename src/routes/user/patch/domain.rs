@@ -0,0 +1,21 @@
+use serde::Deserialize;
+
+use crate::models::auth::AccountStatus;
+
+// Password
+
+#[derive(Debug, Deserialize)]
+pub struct ChangePasswordPayload {
+	pub current_password: String,
+	pub new_password: String,
+}
+
+// Status
+
+#[derive(Debug, Deserialize)]
+pub struct ChangeAccountStatusPayload {
+	pub user_id: String,
+	pub user_type: String,
+	pub status: AccountStatus,
+	pub reason: Option<String>,
+}
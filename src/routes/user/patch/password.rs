@@ -0,0 +1,85 @@
+use rocket::{State, response::status::NoContent, serde::json::Json};
+
+use crate::{
+	error_handling::{ApiError, FieldError, StatusOptionHandling},
+	models::{
+		audit::{AuditAction, AuditEvent, AuditOutcome},
+		auth::{ClientInfo, PasswordChangeGuard},
+	},
+	redis::{get_session, invalidate_other_sessions, update_session},
+	repositories::Repositories,
+	utils::crypto::{hash_password, is_password_valid, verify_password},
+};
+
+use super::domain::ChangePasswordPayload;
+
+#[patch("/user/password", data = "<change_password_payload>")]
+#[allow(clippy::needless_pass_by_value)]
+#[allow(clippy::missing_errors_doc)]
+pub async fn change_password(
+	auth: PasswordChangeGuard,
+	change_password_payload: Json<ChangePasswordPayload>,
+	client: ClientInfo,
+	repositories: &State<Repositories>,
+) -> Result<NoContent, ApiError> {
+	let auth = auth.0;
+	auth.forbid_impersonation()?;
+	let payload = change_password_payload.into_inner();
+	let user_id = auth.get_user_id().await?;
+	let audit = |outcome| {
+		AuditEvent::builder(AuditAction::PasswordChange, outcome)
+			.actor(&user_id, auth.user_type)
+			.target(&user_id)
+			.ip(client.ip)
+	};
+
+	let refused = if !is_password_valid(&payload.new_password) {
+		Some(FieldError::new(
+			"new_password",
+			"Does not follow the password policy",
+		))
+	} else if payload.new_password == payload.current_password {
+		Some(FieldError::new(
+			"new_password",
+			"Must differ from the current password",
+		))
+	} else {
+		let password_hash = repositories
+			.accounts
+			.get_password_hash(auth.user_type, &user_id)
+			.await?
+			.internal_server_error("Account not found")?;
+		(!verify_password(&payload.current_password, &password_hash)?)
+			.then(|| FieldError::new("current_password", "Wrong password"))
+	};
+	if let Some(refused) = refused {
+		audit(AuditOutcome::Failure)
+			.record(repositories.audit.as_ref())
+			.await?;
+		return Err(ApiError::Validation(vec![refused]));
+	}
+
+	repositories
+		.accounts
+		.update_password(
+			auth.user_type,
+			&user_id,
+			&hash_password(&payload.new_password)?,
+		)
+		.await?;
+	invalidate_other_sessions(&user_id, &auth.session_id).await?;
+
+	let mut session = get_session(&auth.session_id)
+		.await?
+		.internal_server_error("Session expired")?;
+	if session.restricted {
+		session.restricted = false;
+		update_session(&auth.session_id, &session).await?;
+	}
+
+	audit(AuditOutcome::Success)
+		.record(repositories.audit.as_ref())
+		.await?;
+
+	Ok(NoContent)
+}
@@ -0,0 +1,65 @@
+use std::str::FromStr;
+
+use rocket::{State, response::status::NoContent, serde::json::Json};
+
+use crate::{
+	error_handling::ApiError,
+	models::{
+		audit::{AuditAction, AuditEvent, AuditOutcome},
+		auth::{AccountStatus, AdminUser, ClientInfo, UserType},
+	},
+	redis::invalidate_user_sessions,
+	repositories::Repositories,
+};
+
+use super::domain::ChangeAccountStatusPayload;
+
+/// Suspends, disables or reactivates an account, the sessions of an inactive account are revoked.
+#[patch("/user/status", data = "<change_account_status_payload>")]
+#[allow(clippy::needless_pass_by_value)]
+#[allow(clippy::missing_errors_doc)]
+pub async fn change_account_status(
+	admin_user: AdminUser,
+	change_account_status_payload: Json<ChangeAccountStatusPayload>,
+	client: ClientInfo,
+	repositories: &State<Repositories>,
+) -> Result<NoContent, ApiError> {
+	let payload = change_account_status_payload.into_inner();
+	let user_type = UserType::from_str(&payload.user_type)?;
+	let audit = AuditEvent::builder(AuditAction::StatusChange, AuditOutcome::Failure)
+		.actor(&admin_user.admin.id, UserType::Admin)
+		.target(&payload.user_id)
+		.ip(client.ip);
+
+	// An admin locking themself out would need a database access to come back
+	if user_type == UserType::Admin && payload.user_id == admin_user.admin.id {
+		audit.record(repositories.audit.as_ref()).await?;
+		return Err(ApiError::Forbidden(
+			"Admins can't change their own status".to_string(),
+		));
+	}
+
+	let reason = match payload.status {
+		AccountStatus::Active => None,
+		_ => payload.reason.as_deref(),
+	};
+	if !repositories
+		.accounts
+		.set_status(user_type, &payload.user_id, payload.status, reason)
+		.await?
+	{
+		audit.record(repositories.audit.as_ref()).await?;
+		return Err(ApiError::NotFound("No account with this id".to_string()));
+	}
+
+	if payload.status != AccountStatus::Active {
+		invalidate_user_sessions(&payload.user_id).await?;
+	}
+
+	audit
+		.outcome(AuditOutcome::Success)
+		.record(repositories.audit.as_ref())
+		.await?;
+
+	Ok(NoContent)
+}
@@ -0,0 +1,70 @@
+use rocket::http::{Method, Status};
+use serde_json::json;
+
+use super::support::{TestApi, run};
+
+#[test]
+fn api_key_reads_the_internships_of_its_scope() {
+	run(async {
+		let api = TestApi::new().await;
+		let (_, jwt) = api.company().await;
+
+		let created = api
+			.post(
+				"/create/api_key",
+				Some(&jwt),
+				json!({ "name": "ERP", "scopes": ["internships:read"] }),
+			)
+			.await;
+		assert_eq!(created.status, Status::Ok, "{}", created.body);
+		let key = created.body["key"].as_str().expect("No API key");
+
+		let internships = api
+			.request_with_api_key(Method::Post, "/courses/internships", key, Some(json!({})))
+			.await;
+		assert_eq!(internships.status, Status::Ok, "{}", internships.body);
+		assert_eq!(internships.body["internships"], json!([]));
+
+		let listed = api.get("/user/company/api_keys", &jwt).await;
+		assert_eq!(listed.body["api_keys"][0]["name"], "ERP");
+		assert!(listed.body["api_keys"][0]["last_used_at"].is_string());
+	});
+}
+
+#[test]
+fn api_key_is_refused_outside_its_scope() {
+	run(async {
+		let api = TestApi::new().await;
+		let (_, jwt) = api.company().await;
+
+		let created = api
+			.post(
+				"/create/api_key",
+				Some(&jwt),
+				json!({ "name": "Publisher", "scopes": ["internships:write"] }),
+			)
+			.await;
+		let key = created.body["key"].as_str().expect("No API key");
+
+		let internships = api
+			.request_with_api_key(Method::Post, "/courses/internships", key, Some(json!({})))
+			.await;
+		assert_eq!(
+			internships.status,
+			Status::Forbidden,
+			"{}",
+			internships.body
+		);
+
+		// Keys can't mint other keys
+		let minted = api
+			.request_with_api_key(
+				Method::Post,
+				"/create/api_key",
+				key,
+				Some(json!({ "name": "Other", "scopes": ["internships:read"] })),
+			)
+			.await;
+		assert_eq!(minted.status, Status::Forbidden, "{}", minted.body);
+	});
+}
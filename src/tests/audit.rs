@@ -0,0 +1,134 @@
+use rocket::http::{ContentType, Header, Method, Status};
+use serde_json::json;
+
+use crate::models::auth::UserType;
+
+use super::support::{TestApi, run};
+
+#[test]
+fn actions_under_impersonation_name_the_admin() {
+	run(async {
+		let api = TestApi::new().await;
+		let (admin, admin_jwt) = api.admin().await;
+		let (university, _) = api.university().await;
+
+		let impersonated = api
+			.post(
+				"/auth/impersonate",
+				Some(&admin_jwt),
+				json!({
+					"user_id": university.id,
+					"user_type": UserType::University.to_string(),
+				}),
+			)
+			.await;
+		assert_eq!(impersonated.status, Status::Ok, "{}", impersonated.body);
+		let jwt = impersonated.body["jwt"].as_str().expect("No access token");
+
+		let created = api
+			.post(
+				"/create/class",
+				Some(jwt),
+				json!({
+					"name": "BUT Informatique",
+					"course_type": "info",
+					"date_internship_start": "2027-04-01",
+					"date_internship_end": "2027-08-31",
+					"maximum_internship_length": 16,
+					"minimum_internship_length": 8,
+				}),
+			)
+			.await;
+		assert_eq!(created.status, Status::NoContent, "{}", created.body);
+
+		let events = api
+			.get(
+				&format!("/audit/events?impersonator_id={}", admin.id),
+				&admin_jwt,
+			)
+			.await;
+		assert_eq!(events.status, Status::Ok, "{}", events.body);
+		let events = events.body["events"].as_array().expect("No events");
+		assert!(events.iter().any(|event| {
+			event["actor_id"] == university.id.as_str()
+				&& event["action"] == "create"
+				&& event["impersonator_id"] == admin.id.as_str()
+		}));
+	});
+}
+
+#[test]
+fn impersonated_session_cannot_revoke_sessions() {
+	run(async {
+		let api = TestApi::new().await;
+		let (_, admin_jwt) = api.admin().await;
+		let (university, university_jwt) = api.university().await;
+
+		let impersonated = api
+			.post(
+				"/auth/impersonate",
+				Some(&admin_jwt),
+				json!({
+					"user_id": university.id,
+					"user_type": UserType::University.to_string(),
+				}),
+			)
+			.await;
+		let jwt = impersonated.body["jwt"].as_str().expect("No access token");
+
+		let sessions = api.get("/auth/sessions", jwt).await;
+		let sessions = sessions.body["sessions"].as_array().expect("No sessions");
+		let own_session = sessions
+			.iter()
+			.find(|session| session["impersonated"] == false)
+			.and_then(|session| session["id"].as_str())
+			.expect("No session of the university");
+
+		for uri in [
+			format!("/auth/sessions/{own_session}"),
+			"/auth/sessions".to_string(),
+		] {
+			let revoked = api.request(Method::Delete, &uri, Some(jwt), None).await;
+			assert_eq!(revoked.status, Status::Forbidden, "{uri}: {}", revoked.body);
+		}
+
+		let kept = api.get("/auth/sessions", &university_jwt).await;
+		assert_eq!(kept.status, Status::Ok, "{}", kept.body);
+	});
+}
+
+#[test]
+fn client_ip_is_not_taken_from_headers() {
+	run(async {
+		let api = TestApi::new().await;
+		let (_, admin_jwt) = api.admin().await;
+
+		let response = api
+			.client
+			.post("/auth/login")
+			.remote("192.0.2.10:41000".parse().expect("Valid address"))
+			.header(Header::new("X-Real-IP", "203.0.113.7"))
+			.header(ContentType::JSON)
+			.body(
+				json!({
+					"login": "nobody",
+					"password": "Wrong-password1",
+					"remember_me": false,
+					"user_type": UserType::Company.to_string(),
+				})
+				.to_string(),
+			)
+			.dispatch()
+			.await;
+		assert_eq!(response.status(), Status::Unauthorized);
+
+		let events = api
+			.get("/audit/events?action=login&target_id=nobody", &admin_jwt)
+			.await;
+		assert_eq!(
+			events.body["events"][0]["ip"], "192.0.2.10",
+			"{}",
+			events.body
+		);
+	});
+}
@@ -0,0 +1,384 @@
+use rocket::http::{ContentType, Header, Method, Status};
+use serde_json::{Value, json};
+
+use crate::models::auth::UserType;
+
+use super::support::{TestApi, run};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Caller {
+	Anonymous,
+	Admin,
+	University,
+	Company,
+	Student,
+}
+
+const CALLERS: [Caller; 5] = [
+	Caller::Anonymous,
+	Caller::Admin,
+	Caller::University,
+	Caller::Company,
+	Caller::Student,
+];
+const AUTHENTICATED: &[Caller] = &[
+	Caller::Admin,
+	Caller::University,
+	Caller::Company,
+	Caller::Student,
+];
+
+/// A route, the callers it serves with `success` and refuses with `403 Forbidden` for the others,
+/// anonymous requests get `401 Unauthorized`.
+struct Case {
+	method: Method,
+	uri: String,
+	body: Option<Value>,
+	allowed: &'static [Caller],
+	success: Status,
+}
+
+impl Case {
+	fn new(method: Method, uri: &str, allowed: &'static [Caller], success: Status) -> Self {
+		Self {
+			method,
+			uri: uri.to_string(),
+			body: None,
+			allowed,
+			success,
+		}
+	}
+
+	fn body(mut self, body: Value) -> Self {
+		self.body = Some(body);
+		self
+	}
+}
+
+fn class_payload() -> Value {
+	json!({
+		"name": "BUT Informatique",
+		"course_type": "info",
+		"date_internship_start": "2027-04-01",
+		"date_internship_end": "2027-08-31",
+		"maximum_internship_length": 16,
+		"minimum_internship_length": 8,
+	})
+}
+
+#[test]
+fn routes_serve_their_roles_only() {
+	run(async {
+		let api = TestApi::new().await;
+		let (_, admin_jwt) = api.admin().await;
+		let (university, university_jwt) = api.university().await;
+		let class = api.class(&university.id).await;
+		let (company, company_jwt) = api.company().await;
+		let internship = api.internship(&company.id).await;
+		let (_, student_jwt) = api.student(&class.id).await;
+		let (suspended_company, _) = api.company().await;
+		let (deleted_company, _) = api.company().await;
+
+		// Each case succeeds at most once, the refused callers never reach the handler
+		let cases = [
+			Case::new(Method::Get, "/user/user_type", AUTHENTICATED, Status::Ok),
+			Case::new(Method::Get, "/auth/sessions", AUTHENTICATED, Status::Ok),
+			Case::new(
+				Method::Post,
+				"/courses/internships",
+				&[Caller::University, Caller::Company, Caller::Student],
+				Status::Ok,
+			)
+			.body(json!({ "course_types": ["info"] })),
+			Case::new(
+				Method::Get,
+				"/user/universities",
+				&[Caller::Admin],
+				Status::Ok,
+			),
+			Case::new(Method::Get, "/user/companies", &[Caller::Admin], Status::Ok),
+			Case::new(Method::Get, "/audit/events", &[Caller::Admin], Status::Ok),
+			Case::new(
+				Method::Post,
+				"/create/company",
+				&[Caller::Admin],
+				Status::Ok,
+			)
+			.body(json!({
+				"login": "matrix-company",
+				"mail": "matrix-company@mosifra.test",
+				"name": "Matrix",
+			})),
+			Case::new(
+				Method::Post,
+				"/create/university",
+				&[Caller::Admin],
+				Status::Ok,
+			)
+			.body(json!({
+				"login": "matrix-university",
+				"mail": "matrix-university@mosifra.test",
+				"name": "Matrix",
+			})),
+			Case::new(
+				Method::Post,
+				"/auth/impersonate",
+				&[Caller::Admin],
+				Status::Ok,
+			)
+			.body(json!({
+				"user_id": university.id,
+				"user_type": UserType::University.to_string(),
+			})),
+			Case::new(
+				Method::Patch,
+				"/user/status",
+				&[Caller::Admin],
+				Status::NoContent,
+			)
+			.body(json!({
+				"user_id": suspended_company.id,
+				"user_type": UserType::Company.to_string(),
+				"status": "suspended",
+				"reason": null,
+			})),
+			Case::new(
+				Method::Delete,
+				"/user/company",
+				&[Caller::Admin],
+				Status::NoContent,
+			)
+			.body(json!({ "id": deleted_company.id })),
+			Case::new(
+				Method::Get,
+				"/courses/classes",
+				&[Caller::University],
+				Status::Ok,
+			),
+			Case::new(
+				Method::Get,
+				"/user/university/course_types",
+				&[Caller::University],
+				Status::Ok,
+			),
+			Case::new(
+				Method::Post,
+				"/courses/class/students",
+				&[Caller::University],
+				Status::Ok,
+			)
+			.body(json!({ "class_id": class.id })),
+			Case::new(
+				Method::Post,
+				"/create/class",
+				&[Caller::University],
+				Status::NoContent,
+			)
+			.body(class_payload()),
+			Case::new(
+				Method::Get,
+				"/user/company/api_keys",
+				&[Caller::Company],
+				Status::Ok,
+			),
+			Case::new(
+				Method::Post,
+				"/create/api_key",
+				&[Caller::Company],
+				Status::Ok,
+			)
+			.body(json!({ "name": "ERP", "scopes": ["internships:read"] })),
+			Case::new(
+				Method::Post,
+				"/create/internship",
+				&[Caller::Company],
+				Status::NoContent,
+			)
+			.body(json!({
+				"course_type": "info",
+				"start_date": "2027-04-01",
+				"end_date": "2027-07-31",
+				"min_internship_length": 8,
+				"max_internship_length": 16,
+				"title": "Développeur Rust",
+				"description": "API de gestion des stages",
+				"place": "Lille",
+			})),
+			Case::new(
+				Method::Delete,
+				"/courses/internship",
+				&[Caller::Company],
+				Status::NoContent,
+			)
+			.body(json!({ "internship_id": internship.id })),
+			Case::new(
+				Method::Get,
+				"/user/student/info",
+				&[Caller::Student],
+				Status::Ok,
+			),
+			Case::new(
+				Method::Get,
+				"/user/student/course_type",
+				&[Caller::Student],
+				Status::Ok,
+			),
+		];
+
+		for case in cases {
+			for caller in CALLERS {
+				let jwt = match caller {
+					Caller::Anonymous => None,
+					Caller::Admin => Some(admin_jwt.as_str()),
+					Caller::University => Some(university_jwt.as_str()),
+					Caller::Company => Some(company_jwt.as_str()),
+					Caller::Student => Some(student_jwt.as_str()),
+				};
+				let expected = if caller == Caller::Anonymous {
+					Status::Unauthorized
+				} else if case.allowed.contains(&caller) {
+					case.success
+				} else {
+					Status::Forbidden
+				};
+
+				let response = api
+					.request(case.method, &case.uri, jwt, case.body.clone())
+					.await;
+				assert_eq!(
+					response.status, expected,
+					"{} {} as {caller:?}: {}",
+					case.method, case.uri, response.body
+				);
+			}
+		}
+	});
+}
+
+/// Multipart body of `/create/students`.
+fn students_form(class_id: &str) -> (ContentType, String) {
+	let boundary = "mosifra-boundary";
+	let body = format!(
+		"--{boundary}\r\n\
+		Content-Disposition: form-data; name=\"class\"\r\n\r\n\
+		{class_id}\r\n\
+		--{boundary}\r\n\
+		Content-Disposition: form-data; name=\"csv\"; filename=\"students.csv\"\r\n\
+		Content-Type: text/csv\r\n\r\n\
+		first_name,last_name,mail\r\n\
+		Camille,Martin,camille.martin@mosifra.test\r\n\
+		--{boundary}--\r\n"
+	);
+
+	(
+		ContentType::new("multipart", "form-data").with_params(("boundary", boundary)),
+		body,
+	)
+}
+
+#[test]
+fn universities_only_reach_their_own_classes() {
+	run(async {
+		let api = TestApi::new().await;
+		let (university, owner_jwt) = api.university().await;
+		let (_, other_jwt) = api.university().await;
+		let class = api.class(&university.id).await;
+
+		for (jwt, expected) in [
+			(&other_jwt, Status::Forbidden),
+			(&owner_jwt, Status::NoContent),
+		] {
+			let (content_type, body) = students_form(&class.id);
+			let response = api
+				.client
+				.post("/create/students")
+				.header(content_type)
+				.header(Header::new("Authorization", format!("Bearer {jwt}")))
+				.body(body)
+				.dispatch()
+				.await;
+			assert_eq!(response.status(), expected);
+		}
+
+		let students = api
+			.post(
+				"/courses/class/students",
+				Some(&other_jwt),
+				json!({ "class_id": class.id }),
+			)
+			.await;
+		assert_eq!(students.status, Status::Forbidden);
+
+		let students = api
+			.post(
+				"/courses/class/students",
+				Some(&owner_jwt),
+				json!({ "class_id": class.id }),
+			)
+			.await;
+		assert_eq!(students.body["students"][0]["first_name"], "Camille");
+
+		for (jwt, expected) in [
+			(&other_jwt, Status::Forbidden),
+			(&owner_jwt, Status::NoContent),
+		] {
+			let deleted = api
+				.request(
+					Method::Delete,
+					"/courses/class",
+					Some(jwt),
+					Some(json!({ "class_id": class.id })),
+				)
+				.await;
+			assert_eq!(deleted.status, expected, "{}", deleted.body);
+		}
+	});
+}
+
+#[test]
+fn companies_only_delete_their_own_internships() {
+	run(async {
+		let api = TestApi::new().await;
+		let (company, owner_jwt) = api.company().await;
+		let (_, other_jwt) = api.company().await;
+		let internship = api.internship(&company.id).await;
+
+		let created = api
+			.post(
+				"/create/api_key",
+				Some(&owner_jwt),
+				json!({ "name": "ERP", "scopes": ["internships:read"] }),
+			)
+			.await;
+		let read_key = created.body["key"].as_str().expect("No API key");
+		let deleted = api
+			.request_with_api_key(
+				Method::Delete,
+				"/courses/internship",
+				read_key,
+				Some(json!({ "internship_id": internship.id })),
+			)
+			.await;
+		assert_eq!(deleted.status, Status::Forbidden, "{}", deleted.body);
+
+		for (jwt, expected) in [
+			(&other_jwt, Status::Forbidden),
+			(&owner_jwt, Status::NoContent),
+		] {
+			let deleted = api
+				.request(
+					Method::Delete,
+					"/courses/internship",
+					Some(jwt),
+					Some(json!({ "internship_id": internship.id })),
+				)
+				.await;
+			assert_eq!(deleted.status, expected, "{}", deleted.body);
+		}
+
+		let internships = api
+			.post("/courses/internships", Some(&owner_jwt), json!({}))
+			.await;
+		assert_eq!(internships.body["internships"], json!([]));
+	});
+}
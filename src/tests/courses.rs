@@ -0,0 +1,101 @@
+use rocket::http::{Method, Status};
+use serde_json::json;
+
+use super::support::{TestApi, run};
+
+#[test]
+fn university_creates_lists_and_deletes_its_classes() {
+	run(async {
+		let api = TestApi::new().await;
+		let (_, jwt) = api.university().await;
+
+		let created = api
+			.post(
+				"/create/class",
+				Some(&jwt),
+				json!({
+					"name": "BUT Informatique",
+					"course_type": "info",
+					"date_internship_start": "2027-04-01",
+					"date_internship_end": "2027-08-31",
+					"maximum_internship_length": 16,
+					"minimum_internship_length": 8,
+				}),
+			)
+			.await;
+		assert_eq!(created.status, Status::NoContent, "{}", created.body);
+
+		let classes = api.get("/courses/classes", &jwt).await;
+		assert_eq!(classes.status, Status::Ok, "{}", classes.body);
+		assert_eq!(classes.body["classes"][0]["name"], "BUT Informatique");
+		let class_id = classes.body["classes"][0]["id"].clone();
+
+		let course_types = api.get("/user/university/course_types", &jwt).await;
+		assert_eq!(course_types.body["course_type"], json!(["info"]));
+
+		let deleted = api
+			.request(
+				Method::Delete,
+				"/courses/class",
+				Some(&jwt),
+				Some(json!({ "class_id": class_id })),
+			)
+			.await;
+		assert_eq!(deleted.status, Status::NoContent, "{}", deleted.body);
+
+		let classes = api.get("/courses/classes", &jwt).await;
+		assert_eq!(classes.body["classes"], json!([]));
+	});
+}
+
+#[test]
+fn internships_are_listed_by_course_type() {
+	run(async {
+		let api = TestApi::new().await;
+		let (_, company_jwt) = api.company().await;
+		let (university, university_jwt) = api.university().await;
+		let class = api.class(&university.id).await;
+		let (_, student_jwt) = api.student(&class.id).await;
+
+		let created = api
+			.post(
+				"/create/internship",
+				Some(&company_jwt),
+				json!({
+					"course_type": "info",
+					"start_date": "2027-04-01",
+					"end_date": "2027-07-31",
+					"min_internship_length": 8,
+					"max_internship_length": 16,
+					"title": "Développeur Rust",
+					"description": "API de gestion des stages",
+					"place": "Lille",
+				}),
+			)
+			.await;
+		assert_eq!(created.status, Status::NoContent, "{}", created.body);
+
+		let course_type = api.get("/user/student/course_type", &student_jwt).await;
+		assert_eq!(course_type.body["course_type"], json!(["info"]));
+
+		for jwt in [&company_jwt, &university_jwt, &student_jwt] {
+			let internships = api
+				.post(
+					"/courses/internships",
+					Some(jwt),
+					json!({ "course_types": ["info"] }),
+				)
+				.await;
+			assert_eq!(internships.status, Status::Ok, "{}", internships.body);
+			assert_eq!(
+				internships.body["internships"][0]["title"],
+				"Développeur Rust"
+			);
+		}
+
+		let without_course_type = api
+			.post("/courses/internships", Some(&student_jwt), json!({}))
+			.await;
+		assert_eq!(without_course_type.status, Status::Forbidden);
+	});
+}
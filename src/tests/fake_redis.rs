@@ -0,0 +1,277 @@
+//! Redis server speaking just enough RESP for the commands of `crate::redis`, kept in memory.
+//!
+//! It runs on its own threads so that it outlives the runtime of any test.
+
+use std::{
+	collections::{HashMap, HashSet},
+	io::{BufRead, BufReader, Write},
+	net::{SocketAddr, TcpListener, TcpStream},
+	sync::{Arc, Mutex},
+	thread,
+	time::{Duration, Instant},
+};
+
+#[derive(Debug, Clone)]
+enum Value {
+	String(Vec<u8>),
+	Set(HashSet<Vec<u8>>),
+}
+
+#[derive(Debug)]
+struct Entry {
+	value: Value,
+	expires_at: Option<Instant>,
+}
+
+#[derive(Debug, Default)]
+struct Store {
+	entries: HashMap<Vec<u8>, Entry>,
+}
+
+enum Reply {
+	Status(&'static str),
+	Error(String),
+	Integer(i64),
+	Bulk(Option<Vec<u8>>),
+	Array(Vec<Vec<u8>>),
+}
+
+/// Starts the server on a free port of the loopback.
+pub fn start() -> SocketAddr {
+	let listener = TcpListener::bind("127.0.0.1:0").expect("Can't bind the fake redis");
+	let address = listener.local_addr().expect("Fake redis has no address");
+	let store = Arc::new(Mutex::new(Store::default()));
+
+	thread::spawn(move || {
+		for stream in listener.incoming().flatten() {
+			let store = store.clone();
+			thread::spawn(move || serve(stream, &store));
+		}
+	});
+
+	address
+}
+
+fn serve(stream: TcpStream, store: &Mutex<Store>) {
+	let Ok(mut writer) = stream.try_clone() else {
+		return;
+	};
+	let mut reader = BufReader::new(stream);
+
+	while let Some(args) = read_command(&mut reader) {
+		let reply = match store.lock() {
+			Ok(mut store) => store.execute(&args),
+			Err(_) => Reply::Error("ERR store poisoned".to_string()),
+		};
+		if writer.write_all(&encode(reply)).is_err() {
+			return;
+		}
+	}
+}
+
+fn read_line(reader: &mut impl BufRead) -> Option<String> {
+	let mut line = String::new();
+	if reader.read_line(&mut line).ok()? == 0 {
+		return None;
+	}
+
+	Some(line.trim_end().to_string())
+}
+
+/// Clients only send arrays of bulk strings.
+fn read_command(reader: &mut impl BufRead) -> Option<Vec<Vec<u8>>> {
+	let count: usize = read_line(reader)?.strip_prefix('*')?.parse().ok()?;
+
+	(0..count)
+		.map(|_| {
+			let length: usize = read_line(reader)?.strip_prefix('$')?.parse().ok()?;
+			let mut arg = vec![0; length + 2];
+			reader.read_exact(&mut arg).ok()?;
+			arg.truncate(length);
+			Some(arg)
+		})
+		.collect()
+}
+
+fn encode(reply: Reply) -> Vec<u8> {
+	fn bulk(value: &[u8]) -> Vec<u8> {
+		[format!("${}\r\n", value.len()).as_bytes(), value, b"\r\n"].concat()
+	}
+
+	match reply {
+		Reply::Status(status) => format!("+{status}\r\n").into_bytes(),
+		Reply::Error(error) => format!("-{error}\r\n").into_bytes(),
+		Reply::Integer(integer) => format!(":{integer}\r\n").into_bytes(),
+		Reply::Bulk(Some(value)) => bulk(&value),
+		Reply::Bulk(None) => b"$-1\r\n".to_vec(),
+		Reply::Array(values) => {
+			let mut encoded = format!("*{}\r\n", values.len()).into_bytes();
+			for value in values {
+				encoded.extend(bulk(&value));
+			}
+			encoded
+		}
+	}
+}
+
+fn text(arg: &[u8]) -> String {
+	String::from_utf8_lossy(arg).to_uppercase()
+}
+
+fn number(arg: &[u8]) -> Option<i64> {
+	std::str::from_utf8(arg).ok()?.parse().ok()
+}
+
+impl Store {
+	fn execute(&mut self, args: &[Vec<u8>]) -> Reply {
+		let Some((name, args)) = args.split_first() else {
+			return Reply::Error("ERR empty command".to_string());
+		};
+		let now = Instant::now();
+		self.entries
+			.retain(|_, entry| entry.expires_at.is_none_or(|expires_at| expires_at > now));
+
+		match (text(name).as_str(), args) {
+			("PING", _) => Reply::Status("PONG"),
+			("CLIENT" | "SELECT", _) => Reply::Status("OK"),
+			("GET", [key]) => Reply::Bulk(self.string(key)),
+			("GETDEL", [key]) => {
+				let value = self.string(key);
+				self.entries.remove(key);
+				Reply::Bulk(value)
+			}
+			("SET", [key, value, options @ ..]) => self.set(key, value, options),
+			("SETEX", [key, seconds, value]) => {
+				self.set(key, value, &[b"EX".to_vec(), seconds.clone()])
+			}
+			("DEL", keys) => Reply::Integer(
+				keys.iter()
+					.filter(|key| self.entries.remove(*key).is_some())
+					.count() as i64,
+			),
+			("EXISTS", keys) => Reply::Integer(
+				keys.iter()
+					.filter(|key| self.entries.contains_key(*key))
+					.count() as i64,
+			),
+			("EXPIRE", [key, seconds]) => match (self.entries.get_mut(key), number(seconds)) {
+				(Some(entry), Some(seconds)) => {
+					entry.expires_at = Some(now + Duration::from_secs(seconds.unsigned_abs()));
+					Reply::Integer(1)
+				}
+				_ => Reply::Integer(0),
+			},
+			("TTL", [key]) => Reply::Integer(match self.entries.get(key) {
+				None => -2,
+				Some(Entry {
+					expires_at: None, ..
+				}) => -1,
+				Some(Entry {
+					expires_at: Some(expires_at),
+					..
+				}) => expires_at.duration_since(now).as_secs().cast_signed(),
+			}),
+			("INCRBY", [key, increment]) => {
+				let current = self.string(key).and_then(|value| number(&value));
+				let value = current.unwrap_or(0) + number(increment).unwrap_or(0);
+				let expires_at = self.entries.get(key).and_then(|entry| entry.expires_at);
+				self.entries.insert(
+					key.clone(),
+					Entry {
+						value: Value::String(value.to_string().into_bytes()),
+						expires_at,
+					},
+				);
+				Reply::Integer(value)
+			}
+			("SADD", [key, members @ ..]) => {
+				let set = self.set_mut(key);
+				Reply::Integer(
+					members
+						.iter()
+						.filter(|member| set.insert((*member).clone()))
+						.count() as i64,
+				)
+			}
+			("SREM", [key, members @ ..]) => {
+				let set = self.set_mut(key);
+				Reply::Integer(members.iter().filter(|member| set.remove(*member)).count() as i64)
+			}
+			("SMEMBERS", [key]) => Reply::Array(match self.entries.get(key) {
+				Some(Entry {
+					value: Value::Set(set),
+					..
+				}) => set.iter().cloned().collect(),
+				_ => vec![],
+			}),
+			(name, _) => Reply::Error(format!("ERR unsupported command {name}")),
+		}
+	}
+
+	fn string(&self, key: &[u8]) -> Option<Vec<u8>> {
+		match self.entries.get(key) {
+			Some(Entry {
+				value: Value::String(value),
+				..
+			}) => Some(value.clone()),
+			_ => None,
+		}
+	}
+
+	fn set_mut(&mut self, key: &[u8]) -> &mut HashSet<Vec<u8>> {
+		let entry = self.entries.entry(key.to_vec()).or_insert(Entry {
+			value: Value::Set(HashSet::new()),
+			expires_at: None,
+		});
+		if !matches!(entry.value, Value::Set(_)) {
+			entry.value = Value::Set(HashSet::new());
+		}
+
+		match &mut entry.value {
+			Value::Set(set) => set,
+			Value::String(_) => unreachable!("Replaced by a set above"),
+		}
+	}
+
+	/// `SET` with the `EX`, `NX`, `XX`, `GET` and `KEEPTTL` options.
+	fn set(&mut self, key: &[u8], value: &[u8], options: &[Vec<u8>]) -> Reply {
+		let options: Vec<String> = options.iter().map(|option| text(option)).collect();
+		let previous = self.string(key);
+		let exists = self.entries.contains_key(key);
+		let get = options.iter().any(|option| option == "GET");
+
+		if (options.iter().any(|option| option == "XX") && !exists)
+			|| (options.iter().any(|option| option == "NX") && exists)
+		{
+			return if get {
+				Reply::Bulk(previous)
+			} else {
+				Reply::Bulk(None)
+			};
+		}
+
+		let expires_at = match options.iter().position(|option| option == "EX") {
+			Some(index) => options
+				.get(index + 1)
+				.and_then(|seconds| seconds.parse().ok())
+				.map(|seconds| Instant::now() + Duration::from_secs(seconds)),
+			None if options.iter().any(|option| option == "KEEPTTL") => {
+				self.entries.get(key).and_then(|entry| entry.expires_at)
+			}
+			None => None,
+		};
+		self.entries.insert(
+			key.to_vec(),
+			Entry {
+				value: Value::String(value.to_vec()),
+				expires_at,
+			},
+		);
+
+		if get {
+			Reply::Bulk(previous)
+		} else {
+			Reply::Status("OK")
+		}
+	}
+}
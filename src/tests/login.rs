@@ -0,0 +1,131 @@
+use rocket::http::{Method, Status};
+use serde_json::json;
+use uuid::Uuid;
+
+use crate::{
+	models::{auth::UserType, users::Company},
+	repositories::{AccountRepository, UserRepository},
+	utils::crypto::hash_password,
+};
+
+use super::support::{PASSWORD, TestApi, TestResponse, run};
+
+async fn log_in_with(api: &TestApi, login: &str, password: &str) -> TestResponse {
+	api.post(
+		"/auth/login",
+		None,
+		json!({
+			"login": login,
+			"password": password,
+			"remember_me": false,
+			"user_type": UserType::Company.to_string(),
+		}),
+	)
+	.await
+}
+
+#[test]
+fn wrong_passwords_lock_the_login() {
+	run(async {
+		let api = TestApi::new().await;
+		let (company, _) = api.company().await;
+
+		for _ in 0..4 {
+			let refused = log_in_with(&api, &company.login, "Wrong-password1").await;
+			assert_eq!(refused.status, Status::Unauthorized, "{}", refused.body);
+			assert_eq!(refused.body["code"], "invalid_credentials");
+		}
+
+		let locked = log_in_with(&api, &company.login, "Wrong-password1").await;
+		assert_eq!(locked.status, Status::TooManyRequests, "{}", locked.body);
+		assert_eq!(locked.body["code"], "locked");
+		assert!(locked.body["retry_after"].as_u64().is_some());
+
+		// The right password waits for the end of the lock too
+		let locked = log_in_with(&api, &company.login, PASSWORD).await;
+		assert_eq!(locked.status, Status::TooManyRequests, "{}", locked.body);
+	});
+}
+
+#[test]
+fn unverified_address_gets_no_code() {
+	run(async {
+		let api = TestApi::new().await;
+		let id = Uuid::new_v4().to_string();
+		let company = Company {
+			login: format!("company-{id}"),
+			mail: format!("company-{id}@mosifra.test"),
+			name: "Entreprise de test".to_string(),
+			id,
+			password: String::new(),
+			mail_verified: false,
+			internship_list: vec![],
+		};
+		api.store
+			.insert_company(&company, "")
+			.await
+			.expect("Can't insert the company");
+		let hash = hash_password(PASSWORD).expect("Can't hash the test password");
+		api.store
+			.update_password(UserType::Company, &company.id, &hash)
+			.await
+			.expect("Can't set the password");
+
+		let refused = log_in_with(&api, &company.login, PASSWORD).await;
+		assert_eq!(refused.status, Status::Forbidden, "{}", refused.body);
+		assert_eq!(refused.body["code"], "mail_not_verified");
+		assert!(api.last_mail_to(&company.mail).is_none());
+	});
+}
+
+#[test]
+fn restricted_session_can_log_out() {
+	run(async {
+		let api = TestApi::new().await;
+		let id = Uuid::new_v4().to_string();
+		let company = Company {
+			login: format!("company-{id}"),
+			mail: format!("company-{id}@mosifra.test"),
+			name: "Entreprise de test".to_string(),
+			id,
+			password: String::new(),
+			mail_verified: true,
+			internship_list: vec![],
+		};
+		// Still the password given at creation, the session is restricted
+		let hash = hash_password(PASSWORD).expect("Can't hash the test password");
+		api.store
+			.insert_company(&company, &hash)
+			.await
+			.expect("Can't insert the company");
+
+		let transaction_id = api.start_login(UserType::Company, &company.login).await;
+		let code = api.last_mail_to(&company.mail).expect("No 2FA mail sent");
+		let twofa = api
+			.finish_login(UserType::Company, &transaction_id, &code)
+			.await;
+		assert_eq!(twofa.body["must_change_password"], true, "{}", twofa.body);
+		let jwt = twofa.body["jwt"].as_str().expect("No access token");
+		let refresh_token = twofa.body["refresh_token"]
+			.as_str()
+			.expect("No refresh token");
+
+		let refused = api.get("/user/user_type", jwt).await;
+		assert_eq!(refused.status, Status::Forbidden, "{}", refused.body);
+		assert_eq!(refused.body["code"], "password_change_required");
+
+		let logout = api
+			.request(Method::Delete, "/auth/logout", Some(jwt), None)
+			.await;
+		assert_eq!(logout.status, Status::NoContent, "{}", logout.body);
+
+		let refreshed = api
+			.post(
+				"/auth/refresh",
+				None,
+				json!({ "refresh_token": refresh_token }),
+			)
+			.await;
+		assert_eq!(refreshed.status, Status::Unauthorized, "{}", refreshed.body);
+	});
+}
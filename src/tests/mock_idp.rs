@@ -0,0 +1,138 @@
+//! OpenID provider serving the discovery document, its key set and the token endpoint, the ID
+//! token handed out being chosen by the test.
+
+use std::{
+	io::{BufRead, BufReader, Read, Write},
+	net::{TcpListener, TcpStream},
+	sync::{Arc, Mutex},
+	thread,
+};
+
+use jsonwebtoken::{Algorithm, EncodingKey, Header, encode, get_current_timestamp};
+use serde_json::{Value, json};
+
+const SIGNING_KEY: &str = include_str!("fixtures/idp_key.pem");
+const KEY_ID: &str = "mock-idp";
+/// Modulus of `SIGNING_KEY`, base64url encoded.
+const KEY_MODULUS: &str = "qPxNsSavXf2Nf5Esl37PhFa3p3h8rCh12xbHGEHHGnzpBRYbj7nMjnop8qoRzlIGT2NfvHsFv3M7AxqA1nyxq0CdX_-buAr4u91p2Lg6fgFlijndLVV6h-ru1rP9WD5QCTYYbhYa-DT7JOPsIkzIMWMAn0YRhiPDeqpG7ZtmAhznNru7d_svIDZo__5Mrp2kaS7saWYTgQbna0me7WtecdEmW3ddMlT3M6GnJPX4Z1SU5nm3oEiW6sJBQ07e4BQu7HL_99cBbBrRX_qsGFA1KAIT5ne8mm9BrbOfobh4aoA1q9C961ifP3jMqeAoyxCanh79bD_h1dGUkA2ucUlrgQ";
+
+pub const CLIENT_ID: &str = "mosifra";
+
+pub struct MockIdp {
+	pub issuer: String,
+	id_token: Arc<Mutex<Option<String>>>,
+}
+
+impl MockIdp {
+	/// Starts the provider on a free port of the loopback.
+	pub fn start() -> Self {
+		let listener = TcpListener::bind("127.0.0.1:0").expect("Can't bind the mock provider");
+		let issuer = format!(
+			"http://{}",
+			listener.local_addr().expect("Mock provider has no address")
+		);
+		let id_token = Arc::new(Mutex::new(None));
+
+		let documents = Documents {
+			issuer: issuer.clone(),
+			id_token: id_token.clone(),
+		};
+		thread::spawn(move || {
+			for stream in listener.incoming().flatten() {
+				documents.serve(stream);
+			}
+		});
+
+		Self { issuer, id_token }
+	}
+
+	/// Signs the claims, completed by the issuer, audience and expiry, as the next ID token.
+	pub fn issue(&self, mut claims: Value) {
+		claims["iss"] = json!(self.issuer);
+		claims["aud"] = json!(CLIENT_ID);
+		claims["exp"] = json!(get_current_timestamp() + 300);
+
+		let mut header = Header::new(Algorithm::RS256);
+		header.kid = Some(KEY_ID.to_string());
+		let key = EncodingKey::from_rsa_pem(SIGNING_KEY.as_bytes()).expect("Invalid signing key");
+		let id_token = encode(&header, &claims, &key).expect("Can't sign the ID token");
+
+		*self.id_token.lock().expect("ID token poisoned") = Some(id_token);
+	}
+}
+
+struct Documents {
+	issuer: String,
+	id_token: Arc<Mutex<Option<String>>>,
+}
+
+impl Documents {
+	fn serve(&self, stream: TcpStream) {
+		let Ok(mut writer) = stream.try_clone() else {
+			return;
+		};
+		let mut reader = BufReader::new(stream);
+
+		let mut request_line = String::new();
+		if reader.read_line(&mut request_line).is_err() {
+			return;
+		}
+		let mut content_length = 0;
+		loop {
+			let mut header = String::new();
+			if reader.read_line(&mut header).is_err() || header.trim().is_empty() {
+				break;
+			}
+			if let Some((name, value)) = header.split_once(':')
+				&& name.eq_ignore_ascii_case("content-length")
+			{
+				content_length = value.trim().parse().unwrap_or(0);
+			}
+		}
+		let mut body = vec![0; content_length];
+		if reader.read_exact(&mut body).is_err() {
+			return;
+		}
+
+		let path = request_line.split_whitespace().nth(1).unwrap_or_default();
+		let (status, document) = match path {
+			"/.well-known/openid-configuration" => (
+				"200 OK",
+				json!({
+					"issuer": self.issuer,
+					"authorization_endpoint": format!("{}/authorize", self.issuer),
+					"token_endpoint": format!("{}/token", self.issuer),
+					"jwks_uri": format!("{}/jwks", self.issuer),
+				}),
+			),
+			"/jwks" => (
+				"200 OK",
+				json!({
+					"keys": [{
+						"kty": "RSA",
+						"use": "sig",
+						"alg": "RS256",
+						"kid": KEY_ID,
+						"n": KEY_MODULUS,
+						"e": "AQAB",
+					}],
+				}),
+			),
+			"/token" => match self.id_token.lock().expect("ID token poisoned").take() {
+				Some(id_token) => (
+					"200 OK",
+					json!({ "id_token": id_token, "token_type": "Bearer" }),
+				),
+				None => ("400 Bad Request", json!({ "error": "invalid_grant" })),
+			},
+			_ => ("404 Not Found", json!({})),
+		};
+
+		let document = document.to_string();
+		let _ = write!(
+			writer,
+			"HTTP/1.1 {status}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{document}",
+			document.len()
+		);
+	}
+}
@@ -0,0 +1,11 @@
+mod api_keys;
+mod audit;
+mod authorization;
+mod courses;
+mod fake_redis;
+mod login;
+mod mock_idp;
+mod oidc;
+mod support;
+mod twofa;
+mod users;
@@ -0,0 +1,98 @@
+use rocket::http::Status;
+use serde_json::{Value, json};
+use url::Url;
+
+use super::{
+	mock_idp::{CLIENT_ID, MockIdp},
+	support::{TestApi, run},
+};
+
+/// Starts a login and returns its `state` and `nonce`, as found in the authorization URL.
+async fn authorize(api: &TestApi, university_id: &str) -> (String, String) {
+	let authorized = api
+		.post(
+			"/auth/oidc/authorize",
+			None,
+			json!({ "university_id": university_id, "remember_me": false }),
+		)
+		.await;
+	assert_eq!(authorized.status, Status::Ok, "{}", authorized.body);
+
+	let url = Url::parse(
+		authorized.body["authorization_url"]
+			.as_str()
+			.expect("No authorization URL"),
+	)
+	.expect("Invalid authorization URL");
+	let param = |name: &str| {
+		url.query_pairs()
+			.find(|(key, _)| key == name)
+			.map(|(_, value)| value.into_owned())
+			.expect("Missing authorization parameter")
+	};
+
+	(param("state"), param("nonce"))
+}
+
+async fn callback(api: &TestApi, state: &str) -> (Status, Value) {
+	let response = api
+		.post(
+			"/auth/oidc/callback",
+			None,
+			json!({ "state": state, "code": "authorization-code", "use_cookie": false }),
+		)
+		.await;
+
+	(response.status, response.body)
+}
+
+#[test]
+fn provider_logs_in_verified_emails_only() {
+	run(async {
+		let api = TestApi::new().await;
+		let idp = MockIdp::start();
+		let (university, university_jwt) = api.university().await;
+		let class = api.class(&university.id).await;
+		let (student, _) = api.student(&class.id).await;
+
+		let registered = api
+			.post(
+				"/create/oidc_provider",
+				Some(&university_jwt),
+				json!({ "issuer": idp.issuer, "client_id": CLIENT_ID, "client_secret": null }),
+			)
+			.await;
+		assert_eq!(registered.status, Status::NoContent, "{}", registered.body);
+
+		let (state, nonce) = authorize(&api, &university.id).await;
+		idp.issue(json!({
+			"sub": "student-1",
+			"email": student.mail,
+			"email_verified": false,
+			"nonce": nonce,
+		}));
+		let (status, body) = callback(&api, &state).await;
+		assert_eq!(status, Status::Unauthorized, "{body}");
+		assert_eq!(body["code"], "oidc_login_failed");
+
+		let (state, nonce) = authorize(&api, &university.id).await;
+		idp.issue(json!({
+			"sub": "student-1",
+			"email": student.mail,
+			"email_verified": true,
+			"nonce": nonce,
+		}));
+		let (status, body) = callback(&api, &state).await;
+		assert_eq!(status, Status::Ok, "{body}");
+		assert_eq!(body["user_type"], "student");
+		let jwt = body["jwt"].as_str().expect("No access token");
+
+		let info = api.get("/user/student/info", jwt).await;
+		assert_eq!(info.status, Status::Ok, "{}", info.body);
+		assert_eq!(info.body["email"], student.mail.as_str());
+
+		// A state is only good for one callback
+		let (status, _) = callback(&api, &state).await;
+		assert_eq!(status, Status::Unauthorized);
+	});
+}
@@ -0,0 +1,400 @@
+//! API mounted on an `InMemoryRepository`, an in-memory mailer and the fake redis, with helpers to
+//! create the users and log them in.
+
+use std::{
+	future::Future,
+	sync::{Arc, LazyLock},
+};
+
+use rocket::{
+	figment::{
+		Figment,
+		providers::{Format, Toml},
+	},
+	http::{ContentType, Header, Method, Status},
+	local::asynchronous::Client,
+};
+use serde_json::{Value, json};
+use tokio::{runtime::Runtime, sync::OnceCell};
+use uuid::Uuid;
+
+use crate::{
+	build_rocket,
+	config::{AppConfig, install_config},
+	models::{
+		auth::{JwtKeyring, UserType},
+		courses::{Class, CourseType, Internship},
+		users::{Company, Student, University, admin::Admin},
+	},
+	postgres::create_pool,
+	redis::{create_connection_manager, install_connection_manager},
+	repositories::{
+		AccountRepository, ClassRepository, InMemoryRepository, InternshipRepository, Repositories,
+		UserRepository,
+	},
+	utils::{crypto::hash_password, mail::Mailer},
+};
+
+use super::fake_redis;
+
+pub const PASSWORD: &str = "Password1";
+
+/// The connection manager and the globals of the API live as long as this runtime, which runs
+/// every test.
+static RUNTIME: LazyLock<Runtime> =
+	LazyLock::new(|| Runtime::new().expect("Can't start the test runtime"));
+static GLOBALS: OnceCell<()> = OnceCell::const_new();
+
+/// Settings of the tests, the database is never reached by the in-memory repositories.
+fn test_config() -> AppConfig {
+	Figment::from(Toml::string(
+		r#"
+		rocket_secret = "hPRYyVRiMyxpw5sBB1XeCMN1kFsDCqKvBi2QJxBVHQk="
+		api_port = 8000
+		frontend_url = "http://localhost:3000"
+
+		[database]
+		url = "postgres://mosifra@127.0.0.1:1/mosifra"
+
+		[smtp]
+		host = "localhost"
+		from = "Mosifra <noreply@mosifra.test>"
+
+		[jwt]
+		secret = "test-secret"
+
+		[oidc]
+		allow_insecure_providers = true
+		"#,
+	))
+	.extract()
+	.expect("Invalid test configuration")
+}
+
+/// Runs a test on the shared runtime, once the globals are installed.
+pub fn run<F: Future>(test: F) -> F::Output {
+	RUNTIME.block_on(async {
+		GLOBALS
+			.get_or_init(|| async {
+				let mut config = test_config();
+				config.redis.url =
+					serde_json::from_value(json!(format!("redis://{}/", fake_redis::start())))
+						.expect("Invalid fake redis URL");
+
+				let redis = create_connection_manager(&config.redis)
+					.await
+					.expect("Can't connect to the fake redis");
+				install_connection_manager(redis);
+				install_config(config);
+			})
+			.await;
+
+		test.await
+	})
+}
+
+pub struct TestApi {
+	pub client: Client,
+	pub store: Arc<InMemoryRepository>,
+}
+
+/// Answer of the API, with its body parsed when it is JSON.
+pub struct TestResponse {
+	pub status: Status,
+	pub body: Value,
+}
+
+impl TestApi {
+	pub async fn new() -> Self {
+		let config = test_config();
+		let store = Arc::new(InMemoryRepository::default());
+		let keyring = JwtKeyring::load(&config.jwt).expect("Invalid test keyring");
+		let mailer = Mailer::in_memory(&config.smtp.from).expect("Invalid test sender");
+		let pool = create_pool(&config.database).expect("Invalid test pool");
+
+		let rocket = build_rocket(
+			&config,
+			keyring,
+			mailer,
+			pool,
+			Repositories::from_backend(&store),
+		);
+
+		Self {
+			client: Client::untracked(rocket)
+				.await
+				.expect("Invalid test rocket"),
+			store,
+		}
+	}
+
+	/// Sends a request with the session of `jwt` if any, and a JSON body if any.
+	pub async fn request(
+		&self,
+		method: Method,
+		uri: &str,
+		jwt: Option<&str>,
+		body: Option<Value>,
+	) -> TestResponse {
+		let header = jwt.map(|jwt| Header::new("Authorization", format!("Bearer {jwt}")));
+		self.send(method, uri, header, body).await
+	}
+
+	/// Sends a request authenticated by an API key.
+	pub async fn request_with_api_key(
+		&self,
+		method: Method,
+		uri: &str,
+		key: &str,
+		body: Option<Value>,
+	) -> TestResponse {
+		let header = Header::new("X-Api-Key", key.to_string());
+		self.send(method, uri, Some(header), body).await
+	}
+
+	async fn send(
+		&self,
+		method: Method,
+		uri: &str,
+		header: Option<Header<'static>>,
+		body: Option<Value>,
+	) -> TestResponse {
+		let mut request = self.client.req(method, uri.to_string());
+		if let Some(header) = header {
+			request.add_header(header);
+		}
+		if let Some(body) = body {
+			request.add_header(ContentType::JSON);
+			request.set_body(body.to_string());
+		}
+
+		let response = request.dispatch().await;
+		let status = response.status();
+		let body = response
+			.into_string()
+			.await
+			.and_then(|body| serde_json::from_str(&body).ok())
+			.unwrap_or(Value::Null);
+
+		TestResponse { status, body }
+	}
+
+	pub async fn get(&self, uri: &str, jwt: &str) -> TestResponse {
+		self.request(Method::Get, uri, Some(jwt), None).await
+	}
+
+	pub async fn post(&self, uri: &str, jwt: Option<&str>, body: Value) -> TestResponse {
+		self.request(Method::Post, uri, jwt, Some(body)).await
+	}
+
+	fn mailer(&self) -> &Mailer {
+		self.client
+			.rocket()
+			.state::<Mailer>()
+			.expect("The mailer is managed")
+	}
+
+	/// Body of the last mail sent to the address.
+	pub fn last_mail_to(&self, mail: &str) -> Option<String> {
+		self.mailer()
+			.sent_mails()
+			.into_iter()
+			.rev()
+			.find(|sent| sent.to == mail)
+			.map(|sent| sent.body)
+	}
+
+	/// First step of the login, returns the 2FA transaction.
+	pub async fn start_login(&self, user_type: UserType, login: &str) -> String {
+		let login = self
+			.post(
+				"/auth/login",
+				None,
+				json!({
+					"login": login,
+					"password": PASSWORD,
+					"remember_me": false,
+					"user_type": user_type.to_string(),
+				}),
+			)
+			.await;
+		assert_eq!(login.status, Status::Ok, "{}", login.body);
+
+		login.body["transaction_id"]
+			.as_str()
+			.expect("No 2FA transaction")
+			.to_string()
+	}
+
+	pub async fn finish_login(
+		&self,
+		user_type: UserType,
+		transaction_id: &str,
+		code: &str,
+	) -> TestResponse {
+		self.post(
+			"/auth/twofa",
+			None,
+			json!({
+				"code": code,
+				"transaction_id": transaction_id,
+				"user_type": user_type.to_string(),
+				"remember_me": false,
+			}),
+		)
+		.await
+	}
+
+	/// Goes through the password and the emailed 2FA code, returns the access token.
+	pub async fn log_in(&self, user_type: UserType, login: &str, mail: &str) -> String {
+		let transaction_id = self.start_login(user_type, login).await;
+		let code = self.last_mail_to(mail).expect("No 2FA mail sent");
+		let twofa = self.finish_login(user_type, &transaction_id, &code).await;
+		assert_eq!(twofa.status, Status::Ok, "{}", twofa.body);
+
+		twofa.body["jwt"]
+			.as_str()
+			.expect("No access token")
+			.to_string()
+	}
+
+	/// Users other than admins are created with a chosen password and a verified address.
+	async fn activate(&self, user_type: UserType, id: &str) {
+		let hash = hash_password(PASSWORD).expect("Can't hash the test password");
+		self.store
+			.update_password(user_type, id, &hash)
+			.await
+			.expect("Can't set the password");
+		self.store
+			.set_mail_verified(user_type, id)
+			.await
+			.expect("Can't verify the address");
+	}
+
+	pub async fn admin(&self) -> (Admin, String) {
+		let id = Uuid::new_v4().to_string();
+		let admin = Admin {
+			login: format!("admin-{id}"),
+			mail: format!("admin-{id}@mosifra.test"),
+			id,
+			password: String::new(),
+		};
+		let hash = hash_password(PASSWORD).expect("Can't hash the test password");
+		self.store
+			.insert_admin(&admin, &hash)
+			.expect("Can't insert the admin");
+
+		let jwt = self
+			.log_in(UserType::Admin, &admin.login, &admin.mail)
+			.await;
+		(admin, jwt)
+	}
+
+	pub async fn university(&self) -> (University, String) {
+		let id = Uuid::new_v4().to_string();
+		let university = University {
+			login: format!("university-{id}"),
+			mail: format!("university-{id}@mosifra.test"),
+			name: "Université de test".to_string(),
+			id,
+			password: String::new(),
+			mail_verified: false,
+			class_list: vec![],
+			intership_list: vec![],
+		};
+		self.store
+			.insert_university(&university, "")
+			.await
+			.expect("Can't insert the university");
+		self.activate(UserType::University, &university.id).await;
+
+		let jwt = self
+			.log_in(UserType::University, &university.login, &university.mail)
+			.await;
+		(university, jwt)
+	}
+
+	pub async fn company(&self) -> (Company, String) {
+		let id = Uuid::new_v4().to_string();
+		let company = Company {
+			login: format!("company-{id}"),
+			mail: format!("company-{id}@mosifra.test"),
+			name: "Entreprise de test".to_string(),
+			id,
+			password: String::new(),
+			mail_verified: false,
+			internship_list: vec![],
+		};
+		self.store
+			.insert_company(&company, "")
+			.await
+			.expect("Can't insert the company");
+		self.activate(UserType::Company, &company.id).await;
+
+		let jwt = self
+			.log_in(UserType::Company, &company.login, &company.mail)
+			.await;
+		(company, jwt)
+	}
+
+	pub async fn class(&self, university_id: &str) -> Class {
+		let class = Class {
+			id: Uuid::new_v4().to_string(),
+			name: "Promotion de test".to_string(),
+			course_type: CourseType::Info,
+			date_internship_start: "2027-04-01".parse().expect("Valid date"),
+			date_internship_end: "2027-08-31".parse().expect("Valid date"),
+			maximum_internship_length: 16,
+			minimum_internship_length: 8,
+			university_id: university_id.to_string(),
+		};
+		self.store
+			.insert(&class)
+			.await
+			.expect("Can't insert the class");
+
+		class
+	}
+
+	pub async fn internship(&self, company_id: &str) -> Internship {
+		let internship = Internship {
+			id: Uuid::new_v4().to_string(),
+			course_type: CourseType::Info,
+			date_start: "2027-04-01".parse().expect("Valid date"),
+			date_end: "2027-07-31".parse().expect("Valid date"),
+			min_internship_length: 8,
+			max_internship_length: 16,
+			title: "Développeur Rust".to_string(),
+			description: "API de gestion des stages".to_string(),
+			place: "Lille".to_string(),
+		};
+		self.store
+			.insert_for_company(&internship, company_id)
+			.await
+			.expect("Can't insert the internship");
+
+		internship
+	}
+
+	pub async fn student(&self, class_id: &str) -> (Student, String) {
+		let id = Uuid::new_v4().to_string();
+		let student = Student {
+			login: format!("student-{id}"),
+			mail: format!("student-{id}@mosifra.test"),
+			first_name: "Camille".to_string(),
+			last_name: "Martin".to_string(),
+			id,
+			password: String::new(),
+		};
+		self.store
+			.insert_student(&student, "", class_id)
+			.await
+			.expect("Can't insert the student");
+		self.activate(UserType::Student, &student.id).await;
+
+		let jwt = self
+			.log_in(UserType::Student, &student.login, &student.mail)
+			.await;
+		(student, jwt)
+	}
+}
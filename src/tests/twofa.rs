@@ -0,0 +1,93 @@
+use rocket::http::{Method, Status};
+use serde_json::json;
+
+use crate::{models::auth::UserType, utils::crypto::totp_code};
+
+use super::support::{TestApi, run};
+
+#[test]
+fn totp_code_is_accepted_once() {
+	run(async {
+		let api = TestApi::new().await;
+		let (company, jwt) = api.company().await;
+
+		let enrolled = api
+			.request(Method::Post, "/auth/totp/enroll", Some(&jwt), None)
+			.await;
+		assert_eq!(enrolled.status, Status::Ok, "{}", enrolled.body);
+		let secret = enrolled.body["secret"].as_str().expect("No TOTP secret");
+
+		let code = totp_code(secret, 0);
+		let confirmed = api
+			.post("/auth/totp/confirm", Some(&jwt), json!({ "code": code }))
+			.await;
+		assert_eq!(confirmed.status, Status::Ok, "{}", confirmed.body);
+		assert_eq!(
+			confirmed.body["recovery_codes"].as_array().map(Vec::len),
+			Some(10)
+		);
+
+		// The code which confirmed the enrollment can't open a session
+		let transaction_id = api.start_login(UserType::Company, &company.login).await;
+		let replayed = api
+			.finish_login(UserType::Company, &transaction_id, &code)
+			.await;
+		assert_eq!(replayed.status, Status::Unauthorized, "{}", replayed.body);
+		assert_eq!(replayed.body["code"], "invalid_twofa_code");
+
+		let next = api
+			.finish_login(UserType::Company, &transaction_id, &totp_code(secret, 1))
+			.await;
+		assert_eq!(next.status, Status::Ok, "{}", next.body);
+		assert!(next.body["jwt"].is_string());
+	});
+}
+
+#[test]
+fn wrong_codes_lock_the_account_across_transactions() {
+	run(async {
+		let api = TestApi::new().await;
+		let (company, _) = api.company().await;
+
+		// A right password opens a new transaction each time, the wrong codes add up anyway
+		for wrong_codes in [3, 2] {
+			let transaction_id = api.start_login(UserType::Company, &company.login).await;
+			for _ in 0..wrong_codes {
+				let refused = api
+					.finish_login(UserType::Company, &transaction_id, "000000")
+					.await;
+				assert_eq!(refused.status, Status::Unauthorized, "{}", refused.body);
+			}
+		}
+
+		let transaction_id = api.start_login(UserType::Company, &company.login).await;
+		let code = api.last_mail_to(&company.mail).expect("No 2FA mail sent");
+		let locked = api
+			.finish_login(UserType::Company, &transaction_id, &code)
+			.await;
+		assert_eq!(locked.status, Status::TooManyRequests, "{}", locked.body);
+		assert_eq!(locked.body["code"], "locked");
+		assert!(locked.body["retry_after"].as_u64().is_some());
+	});
+}
+
+#[test]
+fn finished_transaction_is_invalid() {
+	run(async {
+		let api = TestApi::new().await;
+		let (company, _) = api.company().await;
+
+		let transaction_id = api.start_login(UserType::Company, &company.login).await;
+		let code = api.last_mail_to(&company.mail).expect("No 2FA mail sent");
+		let finished = api
+			.finish_login(UserType::Company, &transaction_id, &code)
+			.await;
+		assert_eq!(finished.status, Status::Ok, "{}", finished.body);
+
+		let replayed = api
+			.finish_login(UserType::Company, &transaction_id, &code)
+			.await;
+		assert_eq!(replayed.status, Status::Unauthorized, "{}", replayed.body);
+		assert_eq!(replayed.body["code"], "invalid_twofa_transaction");
+	});
+}
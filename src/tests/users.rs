@@ -0,0 +1,130 @@
+use rocket::http::{Method, Status};
+use serde_json::json;
+
+use crate::{
+	models::auth::{AccountStatus, UserType},
+	repositories::AccountRepository,
+};
+
+use super::support::{PASSWORD, TestApi, run};
+
+#[test]
+fn admin_creates_a_company_and_lists_it() {
+	run(async {
+		let api = TestApi::new().await;
+		let (_, jwt) = api.admin().await;
+
+		let created = api
+			.post(
+				"/create/company",
+				Some(&jwt),
+				json!({
+					"login": "acme",
+					"mail": "contact@acme.test",
+					"name": "Acme",
+				}),
+			)
+			.await;
+		assert_eq!(created.status, Status::Ok, "{}", created.body);
+		assert!(created.body["password"].is_string());
+
+		let companies = api.get("/user/companies", &jwt).await;
+		assert_eq!(companies.status, Status::Ok, "{}", companies.body);
+		assert_eq!(companies.body["companies"][0]["login"], "acme");
+	});
+}
+
+#[test]
+fn university_login_is_taken_once() {
+	run(async {
+		let api = TestApi::new().await;
+		let (_, jwt) = api.admin().await;
+
+		for (mail, expected) in [
+			("contact@univ.test", Status::Ok),
+			("scolarite@univ.test", Status::Conflict),
+		] {
+			let created = api
+				.post(
+					"/create/university",
+					Some(&jwt),
+					json!({
+						"login": "univ",
+						"mail": mail,
+						"name": "Université",
+					}),
+				)
+				.await;
+			assert_eq!(created.status, expected, "{}", created.body);
+		}
+	});
+}
+
+#[test]
+fn suspended_account_loses_its_sessions() {
+	run(async {
+		let api = TestApi::new().await;
+		let (_, admin_jwt) = api.admin().await;
+		let (company, company_jwt) = api.company().await;
+
+		let suspended = api
+			.request(
+				Method::Patch,
+				"/user/status",
+				Some(&admin_jwt),
+				Some(json!({
+					"user_id": company.id,
+					"user_type": UserType::Company.to_string(),
+					"status": "suspended",
+					"reason": "Unpaid subscription",
+				})),
+			)
+			.await;
+		assert_eq!(suspended.status, Status::NoContent, "{}", suspended.body);
+
+		let refused = api.get("/user/company/api_keys", &company_jwt).await;
+		assert_eq!(refused.status, Status::Unauthorized, "{}", refused.body);
+	});
+}
+
+#[test]
+fn suspended_account_is_told_why() {
+	run(async {
+		let api = TestApi::new().await;
+		let (university, jwt) = api.university().await;
+
+		// Suspended without going through the API, so the session is still there
+		api.store
+			.set_status(
+				UserType::University,
+				&university.id,
+				AccountStatus::Suspended,
+				Some("Contract expired"),
+			)
+			.await
+			.expect("Can't suspend the account");
+
+		let refused = api.get("/courses/classes", &jwt).await;
+		assert_eq!(refused.status, Status::Forbidden, "{}", refused.body);
+		assert_eq!(refused.body["code"], "account_suspended");
+		assert_eq!(refused.body["account_status"], "suspended");
+		assert_eq!(refused.body["status_reason"], "Contract expired");
+
+		let login = api
+			.post(
+				"/auth/login",
+				None,
+				json!({
+					"login": university.login,
+					"password": PASSWORD,
+					"remember_me": false,
+					"user_type": UserType::University.to_string(),
+				}),
+			)
+			.await;
+		assert_eq!(login.status, Status::Forbidden, "{}", login.body);
+		assert_eq!(login.body["code"], "account_suspended");
+		assert_eq!(login.body["account_status"], "suspended");
+		assert_eq!(login.body["status_reason"], "Contract expired");
+	});
+}
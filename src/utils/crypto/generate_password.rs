@@ -1,12 +1,12 @@
 use passwords::PasswordGenerator;
 use rocket::http::Status;
 
-use crate::error_handling::StatusResultHandling;
+use crate::{config::password_policy, error_handling::StatusResultHandling};
 
 #[allow(clippy::missing_errors_doc)]
 pub fn generate_password() -> Result<String, Status> {
 	PasswordGenerator::new()
-		.length(8)
+		.length(password_policy().min_length)
 		.numbers(true)
 		.lowercase_letters(true)
 		.uppercase_letters(true)
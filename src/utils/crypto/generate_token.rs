@@ -0,0 +1,14 @@
+use std::fmt::Write;
+
+use rand::Rng;
+
+/// Generates a random 256 bits token encoded as hexadecimal.
+#[must_use]
+pub fn generate_token() -> String {
+	let bytes: [u8; 32] = rand::rng().random();
+
+	bytes.iter().fold(String::new(), |mut acc, byte| {
+		let _ = write!(acc, "{byte:02x}");
+		acc
+	})
+}
@@ -0,0 +1,16 @@
+use std::fmt::Write;
+
+use sha2::{Digest, Sha256};
+
+/// Hashes a random token (refresh token, reset token...) before storing it.
+///
+/// Tokens are already high entropy so a fast hash is enough, unlike passwords.
+#[must_use]
+pub fn hash_token(token: &str) -> String {
+	Sha256::digest(token.as_bytes())
+		.iter()
+		.fold(String::new(), |mut acc, byte| {
+			let _ = write!(acc, "{byte:02x}");
+			acc
+		})
+}
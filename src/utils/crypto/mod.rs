@@ -1,7 +1,17 @@
 mod generate_password;
+mod generate_token;
 mod hash_password;
+mod hash_token;
+mod password_policy;
+mod totp;
 mod verify_password;
 
 pub use generate_password::generate_password;
+pub use generate_token::generate_token;
 pub use hash_password::hash_password;
+pub use hash_token::hash_token;
+pub use password_policy::is_password_valid;
+#[cfg(test)]
+pub use totp::totp_code;
+pub use totp::{generate_totp_secret, totp_uri, verify_totp_code};
 pub use verify_password::verify_password;
@@ -0,0 +1,13 @@
+use crate::config::password_policy;
+
+/// Configured policy for passwords chosen by users, generated ones already follow it.
+#[must_use]
+pub fn is_password_valid(password: &str) -> bool {
+	let policy = password_policy();
+
+	password.chars().count() >= policy.min_length
+		&& (!policy.require_lowercase || password.chars().any(char::is_lowercase))
+		&& (!policy.require_uppercase || password.chars().any(char::is_uppercase))
+		&& (!policy.require_digit || password.chars().any(|c| c.is_ascii_digit()))
+		&& (!policy.require_symbol || password.chars().any(|c| c.is_ascii_punctuation()))
+}
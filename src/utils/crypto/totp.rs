@@ -0,0 +1,83 @@
+use base32::Alphabet;
+use hmac::{Hmac, Mac};
+use jsonwebtoken::get_current_timestamp;
+use rand::Rng;
+use rocket::http::{RawStr, Status};
+use sha1::Sha1;
+
+use crate::error_handling::{StatusOptionHandling, StatusResultHandling};
+
+type HmacSha1 = Hmac<Sha1>;
+
+const TOTP_ISSUER: &str = "Mosifra";
+const TOTP_STEP_SECONDS: u64 = 30;
+const TOTP_DIGITS: u32 = 6;
+// Number of steps accepted before and after the current one to tolerate clock drift
+const TOTP_WINDOW: u64 = 1;
+
+const BASE32: Alphabet = Alphabet::Rfc4648 { padding: false };
+
+/// Generates a 160 bits secret encoded in base32, as expected by authenticator apps.
+#[must_use]
+pub fn generate_totp_secret() -> String {
+	let bytes: [u8; 20] = rand::rng().random();
+	base32::encode(BASE32, &bytes)
+}
+
+#[must_use]
+pub fn totp_uri(secret: &str, account: &str) -> String {
+	let label = RawStr::new(&format!("{TOTP_ISSUER}:{account}"))
+		.percent_encode()
+		.to_string();
+
+	format!(
+		"otpauth://totp/{label}?secret={secret}&issuer={TOTP_ISSUER}&algorithm=SHA1&digits={TOTP_DIGITS}&period={TOTP_STEP_SECONDS}"
+	)
+}
+
+/// Time step matched by the code, if any.
+#[allow(clippy::missing_errors_doc)]
+pub fn verify_totp_code(secret: &str, code: &str) -> Result<Option<u64>, Status> {
+	if code.len() != TOTP_DIGITS as usize || !code.chars().all(|c| c.is_ascii_digit()) {
+		return Ok(None);
+	}
+
+	let secret = base32::decode(BASE32, secret).internal_server_error("Invalid TOTP secret")?;
+	let current_step = get_current_timestamp() / TOTP_STEP_SECONDS;
+
+	for step in current_step.saturating_sub(TOTP_WINDOW)..=current_step + TOTP_WINDOW {
+		if format!("{:0width$}", hotp(&secret, step)?, width = TOTP_DIGITS as usize) == code {
+			return Ok(Some(step));
+		}
+	}
+
+	Ok(None)
+}
+
+// RFC 4226 HOTP, TOTP is HOTP with the time step as counter
+fn hotp(secret: &[u8], counter: u64) -> Result<u32, Status> {
+	let mut mac =
+		HmacSha1::new_from_slice(secret).internal_server_error("Invalid TOTP secret length")?;
+	mac.update(&counter.to_be_bytes());
+	let hash = mac.finalize().into_bytes();
+
+	let offset = (hash.last().internal_server_error("Empty HMAC")? & 0x0f) as usize;
+	let binary = u32::from_be_bytes([
+		hash[offset] & 0x7f,
+		hash[offset + 1],
+		hash[offset + 2],
+		hash[offset + 3],
+	]);
+
+	Ok(binary % 10_u32.pow(TOTP_DIGITS))
+}
+
+/// Code accepted `offset` steps after the current one, as an authenticator app would show it.
+#[cfg(test)]
+pub fn totp_code(secret: &str, offset: u64) -> String {
+	let secret = base32::decode(BASE32, secret).expect("Invalid TOTP secret");
+	let step = get_current_timestamp() / TOTP_STEP_SECONDS + offset;
+	let code = hotp(&secret, step).expect("Invalid TOTP secret length");
+
+	format!("{code:0width$}", width = TOTP_DIGITS as usize)
+}
@@ -1,5 +1,11 @@
 mod send_2fa_mail;
+mod send_mail;
+mod send_mail_verification_mail;
+mod send_password_reset_mail;
 mod verify_mail;
 
 pub use send_2fa_mail::send_2fa_mail;
+pub use send_mail::{Mailer, SentMail};
+pub use send_mail_verification_mail::send_mail_verification_mail;
+pub use send_password_reset_mail::send_password_reset_mail;
 pub use verify_mail::verify_mail;
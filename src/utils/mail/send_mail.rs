@@ -0,0 +1,143 @@
+use std::{sync::Mutex, time::Instant};
+
+use lettre::{
+	AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor,
+	message::{Mailbox, header::ContentType},
+	transport::smtp::authentication::Credentials,
+};
+use rocket::http::Status;
+use tracing::{Instrument, debug, debug_span};
+
+use crate::{
+	config::SmtpConfig, error_handling::StatusResultHandling, logging::millis, metrics::record_mail,
+};
+
+/// Mail as handed to the transport.
+#[derive(Debug, Clone)]
+pub struct SentMail {
+	pub to: String,
+	pub subject: String,
+	pub body: String,
+}
+
+#[derive(Debug)]
+enum Transport {
+	Smtp(AsyncSmtpTransport<Tokio1Executor>),
+	/// Keeps the mails instead of sending them
+	InMemory(Mutex<Vec<SentMail>>),
+}
+
+/// Sends the mails of the API on the async runtime, built once and managed by Rocket.
+#[derive(Debug)]
+pub struct Mailer {
+	from: Mailbox,
+	host: String,
+	transport: Transport,
+}
+
+impl Mailer {
+	/// Builds the transport, a connection is opened for each mail.
+	pub fn smtp(smtp: &SmtpConfig) -> Result<Self, String> {
+		let mut builder = AsyncSmtpTransport::<Tokio1Executor>::relay(&smtp.host)
+			.map_err(|e| format!("Invalid SMTP_HOST: {e}"))?;
+		if let Some(port) = smtp.port {
+			builder = builder.port(port);
+		}
+		if let (Some(username), Some(password)) = (&smtp.username, &smtp.password) {
+			builder = builder.credentials(Credentials::new(
+				username.clone(),
+				password.expose().to_string(),
+			));
+		}
+
+		Ok(Self {
+			from: parse_from(&smtp.from)?,
+			host: smtp.host.clone(),
+			transport: Transport::Smtp(builder.build()),
+		})
+	}
+
+	/// Mailer whose mails are read back with `sent_mails`.
+	pub fn in_memory(from: &str) -> Result<Self, String> {
+		Ok(Self {
+			from: parse_from(from)?,
+			host: "in-memory".to_string(),
+			transport: Transport::InMemory(Mutex::new(vec![])),
+		})
+	}
+
+	/// Mails kept by an in-memory mailer, always empty for the relay.
+	pub fn sent_mails(&self) -> Vec<SentMail> {
+		match &self.transport {
+			Transport::Smtp(_) => vec![],
+			Transport::InMemory(mails) => {
+				mails.lock().map(|mails| mails.clone()).unwrap_or_default()
+			}
+		}
+	}
+
+	#[allow(clippy::missing_errors_doc)]
+	pub async fn send(&self, to: &str, subject: &str, body: String) -> Result<(), Status> {
+		// Neither the address nor the body, which holds the codes and links
+		let span = debug_span!("smtp", host = self.host, subject);
+
+		let email = Message::builder()
+			.from(self.from.clone())
+			.to(Mailbox::new(
+				None,
+				to.parse()
+					.internal_server_error("Error while parsing 'to' email")?,
+			))
+			.subject(subject)
+			.header(ContentType::TEXT_PLAIN)
+			.body(body.clone())
+			.internal_server_error("Error while building email")?;
+
+		let start = Instant::now();
+		let sent = match &self.transport {
+			Transport::Smtp(transport) => transport
+				.send(email)
+				.instrument(span.clone())
+				.await
+				.map(|_| ())
+				.internal_server_error("Error email failed to send"),
+			Transport::InMemory(mails) => {
+				mails
+					.lock()
+					.internal_server_error("In-memory mailer poisoned")?
+					.push(SentMail {
+						to: to.to_string(),
+						subject: subject.to_string(),
+						body,
+					});
+				Ok(())
+			}
+		};
+		record_mail(sent.is_ok());
+		sent?;
+		span.in_scope(|| debug!(duration_ms = millis(start.elapsed()), "Done"));
+
+		Ok(())
+	}
+
+	/// Readiness probe, opens a connection to the relay and checks it answers `NOOP`.
+	pub async fn check_connection(&self) -> Result<(), String> {
+		let connected = match &self.transport {
+			Transport::Smtp(transport) => transport
+				.test_connection()
+				.await
+				.map_err(|e| e.to_string())?,
+			Transport::InMemory(_) => true,
+		};
+
+		if connected {
+			Ok(())
+		} else {
+			Err("The relay did not answer NOOP".to_string())
+		}
+	}
+}
+
+fn parse_from(from: &str) -> Result<Mailbox, String> {
+	from.parse().map_err(|e| format!("Invalid SMTP_FROM: {e}"))
+}
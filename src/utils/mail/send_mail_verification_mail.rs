@@ -0,0 +1,30 @@
+use rocket::http::{RawStr, Status};
+
+use crate::config::{app_config, ttl};
+
+use super::Mailer;
+
+#[allow(clippy::missing_errors_doc)]
+pub async fn send_mail_verification_mail(
+	mailer: &Mailer,
+	to: &str,
+	token: &str,
+) -> Result<(), Status> {
+	let frontend_url = &app_config()?.frontend_url;
+	let link = format!(
+		"{}/verify-mail?token={}",
+		frontend_url.trim_end_matches('/'),
+		RawStr::new(token).percent_encode()
+	);
+
+	mailer
+		.send(
+			to,
+			"Mosifra - Vérification de l'adresse mail",
+			format!(
+				"Pour activer votre compte Mosifra, confirmez votre adresse mail en suivant ce lien (valable {} heures) :\n{link}\n\nSi vous n'attendiez pas ce message, ignorez-le.",
+				ttl().mail_verification_seconds / 3600
+			),
+		)
+		.await
+}
@@ -0,0 +1,30 @@
+use rocket::http::{RawStr, Status};
+
+use crate::config::{app_config, ttl};
+
+use super::Mailer;
+
+#[allow(clippy::missing_errors_doc)]
+pub async fn send_password_reset_mail(
+	mailer: &Mailer,
+	to: &str,
+	token: &str,
+) -> Result<(), Status> {
+	let frontend_url = &app_config()?.frontend_url;
+	let link = format!(
+		"{}/reset-password?token={}",
+		frontend_url.trim_end_matches('/'),
+		RawStr::new(token).percent_encode()
+	);
+
+	mailer
+		.send(
+			to,
+			"Mosifra - Réinitialisation du mot de passe",
+			format!(
+				"Pour choisir un nouveau mot de passe, suivez ce lien (valable {} minutes) :\n{link}\n\nSi vous n'êtes pas à l'origine de cette demande, ignorez ce message.",
+				ttl().password_reset_seconds / 60
+			),
+		)
+		.await
+}
@@ -3,7 +3,6 @@ use rocket::http::Status;
 
 use crate::error_handling::StatusResultHandling;
 
-#[must_use]
 #[allow(
 	clippy::missing_panics_doc,
 	clippy::result_unit_err,